@@ -0,0 +1,236 @@
+use std::borrow::Borrow;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::dotevery_editor::{DotEveryEditor, DotEveryEditorOperationIndex};
+use crate::program_module::{ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CursorPosition {
+    Module(Uuid),
+    Input(Uuid, usize),
+    Slot(Uuid, DotEveryEditorOperationIndex),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CursorKey {
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Escape,
+    PickUp,
+    Drop,
+    Delete,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CursorOperation<Type: 'static + Clone + PartialEq = ()> {
+    Move(Uuid, Uuid, DotEveryEditorOperationIndex, ProgramModule<Type>),
+    Remove(Uuid),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct CursorEntry {
+    position: CursorPosition,
+    owner: Uuid,
+    index: DotEveryEditorOperationIndex,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EditorCursor {
+    position: Option<CursorPosition>,
+    holding: Option<Uuid>,
+    editing: bool,
+}
+
+impl EditorCursor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn position(&self) -> Option<CursorPosition> {
+        self.position
+    }
+
+    pub fn holding(&self) -> Option<Uuid> {
+        self.holding
+    }
+
+    pub fn editing(&self) -> bool {
+        self.editing
+    }
+
+    pub fn set_position(&mut self, position: Option<CursorPosition>) {
+        self.position = position;
+        self.editing = false;
+    }
+
+    pub fn is_on_module(&self, id: Uuid) -> bool {
+        self.position == Some(CursorPosition::Module(id))
+    }
+
+    pub fn positions<T: 'static + Clone + PartialEq>(editor: &DotEveryEditor<T>) -> Vec<CursorPosition> {
+        Self::entries(editor).into_iter().map(|entry| entry.position).collect()
+    }
+
+    pub fn handle_key<T: 'static + Clone + PartialEq>(&mut self, editor: &DotEveryEditor<T>, key: CursorKey) -> Vec<CursorOperation<T>> {
+        if let Some(holding) = self.holding {
+            if editor.get_module(holding).is_err() {
+                self.holding = None;
+            }
+        }
+        let entries = Self::entries(editor);
+        let current = self.position.and_then(|position| entries.iter().position(|entry| entry.position == position));
+        if current.is_none() {
+            self.editing = false;
+        }
+        if self.editing {
+            if let CursorKey::Enter | CursorKey::Escape = key {
+                self.editing = false;
+            }
+            return Vec::new();
+        }
+        match key {
+            CursorKey::Up => {
+                self.position = match current {
+                    Some(i) => entries.get(i.saturating_sub(1)),
+                    None => entries.first(),
+                }.map(|entry| entry.position);
+                Vec::new()
+            }
+            CursorKey::Down => {
+                self.position = match current {
+                    Some(i) => entries.get(usize::min(i + 1, entries.len() - 1)),
+                    None => entries.first(),
+                }.map(|entry| entry.position);
+                Vec::new()
+            }
+            CursorKey::Left => {
+                if let Some(i) = current {
+                    let owner = match entries[i].position {
+                        CursorPosition::Module(_) => entries[i].owner,
+                        CursorPosition::Input(id, _) | CursorPosition::Slot(id, _) => id,
+                    };
+                    if !owner.is_nil() {
+                        self.position = Some(CursorPosition::Module(owner));
+                    }
+                }
+                Vec::new()
+            }
+            CursorKey::Right => {
+                if let Some(i) = current {
+                    if let (CursorPosition::Module(id), Some(next)) = (entries[i].position, entries.get(i + 1)) {
+                        if next.owner == id {
+                            self.position = Some(next.position);
+                        }
+                    }
+                }
+                Vec::new()
+            }
+            CursorKey::Enter => {
+                if let Some(CursorPosition::Input(_, _)) = self.position {
+                    self.editing = current.is_some();
+                }
+                Vec::new()
+            }
+            CursorKey::Escape => {
+                self.holding = None;
+                Vec::new()
+            }
+            CursorKey::PickUp => {
+                if let Some(CursorPosition::Module(id)) = self.position {
+                    if current.is_some() && editor.can_move(id).is_ok() {
+                        self.holding = Some(id);
+                    }
+                }
+                Vec::new()
+            }
+            CursorKey::Drop => {
+                let (holding, entry) = match (self.holding, current) {
+                    (Some(holding), Some(i)) => (holding, entries[i]),
+                    _ => return Vec::new(),
+                };
+                let (target, index) = match entry.position {
+                    CursorPosition::Module(_) => match entry.index {
+                        DotEveryEditorOperationIndex::Child(_) => (entry.owner, entry.index),
+                        _ => return Vec::new(),
+                    },
+                    CursorPosition::Slot(id, index) => (id, index),
+                    CursorPosition::Input(_, _) => return Vec::new(),
+                };
+                let module = match editor.get_module(holding) {
+                    Ok(module) => module,
+                    Err(_) => return Vec::new(),
+                };
+                if module.get_module(target).is_ok() {
+                    return Vec::new();
+                }
+                let module = module.deep_clone();
+                self.holding = None;
+                self.position = Some(CursorPosition::Module(module.id()));
+                vec![CursorOperation::Move(holding, target, index, module)]
+            }
+            CursorKey::Delete => {
+                if let Some(i) = current {
+                    if let CursorPosition::Module(id) = entries[i].position {
+                        if self.holding == Some(id) {
+                            self.holding = None;
+                        }
+                        let CursorEntry { owner, index, .. } = entries[i];
+                        self.position = match index {
+                            DotEveryEditorOperationIndex::Child(index) => entries[i + 1..]
+                                .iter()
+                                .find(|entry| entry.owner == owner)
+                                .map(|entry| match entry.position {
+                                    CursorPosition::Module(_) => entry.position,
+                                    _ => CursorPosition::Slot(owner, DotEveryEditorOperationIndex::Child(index)),
+                                }),
+                            _ => Some(CursorPosition::Slot(owner, index)),
+                        };
+                        return vec![CursorOperation::Remove(id)];
+                    }
+                }
+                Vec::new()
+            }
+        }
+    }
+
+    fn entries<T: 'static + Clone + PartialEq>(editor: &DotEveryEditor<T>) -> Vec<CursorEntry> {
+        let mut entries = Vec::new();
+        Self::list_entries(&mut entries, Uuid::nil(), &editor.list);
+        entries
+    }
+
+    fn list_entries<T: 'static + Clone + PartialEq>(entries: &mut Vec<CursorEntry>, owner: Uuid, list: &[impl Borrow<ProgramModule<T>>]) {
+        for (i, module) in list.iter().enumerate() {
+            Self::module_entries(entries, owner, DotEveryEditorOperationIndex::Child(i), module.borrow());
+        }
+        let index = DotEveryEditorOperationIndex::Child(list.len());
+        entries.push(CursorEntry { position: CursorPosition::Slot(owner, index), owner, index });
+    }
+
+    fn module_entries<T: 'static + Clone + PartialEq>(entries: &mut Vec<CursorEntry>, owner: Uuid, index: DotEveryEditorOperationIndex, module: &ProgramModule<T>) {
+        let id = module.id();
+        entries.push(CursorEntry { position: CursorPosition::Module(id), owner, index });
+        for (i, option) in module.options.iter().enumerate() {
+            let index = DotEveryEditorOperationIndex::OptionAbsolute(i);
+            match option {
+                ProgramModuleOption::StringSign(_) => {}
+                ProgramModuleOption::StringInput(_) => entries.push(CursorEntry { position: CursorPosition::Input(id, i), owner: id, index }),
+                ProgramModuleOption::ProgramModule(None) => entries.push(CursorEntry { position: CursorPosition::Slot(id, index), owner: id, index }),
+                ProgramModuleOption::ProgramModule(Some(module)) => Self::module_entries(entries, id, index, module),
+            }
+        }
+        match &module.child {
+            ProgramModuleChildItems::None => {}
+            ProgramModuleChildItems::BlockVertical(list) | ProgramModuleChildItems::BlockHorizontal(list) => Self::list_entries(entries, id, list),
+        }
+    }
+}
@@ -0,0 +1,336 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::program_module::{ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
+
+// use crate::program_module_list::ProgramModuleList;
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum DotEveryEditorErrorMessage {
+    IndexOutOfRange,
+    NotFound,
+    ErrorInGetModule(Box<DotEveryEditorErrorMessage>),
+    ErrorInAddModule(Box<DotEveryEditorErrorMessage>),
+    OptionDoesNotExpectProgramModule,
+    ChildDoesNotExpectProgramModule,
+    CanNotReplace,
+    CanNotAddIntoItself,
+    TooManyModules,
+    Locked,
+    LimitExceeded,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DotEveryEditorOperationIndex {
+    OptionAbsolute(usize),
+    OptionInputFiltered(usize),
+    OptionProgramModuleFiltered(usize),
+    Child(usize),
+}
+
+pub(crate) type DotEveryEditorResult<T> = Result<T, DotEveryEditorErrorMessage>;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DotEveryEditor<Type: 'static + Clone + PartialEq = ()> {
+    pub(crate) id: Uuid,
+    pub list: Vec<ProgramModule<Type>>,
+}
+
+impl<T: 'static + Clone + PartialEq> DotEveryEditor<T> {
+    pub fn new(mut list: Vec<ProgramModule<T>>) -> Self {
+        let id = Uuid::new_v4();
+        list.iter_mut().for_each(|module| module.parent = Some(id));
+        Self {
+            id,
+            list,
+        }
+    }
+
+    pub fn add(&mut self, target: Uuid, index: DotEveryEditorOperationIndex, module: &ProgramModule<T>) -> DotEveryEditorResult<()> {
+        self.check_children_lock(target)?;
+        if target.is_nil() || target == self.id {
+            if let DotEveryEditorOperationIndex::Child(index) = index {
+                if index <= self.list.len() {
+                    let mut module = module.clone();
+                    module.parent = Some(self.id);
+                    self.list.insert(index, module);
+                    Ok(())
+                } else {
+                    Err(DotEveryEditorErrorMessage::IndexOutOfRange)
+                }
+            } else {
+                Err(DotEveryEditorErrorMessage::IndexOutOfRange)
+            }
+        } else {
+            self.list.iter_mut().find_map(|m| match m.add(target, index, module) {
+                Err(DotEveryEditorErrorMessage::NotFound) => None,
+                result => Some(result)
+            }).unwrap_or(Err(DotEveryEditorErrorMessage::NotFound))
+        }
+    }
+
+    pub fn can_add(&self, target: Uuid, index: DotEveryEditorOperationIndex, module: &ProgramModule<T>) -> DotEveryEditorResult<()> {
+        if module.get_module(target).is_ok() {
+            return Err(DotEveryEditorErrorMessage::CanNotAddIntoItself);
+        }
+        self.check_children_lock(target)?;
+        if target.is_nil() || target == self.id {
+            match index {
+                DotEveryEditorOperationIndex::Child(index) if index <= self.list.len() => Ok(()),
+                _ => Err(DotEveryEditorErrorMessage::IndexOutOfRange),
+            }
+        } else {
+            self.get_module(target)?.can_accept(index)
+        }
+    }
+
+    pub fn can_add_modules(&self, target: Uuid, index: DotEveryEditorOperationIndex, modules: &[ProgramModule<T>]) -> DotEveryEditorResult<()> {
+        if modules.len() > 1 && !matches!(index, DotEveryEditorOperationIndex::Child(_)) {
+            return Err(DotEveryEditorErrorMessage::TooManyModules);
+        }
+        if modules.iter().any(|module| module.get_module(target).is_ok()) {
+            return Err(DotEveryEditorErrorMessage::CanNotAddIntoItself);
+        }
+        match modules.first() {
+            Some(module) => self.can_add(target, index, module),
+            None => Ok(()),
+        }
+    }
+
+    pub fn get_following_modules(&self, id: Uuid) -> DotEveryEditorResult<Vec<ProgramModule<T>>> {
+        let mut lists: Vec<Vec<&ProgramModule<T>>> = vec![self.list.iter().collect()];
+        while let Some(list) = lists.pop() {
+            for (i, module) in list.iter().enumerate() {
+                if module.id == id {
+                    return Ok(list[i..].iter().map(|module| (*module).clone()).collect());
+                }
+                for option in &module.options {
+                    if let ProgramModuleOption::ProgramModule(Some(module)) = option {
+                        lists.push(vec![module]);
+                    }
+                }
+                match &module.child {
+                    ProgramModuleChildItems::None => {}
+                    ProgramModuleChildItems::BlockVertical(list) | ProgramModuleChildItems::BlockHorizontal(list) => lists.push(list.iter().map(|module| &**module).collect()),
+                }
+            }
+        }
+        Err(DotEveryEditorErrorMessage::NotFound)
+    }
+
+    pub fn move_module(&mut self, src: Uuid, dest: Uuid, index: DotEveryEditorOperationIndex, module: &ProgramModule<T>) -> DotEveryEditorResult<()> {
+        self.move_modules(&[src], dest, index, std::slice::from_ref(module))
+    }
+
+    pub fn move_modules(&mut self, src: &[Uuid], dest: Uuid, index: DotEveryEditorOperationIndex, modules: &[ProgramModule<T>]) -> DotEveryEditorResult<()> {
+        if modules.len() > 1 && !matches!(index, DotEveryEditorOperationIndex::Child(_)) {
+            return Err(DotEveryEditorErrorMessage::TooManyModules);
+        }
+        for src in src {
+            if let Ok(src_module) = self.get_module(*src) {
+                if src_module.get_module(dest).is_ok() {
+                    return Err(DotEveryEditorErrorMessage::CanNotAddIntoItself);
+                }
+            }
+            match self.can_move(*src) {
+                Ok(()) | Err(DotEveryEditorErrorMessage::NotFound) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        self.check_children_lock(dest)?;
+        for (i, module) in modules.iter().enumerate() {
+            let index = match index {
+                DotEveryEditorOperationIndex::Child(index) => DotEveryEditorOperationIndex::Child(index + i),
+                index => index,
+            };
+            self.add(dest, index, module)?;
+        }
+        for src in src {
+            match self.remove_unchecked(*src) {
+                Ok(()) | Err(DotEveryEditorErrorMessage::NotFound) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn copy(&mut self, src: Uuid, dest: Uuid, dest_index: DotEveryEditorOperationIndex) -> DotEveryEditorResult<()> {
+        let module = match self.get_module(src) {
+            Ok(module) => module,
+            Err(msg) => return Err(DotEveryEditorErrorMessage::ErrorInGetModule(Box::new(msg)))
+        };
+        match self.add(dest, dest_index, &module.deep_clone().unlocked()) {
+            Ok(_) => Ok(()),
+            Err(msg) => Err(DotEveryEditorErrorMessage::ErrorInAddModule(Box::new(msg))),
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn get_module(&self, id: Uuid) -> DotEveryEditorResult<&ProgramModule<T>> {
+        self.list.iter().find_map(move |module| match module.get_module(id) {
+            Err(DotEveryEditorErrorMessage::NotFound) => None,
+            result => Some(result),
+        }).unwrap_or(Err(DotEveryEditorErrorMessage::NotFound))
+    }
+
+    pub fn get_module_mut(&mut self, id: Uuid) -> DotEveryEditorResult<&mut ProgramModule<T>> {
+        self.list.iter_mut().find_map(move |module| match module.get_module_mut(id) {
+            Err(DotEveryEditorErrorMessage::NotFound) => None,
+            result => Some(result),
+        }).unwrap_or(Err(DotEveryEditorErrorMessage::NotFound))
+    }
+
+    pub fn get_modules_by_type(&self, type_data: &T) -> Vec<&ProgramModule<T>> {
+        self.list.iter().map(|module| {
+            module.get_modules_by_type(type_data)
+        }).flatten().collect()
+    }
+
+    pub fn remove(&mut self, id: Uuid) -> DotEveryEditorResult<()> {
+        self.can_remove(id)?;
+        self.remove_unchecked(id)
+    }
+
+    // neither the module nor one of its descendants is locked against moving, and its parent takes children out
+    pub fn can_move(&self, id: Uuid) -> DotEveryEditorResult<()> {
+        let module = self.get_module(id)?;
+        if module.any_lock(|lock| lock.no_move) {
+            return Err(DotEveryEditorErrorMessage::Locked);
+        }
+        self.check_children_lock(module.parent().unwrap_or(self.id))
+    }
+
+    pub fn can_remove(&self, id: Uuid) -> DotEveryEditorResult<()> {
+        let module = self.get_module(id)?;
+        if module.any_lock(|lock| lock.no_delete) {
+            return Err(DotEveryEditorErrorMessage::Locked);
+        }
+        self.check_children_lock(module.parent().unwrap_or(self.id))
+    }
+
+    pub fn update_input(&mut self, id: Uuid, index: usize, value: String) -> DotEveryEditorResult<()> {
+        let module = self.get_module_mut(id)?;
+        if module.lock.no_edit {
+            return Err(DotEveryEditorErrorMessage::Locked);
+        }
+        match module.options.get_mut(index) {
+            Some(ProgramModuleOption::StringInput(s)) => {
+                *s = value;
+                Ok(())
+            }
+            _ => Err(DotEveryEditorErrorMessage::IndexOutOfRange),
+        }
+    }
+
+    pub fn set_disabled(&mut self, id: Uuid, disabled: bool) -> DotEveryEditorResult<()> {
+        let module = self.get_module_mut(id)?;
+        if module.lock.no_edit {
+            return Err(DotEveryEditorErrorMessage::Locked);
+        }
+        module.disabled = disabled;
+        Ok(())
+    }
+
+    pub fn set_comment(&mut self, id: Uuid, comment: Option<String>) -> DotEveryEditorResult<()> {
+        let module = self.get_module_mut(id)?;
+        if module.lock.no_edit {
+            return Err(DotEveryEditorErrorMessage::Locked);
+        }
+        module.comment = comment.filter(|comment| !comment.trim().is_empty());
+        Ok(())
+    }
+
+    // the parent of the module and the index of the module in it
+    pub fn position(&self, id: Uuid) -> DotEveryEditorResult<(Uuid, DotEveryEditorOperationIndex)> {
+        let parent = self.get_module(id)?.parent().unwrap_or(self.id);
+        if parent == self.id {
+            let i = self.list.iter().position(|module| module.id == id).ok_or(DotEveryEditorErrorMessage::NotFound)?;
+            return Ok((parent, DotEveryEditorOperationIndex::Child(i)));
+        }
+        let parent_module = self.get_module(parent)?;
+        let option = parent_module.options.iter().position(|option| matches!(option, ProgramModuleOption::ProgramModule(Some(module)) if module.id == id));
+        if let Some(i) = option {
+            return Ok((parent, DotEveryEditorOperationIndex::OptionAbsolute(i)));
+        }
+        match &parent_module.child {
+            ProgramModuleChildItems::BlockVertical(list) | ProgramModuleChildItems::BlockHorizontal(list) => list.iter()
+                .position(|module| module.id == id)
+                .map(|i| (parent, DotEveryEditorOperationIndex::Child(i)))
+                .ok_or(DotEveryEditorErrorMessage::NotFound),
+            ProgramModuleChildItems::None => Err(DotEveryEditorErrorMessage::NotFound),
+        }
+    }
+
+    // where a duplicate goes, right after the module or, for a module in a slot, after the nearest module in a list
+    pub fn duplicate_position(&self, id: Uuid) -> DotEveryEditorResult<(Uuid, DotEveryEditorOperationIndex)> {
+        let mut id = id;
+        loop {
+            match self.position(id)? {
+                (parent, DotEveryEditorOperationIndex::Child(i)) => return Ok((parent, DotEveryEditorOperationIndex::Child(i + 1))),
+                (parent, _) => id = parent,
+            }
+        }
+    }
+
+    fn check_children_lock(&self, target: Uuid) -> DotEveryEditorResult<()> {
+        if target.is_nil() || target == self.id { return Ok(()); }
+        match self.get_module(target) {
+            Ok(module) if module.lock.no_children => Err(DotEveryEditorErrorMessage::Locked),
+            _ => Ok(()),
+        }
+    }
+
+    fn remove_unchecked(&mut self, id: Uuid) -> DotEveryEditorResult<()> {
+        if let Some(i) = self.list
+            .iter()
+            .position(move |module| module.id == id) {
+            self.list.remove(i);
+            Ok(())
+        } else if let Some(result) = self.list
+            .iter_mut()
+            .find_map(|module| match module.remove(id) {
+                DotEveryEditorResult::Err(DotEveryEditorErrorMessage::NotFound) => None,
+                result => Some(result),
+            }) {
+            result
+        } else {
+            Err(DotEveryEditorErrorMessage::NotFound)
+        }
+    }
+
+    // replaces the module with the same id in place, the parent is kept
+    pub fn replace_module(&mut self, mut module: ProgramModule<T>) -> DotEveryEditorResult<()> {
+        let target = self.get_module_mut(module.id)?;
+        module.parent = target.parent;
+        *target = module;
+        Ok(())
+    }
+
+    // outermost modules covering every id, None when the root list itself has to be replaced
+    pub fn changed_subtrees(&self, ids: &[Uuid]) -> Option<Vec<Uuid>> {
+        let mut result: Vec<Uuid> = Vec::new();
+        for id in ids {
+            if id.is_nil() || *id == self.id || self.get_module(*id).is_err() {
+                return None;
+            }
+            if result.iter().any(|outer| self.get_module(*outer).map(|outer| outer.get_module(*id).is_ok()).unwrap_or(false)) {
+                continue;
+            }
+            result.retain(|inner| self.get_module(*id).map(|module| module.get_module(*inner).is_err()).unwrap_or(true));
+            result.push(*id);
+        }
+        Some(result)
+    }
+
+    pub fn set_root_children(&mut self, mut children: Vec<ProgramModule<T>>) -> DotEveryEditorResult<()> {
+        children.iter_mut().for_each(|module| module.parent = Some(self.id));
+        self.list = children;
+        Ok(())
+    }
+}
\ No newline at end of file
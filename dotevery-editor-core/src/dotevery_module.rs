@@ -0,0 +1,34 @@
+#[cfg(feature = "derive")]
+pub use dotevery_editor_derive::DotEveryModule;
+
+use crate::i18n::MessageCatalog;
+use crate::palette::Palette;
+use crate::program_module::ProgramModule;
+
+// a category declared with #[module_category]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ModuleCategory {
+    pub name: &'static str,
+    pub color: &'static str,
+}
+
+pub trait DotEveryModule: 'static + Clone + PartialEq {
+    fn create_module(&self) -> ProgramModule<Self>;
+
+    fn palette_types() -> Vec<Self>;
+
+    fn categories() -> Vec<ModuleCategory> {
+        Vec::new()
+    }
+
+    fn category(&self) -> Option<ModuleCategory> {
+        None
+    }
+
+    // registers the translations written with `sign(text = "...", locales(...))`
+    fn register_signs(_catalog: &mut MessageCatalog) {}
+
+    fn palette() -> Palette<Self> {
+        Palette::from(Self::palette_types().iter().map(Self::create_module).collect::<Vec<_>>())
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::dotevery_editor::DotEveryEditor;
+use crate::program_module::{ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MessageCatalog {
+    locale: String,
+    fallback: String,
+    // locale -> key -> text
+    messages: HashMap<String, HashMap<String, String>>,
+    // "type tag.option index" -> locale -> displayed text, the same text may read differently in other modules
+    signs: HashMap<String, HashMap<String, String>>,
+}
+
+impl MessageCatalog {
+    pub fn new(locale: &str) -> Self {
+        Self {
+            locale: locale.to_string(),
+            fallback: locale.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_fallback(mut self, fallback: &str) -> Self {
+        self.fallback = fallback.to_string();
+        self
+    }
+
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    pub fn set_locale(&mut self, locale: &str) {
+        self.locale = locale.to_string();
+    }
+
+    pub fn locales(&self) -> Vec<String> {
+        let mut locales = self.messages.keys().chain(self.signs.values().flat_map(HashMap::keys)).cloned().collect::<Vec<_>>();
+        locales.sort();
+        locales.dedup();
+        locales
+    }
+
+    pub fn insert(&mut self, locale: &str, key: &str, text: &str) {
+        self.messages.entry(locale.to_string()).or_default().insert(key.to_string(), text.to_string());
+    }
+
+    pub fn insert_sign(&mut self, tag: &str, index: usize, locale: &str, text: &str) {
+        self.signs.entry(sign_key(tag, index)).or_default().insert(locale.to_string(), text.to_string());
+    }
+
+    // entries of `other` win, the locale of `self` is kept
+    pub fn merge(&mut self, other: &MessageCatalog) {
+        for (locale, messages) in &other.messages {
+            self.messages.entry(locale.clone()).or_default().extend(messages.iter().map(|(key, text)| (key.clone(), text.clone())));
+        }
+        for (sign, texts) in &other.signs {
+            self.signs.entry(sign.clone()).or_default().extend(texts.iter().map(|(locale, text)| (locale.clone(), text.clone())));
+        }
+    }
+
+    // the current locale, then the fallback locale, then the key itself
+    pub fn get(&self, key: &str) -> String {
+        [&self.locale, &self.fallback].iter()
+            .find_map(|locale| self.messages.get(*locale).and_then(|messages| messages.get(key)))
+            .cloned()
+            .unwrap_or_else(|| key.to_string())
+    }
+
+    // replaces `{name}` in the message with the value of `name`
+    pub fn format(&self, key: &str, args: &[(&str, &str)]) -> String {
+        args.iter().fold(self.get(key), |text, (name, value)| text.replace(&format!("{{{}}}", name), value))
+    }
+
+    // the sign at `index` of a module of type `tag`, displayed as stored when there is no translation for the current locale
+    pub fn sign<'a>(&'a self, tag: Option<&str>, index: usize, sign: &'a str) -> &'a str {
+        tag.and_then(|tag| self.signs.get(&sign_key(tag, index)))
+            .and_then(|texts| texts.get(&self.locale))
+            .map(String::as_str)
+            .unwrap_or(sign)
+    }
+
+    // a copy for display, the ids are kept so that it can stand in for the original
+    pub fn localize<T: 'static + Clone + PartialEq>(&self, module: &ProgramModule<T>, tag: &dyn Fn(&T) -> Option<String>) -> ProgramModule<T> {
+        let mut module = module.clone();
+        self.localize_in_place(&mut module, tag);
+        module
+    }
+
+    pub fn localize_editor<T: 'static + Clone + PartialEq>(&self, editor: &DotEveryEditor<T>, tag: &dyn Fn(&T) -> Option<String>) -> DotEveryEditor<T> {
+        let mut editor = editor.clone();
+        for module in &mut editor.list {
+            self.localize_in_place(module, tag);
+        }
+        editor
+    }
+
+    fn localize_in_place<T: 'static + Clone + PartialEq>(&self, module: &mut ProgramModule<T>, tag: &dyn Fn(&T) -> Option<String>) {
+        if self.signs.is_empty() { return; }
+        let type_tag = tag(&module.type_data);
+        for (i, option) in module.options.iter_mut().enumerate() {
+            match option {
+                ProgramModuleOption::StringSign(s) => {
+                    let localized = self.sign(type_tag.as_deref(), i, s).to_string();
+                    *s = localized;
+                }
+                ProgramModuleOption::ProgramModule(Some(module)) => self.localize_in_place(Arc::make_mut(module), tag),
+                _ => {}
+            }
+        }
+        if let ProgramModuleChildItems::BlockVertical(list) | ProgramModuleChildItems::BlockHorizontal(list) = &mut module.child {
+            for module in list {
+                self.localize_in_place(Arc::make_mut(module), tag);
+            }
+        }
+    }
+}
+
+fn sign_key(tag: &str, index: usize) -> String {
+    format!("{}.{}", tag, index)
+}
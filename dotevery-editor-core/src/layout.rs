@@ -0,0 +1,468 @@
+use std::borrow::Borrow;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::dotevery_editor::{DotEveryEditor, DotEveryEditorOperationIndex};
+use crate::program_module::{ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
+use crate::util::Rect;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LayoutMetrics {
+    pub char_width: f64,
+    pub line_height: f64,
+    pub padding: f64,
+    pub nested_padding: f64,
+    pub gap: f64,
+    pub child_gap: f64,
+    pub input_min_width: f64,
+    pub slot_width: f64,
+    pub child_indent: f64,
+    pub empty_child_height: f64,
+    pub connector_offset: f64,
+    pub connector_width: f64,
+    pub connector_height: f64,
+    pub icon_width: f64,
+}
+
+impl Default for LayoutMetrics {
+    fn default() -> Self {
+        Self {
+            char_width: 8f64,
+            line_height: 20f64,
+            padding: 6f64,
+            nested_padding: 4f64,
+            gap: 4f64,
+            child_gap: 4f64,
+            input_min_width: 40f64,
+            slot_width: 40f64,
+            child_indent: 16f64,
+            empty_child_height: 24f64,
+            connector_offset: 12f64,
+            connector_width: 24f64,
+            connector_height: 6f64,
+            icon_width: 16f64,
+        }
+    }
+}
+
+impl LayoutMetrics {
+    // nested modules sit inside an option row, so they get a tighter padding than blocks
+    fn padding(&self, nested: bool) -> f64 {
+        if nested { self.nested_padding } else { self.padding }
+    }
+}
+
+pub trait TextMetrics {
+    fn text_width(&self, text: &str) -> f64;
+}
+
+// monospace fallback, wide characters take two cells
+impl TextMetrics for LayoutMetrics {
+    fn text_width(&self, text: &str) -> f64 {
+        text.chars().map(|c| if c.is_ascii() { self.char_width } else { self.char_width * 2f64 }).sum()
+    }
+}
+
+struct Layouter<'a> {
+    metrics: &'a LayoutMetrics,
+    text: &'a dyn TextMetrics,
+}
+
+impl<'a> Layouter<'a> {
+    fn module<T: 'static + Clone + PartialEq>(&self, module: &ProgramModule<T>, x: f64, y: f64, nested: bool) -> ModuleLayout {
+        let metrics = self.metrics;
+        let mut options = module.options.iter().map(|option| match option {
+            ProgramModuleOption::StringSign(s) => OptionLayout::Sign(Rect { x: 0f64, y: 0f64, w: self.text.text_width(s), h: metrics.line_height }),
+            ProgramModuleOption::StringInput(s) => OptionLayout::Input(Rect {
+                x: 0f64,
+                y: 0f64,
+                w: f64::max(metrics.input_min_width, self.text.text_width(s) + metrics.gap * 2f64),
+                h: metrics.line_height,
+            }),
+            ProgramModuleOption::ProgramModule(None) => OptionLayout::Slot(Rect { x: 0f64, y: 0f64, w: metrics.slot_width, h: metrics.line_height }),
+            ProgramModuleOption::ProgramModule(Some(module)) => OptionLayout::Module(Box::new(self.module(module, 0f64, 0f64, true))),
+        }).collect::<Vec<_>>();
+        let padding = metrics.padding(nested);
+        let row_height = options.iter().map(|option| option.rect().h).fold(metrics.line_height, f64::max);
+        let top = if nested { 0f64 } else { metrics.connector_height };
+        // the comment takes a line above the options
+        let comment = module.comment.as_ref().map(|comment| Rect { x: x + padding, y: y + top + padding, w: self.text.text_width(comment), h: metrics.line_height });
+        let row_y = y + top + padding + comment.as_ref().map(|comment| comment.h).unwrap_or(0f64);
+        let mut option_x = x + padding;
+        for option in &mut options {
+            let (w, h) = (option.rect().w, option.rect().h);
+            option.translate(option_x, row_y + (row_height - h) / 2f64);
+            option_x += w + metrics.gap;
+        }
+        // the lock icon follows the options inside the header
+        let lock = if module.lock.is_locked() {
+            let rect = Rect { x: option_x, y: row_y + (row_height - metrics.line_height) / 2f64, w: metrics.icon_width, h: metrics.line_height };
+            option_x += metrics.icon_width + metrics.gap;
+            Some(rect)
+        } else {
+            None
+        };
+        let comment_w = comment.as_ref().map(|comment| comment.w + padding * 2f64).unwrap_or(0f64);
+        let header_w = f64::max(f64::max(option_x - metrics.gap + padding - x, padding * 2f64 + metrics.slot_width), comment_w);
+        let header = Rect { x, y, w: header_w, h: row_y - y + row_height + padding };
+
+        let child = match &module.child {
+            _ if module.collapsed => None,
+            ProgramModuleChildItems::None => None,
+            ProgramModuleChildItems::BlockVertical(list) => Some((ChildDirection::Vertical, list)),
+            ProgramModuleChildItems::BlockHorizontal(list) => Some((ChildDirection::Horizontal, list)),
+        }.map(|(direction, list)| self.child(direction, list, x + metrics.child_indent, y + header.h));
+
+        let rect = match &child {
+            Some(child) => Rect {
+                x,
+                y,
+                w: f64::max(header.w, child.rect.x + child.rect.w - x),
+                h: header.h + child.rect.h + padding * 2f64,
+            },
+            None => header.clone(),
+        };
+        ModuleLayout { id: module.id(), rect, header, comment, options, lock, child, nested }
+    }
+
+    // vertical children are stacked without a gap so that their connectors meet
+    fn child<T: 'static + Clone + PartialEq>(&self, direction: ChildDirection, list: &[impl Borrow<ProgramModule<T>>], x: f64, y: f64) -> ChildLayout {
+        let metrics = self.metrics;
+        let mut modules = Vec::with_capacity(list.len());
+        let (mut w, mut h) = (0f64, 0f64);
+        for (i, module) in list.iter().map(Borrow::borrow).enumerate() {
+            if direction == ChildDirection::Horizontal && i > 0 {
+                w += metrics.child_gap;
+            }
+            let layout = match direction {
+                ChildDirection::Vertical => self.module(module, x, y + h, false),
+                ChildDirection::Horizontal => self.module(module, x + w, y, false),
+            };
+            match direction {
+                ChildDirection::Vertical => {
+                    w = f64::max(w, layout.rect.w);
+                    h += layout.rect.h;
+                }
+                ChildDirection::Horizontal => {
+                    w += layout.rect.w;
+                    h = f64::max(h, layout.rect.h);
+                }
+            }
+            modules.push(layout);
+        }
+        let (w, h) = match direction {
+            ChildDirection::Vertical => (f64::max(w, metrics.slot_width), h + metrics.empty_child_height),
+            // keep a slot wide drop area after the last module
+            ChildDirection::Horizontal if modules.is_empty() => (metrics.slot_width, metrics.empty_child_height),
+            ChildDirection::Horizontal => (w + metrics.child_gap + metrics.slot_width, f64::max(h, metrics.empty_child_height)),
+        };
+        ChildLayout { direction, rect: Rect { x, y, w, h }, modules }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ChildDirection {
+    Vertical,
+    Horizontal,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum OptionLayout {
+    Sign(Rect),
+    Input(Rect),
+    Slot(Rect),
+    Module(Box<ModuleLayout>),
+}
+
+impl OptionLayout {
+    pub fn rect(&self) -> &Rect {
+        match self {
+            OptionLayout::Sign(rect) | OptionLayout::Input(rect) | OptionLayout::Slot(rect) => rect,
+            OptionLayout::Module(layout) => &layout.rect,
+        }
+    }
+
+    fn translate(&mut self, dx: f64, dy: f64) {
+        match self {
+            OptionLayout::Sign(rect) | OptionLayout::Input(rect) | OptionLayout::Slot(rect) => *rect = rect.translated(dx, dy),
+            OptionLayout::Module(layout) => layout.translate(dx, dy),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ChildLayout {
+    pub direction: ChildDirection,
+    pub rect: Rect,
+    pub modules: Vec<ModuleLayout>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ModuleLayout {
+    pub id: Uuid,
+    pub rect: Rect,
+    pub header: Rect,
+    pub comment: Option<Rect>,
+    pub options: Vec<OptionLayout>,
+    pub lock: Option<Rect>,
+    pub child: Option<ChildLayout>,
+    pub nested: bool,
+}
+
+impl ModuleLayout {
+    pub fn new<T: 'static + Clone + PartialEq>(module: &ProgramModule<T>, metrics: &LayoutMetrics, x: f64, y: f64) -> Self {
+        Self::with_text_metrics(module, metrics, metrics, x, y)
+    }
+
+    pub fn with_text_metrics<T: 'static + Clone + PartialEq>(module: &ProgramModule<T>, metrics: &LayoutMetrics, text: &dyn TextMetrics, x: f64, y: f64) -> Self {
+        Layouter { metrics, text }.module(module, x, y, false)
+    }
+
+    pub fn translate(&mut self, dx: f64, dy: f64) {
+        self.rect = self.rect.translated(dx, dy);
+        self.header = self.header.translated(dx, dy);
+        for option in &mut self.options {
+            option.translate(dx, dy);
+        }
+        if let Some(comment) = &mut self.comment {
+            *comment = comment.translated(dx, dy);
+        }
+        if let Some(lock) = &mut self.lock {
+            *lock = lock.translated(dx, dy);
+        }
+        if let Some(child) = &mut self.child {
+            child.rect = child.rect.translated(dx, dy);
+            for module in &mut child.modules {
+                module.translate(dx, dy);
+            }
+        }
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<&ModuleLayout> {
+        if self.id == id {
+            return Some(self);
+        }
+        self.options.iter()
+            .filter_map(|option| match option {
+                OptionLayout::Module(layout) => layout.get(id),
+                _ => None,
+            })
+            .chain(self.child.iter().flat_map(|child| child.modules.iter()).filter_map(|module| module.get(id)))
+            .next()
+    }
+
+    // innermost module under the point
+    pub fn hit_test(&self, x: f64, y: f64) -> Option<Uuid> {
+        if !self.rect.encloses(x, y) {
+            return None;
+        }
+        self.options.iter()
+            .filter_map(|option| match option {
+                OptionLayout::Module(layout) => layout.hit_test(x, y),
+                _ => None,
+            })
+            .chain(self.child.iter().flat_map(|child| child.modules.iter()).filter_map(|module| module.hit_test(x, y)))
+            .next()
+            .or(Some(self.id))
+    }
+
+    fn collect_rects(&self, parent: Uuid, rects: &mut Vec<(Uuid, Uuid, Rect)>) {
+        rects.push((self.id, parent, self.rect.clone()));
+        for option in &self.options {
+            if let OptionLayout::Module(layout) = option {
+                layout.collect_rects(self.id, rects);
+            }
+        }
+        if let Some(child) = &self.child {
+            for module in &child.modules {
+                module.collect_rects(self.id, rects);
+            }
+        }
+    }
+}
+
+impl ChildLayout {
+    fn insertion_index(&self, x: f64, y: f64) -> usize {
+        self.modules.iter()
+            .take_while(|module| {
+                let (center_x, center_y) = module.rect.center();
+                match self.direction {
+                    ChildDirection::Vertical => center_y < y,
+                    ChildDirection::Horizontal => center_x < x,
+                }
+            })
+            .count()
+    }
+
+    fn insertion_marker(&self, index: usize, metrics: &LayoutMetrics) -> Rect {
+        let thickness = metrics.connector_height / 2f64;
+        match self.direction {
+            ChildDirection::Vertical => {
+                let y = self.modules.get(index).map(|module| module.rect.y).unwrap_or_else(|| self.modules.last().map(|module| module.rect.y + module.rect.h).unwrap_or(self.rect.y));
+                Rect { x: self.rect.x, y: y - thickness / 2f64, w: self.rect.w, h: thickness }
+            }
+            ChildDirection::Horizontal => {
+                let x = self.modules.get(index).map(|module| module.rect.x).unwrap_or_else(|| self.modules.last().map(|module| module.rect.x + module.rect.w).unwrap_or(self.rect.x));
+                Rect { x: x - thickness / 2f64, y: self.rect.y, w: thickness, h: self.rect.h }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EditorLayout {
+    pub root: Uuid,
+    pub metrics: LayoutMetrics,
+    pub list: ChildLayout,
+    pub bounds: Rect,
+}
+
+impl EditorLayout {
+    pub fn new<T: 'static + Clone + PartialEq>(editor: &DotEveryEditor<T>, metrics: &LayoutMetrics) -> Self {
+        Self::with_text_metrics(editor, metrics, metrics)
+    }
+
+    pub fn with_text_metrics<T: 'static + Clone + PartialEq>(editor: &DotEveryEditor<T>, metrics: &LayoutMetrics, text: &dyn TextMetrics) -> Self {
+        let list = Layouter { metrics, text }.child(ChildDirection::Vertical, &editor.list, metrics.padding, metrics.padding);
+        let bounds = Rect {
+            x: 0f64,
+            y: 0f64,
+            w: list.rect.w + metrics.padding * 2f64,
+            h: list.rect.h + metrics.padding * 2f64,
+        };
+        Self { root: editor.id(), metrics: metrics.clone(), list, bounds }
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<&ModuleLayout> {
+        self.list.modules.iter().filter_map(|module| module.get(id)).next()
+    }
+
+    // nil when the point is on the editor but not on any module
+    pub fn hit_test(&self, x: f64, y: f64) -> Option<Uuid> {
+        self.list.modules.iter().filter_map(|module| module.hit_test(x, y)).next()
+            .or_else(|| if self.bounds.encloses(x, y) { Some(Uuid::nil()) } else { None })
+    }
+
+    pub fn contains(&self, id: Uuid) -> bool {
+        id.is_nil() || self.get(id).is_some()
+    }
+
+    // (id, parent, rect) of every module, the root list is registered as nil like ProgramModuleDefault
+    pub fn rects(&self) -> Vec<(Uuid, Uuid, Rect)> {
+        let mut rects = vec![(Uuid::nil(), self.root, self.bounds.clone())];
+        for module in &self.list.modules {
+            module.collect_rects(Uuid::nil(), &mut rects);
+        }
+        rects
+    }
+
+    fn parent_list(&self, id: Uuid) -> Option<(Uuid, &ChildLayout, usize)> {
+        let mut stack = vec![(Uuid::nil(), &self.list)];
+        while let Some((owner, list)) = stack.pop() {
+            for (i, module) in list.modules.iter().enumerate() {
+                if module.id == id {
+                    return Some((owner, list, i));
+                }
+            }
+            for module in &list.modules {
+                let mut modules = vec![module];
+                while let Some(module) = modules.pop() {
+                    if let Some(child) = &module.child {
+                        stack.push((module.id, child));
+                    }
+                    modules.extend(module.options.iter().filter_map(|option| match option {
+                        OptionLayout::Module(layout) => Some(layout.as_ref()),
+                        _ => None,
+                    }));
+                }
+            }
+        }
+        None
+    }
+
+    pub fn drop_index(&self, target: Uuid, x: f64, y: f64) -> Option<(Uuid, DotEveryEditorOperationIndex)> {
+        if target.is_nil() {
+            return Some((target, DotEveryEditorOperationIndex::Child(self.list.insertion_index(x, y))));
+        }
+        let module = self.get(target)?;
+        for (i, option) in module.options.iter().enumerate() {
+            if let OptionLayout::Slot(_) | OptionLayout::Module(_) = option {
+                if option.rect().encloses(x, y) {
+                    return Some((target, DotEveryEditorOperationIndex::OptionAbsolute(i)));
+                }
+            }
+        }
+        if let Some(child) = &module.child {
+            if y >= child.rect.y {
+                return Some((target, DotEveryEditorOperationIndex::Child(child.insertion_index(x, y))));
+            }
+        }
+        if module.nested {
+            return None;
+        }
+        let (owner, list, i) = self.parent_list(target)?;
+        let (center_x, center_y) = module.header.center();
+        let after = match list.direction {
+            ChildDirection::Vertical => y > center_y,
+            ChildDirection::Horizontal => x > center_x,
+        };
+        Some((owner, DotEveryEditorOperationIndex::Child(if after { i + 1 } else { i })))
+    }
+
+    pub fn insertion_marker(&self, target: Uuid, index: DotEveryEditorOperationIndex) -> Option<Rect> {
+        let list = if target.is_nil() {
+            &self.list
+        } else {
+            let module = self.get(target)?;
+            if let DotEveryEditorOperationIndex::OptionAbsolute(i) = index {
+                return module.options.get(i).map(|option| option.rect().clone());
+            }
+            module.child.as_ref()?
+        };
+        match index {
+            DotEveryEditorOperationIndex::Child(i) if i <= list.modules.len() => Some(list.insertion_marker(i, &self.metrics)),
+            _ => None,
+        }
+    }
+}
+
+pub fn block_path(layout: &ModuleLayout, metrics: &LayoutMetrics) -> String {
+    let Rect { x, y, w, h } = layout.rect;
+    let d = metrics.connector_height;
+    let (notch_x, notch_w) = (metrics.connector_offset, metrics.connector_width);
+    let slope = d;
+    let mut path = format!("M {} {} H {}", x, y, x + notch_x);
+    // notch on the top edge
+    path.push_str(&format!(" l {} {} h {} l {} {}", slope, d, notch_w - slope * 2f64, slope, -d));
+    path.push_str(&format!(" H {} V {}", x + w, y + layout.header.h));
+    if let Some(child) = &layout.child {
+        let inner_x = child.rect.x;
+        // tab hanging into the first child's notch
+        path.push_str(&format!(" H {} l {} {} h {} l {} {}", inner_x + notch_x + notch_w, -slope, d, -(notch_w - slope * 2f64), -slope, -d));
+        path.push_str(&format!(" H {} V {} H {} V {}", inner_x, child.rect.y + child.rect.h, x + w, y + h));
+    }
+    // tab on the bottom edge
+    path.push_str(&format!(" H {} l {} {} h {} l {} {}", x + notch_x + notch_w, -slope, d, -(notch_w - slope * 2f64), -slope, -d));
+    path.push_str(&format!(" H {} Z", x));
+    path
+}
+
+pub fn rounded_path(rect: &Rect) -> String {
+    let Rect { x, y, w, h } = *rect;
+    let r = f64::min(h, w) / 2f64;
+    format!("M {} {} H {} A {} {} 0 0 1 {} {} H {} A {} {} 0 0 1 {} {} Z", x + r, y, x + w - r, r, r, x + w - r, y + h, x + r, r, r, x + r, y)
+}
+
+pub fn hexagon_path(rect: &Rect) -> String {
+    let Rect { x, y, w, h } = *rect;
+    let r = f64::min(h, w) / 2f64;
+    format!("M {} {} H {} L {} {} L {} {} H {} L {} {} Z", x + r, y, x + w - r, x + w, y + h / 2f64, x + w - r, y + h, x + r, x, y + h / 2f64)
+}
@@ -0,0 +1,19 @@
+pub mod cursor;
+pub mod dotevery_editor;
+pub mod dotevery_module;
+pub mod i18n;
+pub mod layout;
+pub mod outline;
+pub mod palette;
+pub mod program_module;
+pub mod spatial_index;
+pub mod svg_export;
+#[cfg(feature = "template")]
+pub mod template;
+pub mod util;
+pub mod viewport;
+#[cfg(test)]
+mod tests;
+
+#[cfg(test)]
+extern crate self as dotevery_editor_core;
@@ -0,0 +1,75 @@
+use uuid::Uuid;
+
+use crate::dotevery_editor::DotEveryEditor;
+use crate::program_module::{ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineEntry<T: 'static + Clone + PartialEq = ()> {
+    pub id: Uuid,
+    pub depth: usize,
+    pub text: String,
+    pub collapsed: bool,
+    pub type_data: T,
+}
+
+// the modules in document order, the children of a collapsed module are left out like in the editor
+pub fn outline<T: 'static + Clone + PartialEq>(editor: &DotEveryEditor<T>) -> Vec<OutlineEntry<T>> {
+    let mut entries = Vec::new();
+    for module in &editor.list {
+        push_entries(module, 0, &mut entries);
+    }
+    entries
+}
+
+// the signs of the module, joined by spaces
+pub fn outline_text<T: 'static + Clone + PartialEq>(module: &ProgramModule<T>) -> String {
+    module.options.iter()
+        .filter_map(|option| match option {
+            ProgramModuleOption::StringSign(s) => Some(s.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// the module and its descendants as indented lines, for the clipboard
+pub fn plain_text<T: 'static + Clone + PartialEq>(module: &ProgramModule<T>) -> String {
+    let mut lines = Vec::new();
+    push_lines(module, 0, &mut lines);
+    lines.join("\n")
+}
+
+fn inline_text<T: 'static + Clone + PartialEq>(module: &ProgramModule<T>) -> String {
+    module.options.iter()
+        .map(|option| match option {
+            ProgramModuleOption::StringSign(s) | ProgramModuleOption::StringInput(s) => s.clone(),
+            ProgramModuleOption::ProgramModule(Some(module)) => format!("({})", inline_text(module)),
+            ProgramModuleOption::ProgramModule(None) => "()".to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn push_lines<T: 'static + Clone + PartialEq>(module: &ProgramModule<T>, depth: usize, lines: &mut Vec<String>) {
+    lines.push(format!("{}{}", "  ".repeat(depth), inline_text(module)));
+    if let ProgramModuleChildItems::BlockVertical(list) | ProgramModuleChildItems::BlockHorizontal(list) = &module.child {
+        for module in list {
+            push_lines(module, depth + 1, lines);
+        }
+    }
+}
+
+fn push_entries<T: 'static + Clone + PartialEq>(module: &ProgramModule<T>, depth: usize, entries: &mut Vec<OutlineEntry<T>>) {
+    entries.push(OutlineEntry { id: module.id(), depth, text: outline_text(module), collapsed: module.collapsed, type_data: module.type_data.clone() });
+    for option in &module.options {
+        if let ProgramModuleOption::ProgramModule(Some(module)) = option {
+            push_entries(module, depth + 1, entries);
+        }
+    }
+    if module.collapsed { return; }
+    if let ProgramModuleChildItems::BlockVertical(list) | ProgramModuleChildItems::BlockHorizontal(list) = &module.child {
+        for module in list {
+            push_entries(module, depth + 1, entries);
+        }
+    }
+}
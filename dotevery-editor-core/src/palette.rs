@@ -0,0 +1,155 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::dotevery_editor::{DotEveryEditor, DotEveryEditorErrorMessage, DotEveryEditorResult};
+use crate::program_module::ProgramModule;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PaletteCategory<Type: 'static + Clone + PartialEq = ()> {
+    pub name: String,
+    pub color: String,
+    pub items: Vec<ProgramModule<Type>>,
+}
+
+impl<T: 'static + Clone + PartialEq> PaletteCategory<T> {
+    pub fn new(name: impl Into<String>, color: impl Into<String>, items: Vec<ProgramModule<T>>) -> Self {
+        Self {
+            name: name.into(),
+            color: color.into(),
+            items,
+        }
+    }
+
+    pub fn filter(&self, query: &str) -> Self {
+        let query = query.trim().to_lowercase();
+        Self {
+            name: self.name.clone(),
+            color: self.color.clone(),
+            items: self.items
+                .iter()
+                .filter(|module| module.sign_text().to_lowercase().contains(&query))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Palette<Type: 'static + Clone + PartialEq = ()> {
+    pub categories: Vec<PaletteCategory<Type>>,
+    // the most modules of the type that the program may contain
+    #[cfg_attr(feature = "serde", serde(default = "Vec::new"))]
+    pub limits: Vec<(Type, usize)>,
+    // the most modules in the whole program
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub budget: Option<usize>,
+}
+
+impl<T: 'static + Clone + PartialEq> Default for Palette<T> {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl<T: 'static + Clone + PartialEq> From<Vec<ProgramModule<T>>> for Palette<T> {
+    fn from(items: Vec<ProgramModule<T>>) -> Self {
+        Self::new(vec![PaletteCategory::new(String::new(), String::new(), items)])
+    }
+}
+
+impl<T: 'static + Clone + PartialEq> Palette<T> {
+    pub fn new(categories: Vec<PaletteCategory<T>>) -> Self {
+        Self { categories, limits: Vec::new(), budget: None }
+    }
+
+    pub fn set_limit(&mut self, type_data: T, limit: Option<usize>) {
+        self.limits.retain(|(limited, _)| limited != &type_data);
+        if let Some(limit) = limit {
+            self.limits.push((type_data, limit));
+        }
+    }
+
+    pub fn limit(&self, type_data: &T) -> Option<usize> {
+        self.limits.iter().find(|(limited, _)| limited == type_data).map(|(_, limit)| *limit)
+    }
+
+    // how many more modules of the type can be placed, None without a limit
+    pub fn remaining(&self, editor: &DotEveryEditor<T>, type_data: &T) -> Option<usize> {
+        self.limit(type_data).map(|limit| limit.saturating_sub(editor.get_modules_by_type(type_data).len()))
+    }
+
+    pub fn remaining_budget(&self, editor: &DotEveryEditor<T>) -> Option<usize> {
+        self.budget.map(|budget| budget.saturating_sub(editor.list.iter().map(ProgramModule::module_count).sum()))
+    }
+
+    // whether the new modules, with their descendants, fit into the limits and the budget
+    pub fn can_place(&self, editor: &DotEveryEditor<T>, modules: &[ProgramModule<T>]) -> DotEveryEditorResult<()> {
+        if modules.is_empty() { return Ok(()); }
+        if let Some(remaining) = self.remaining_budget(editor) {
+            if modules.iter().map(ProgramModule::module_count).sum::<usize>() > remaining {
+                return Err(DotEveryEditorErrorMessage::LimitExceeded);
+            }
+        }
+        for (type_data, _) in &self.limits {
+            let added = modules.iter().map(|module| module.get_modules_by_type(type_data).len()).sum::<usize>();
+            if added > self.remaining(editor, type_data).unwrap_or(0) {
+                return Err(DotEveryEditorErrorMessage::LimitExceeded);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn category(&self, name: &str) -> Option<&PaletteCategory<T>> {
+        self.categories.iter().find(|category| category.name == name)
+    }
+
+    pub fn category_mut(&mut self, name: &str) -> Option<&mut PaletteCategory<T>> {
+        self.categories.iter_mut().find(|category| category.name == name)
+    }
+
+    pub fn push(&mut self, category: &str, module: ProgramModule<T>) {
+        if let Some(category) = self.category_mut(category) {
+            category.items.push(module);
+        } else {
+            self.categories.push(PaletteCategory::new(category, String::new(), vec![module]));
+        }
+    }
+
+    pub fn remove_by_type(&mut self, type_data: &T) -> Option<ProgramModule<T>> {
+        self.categories.iter_mut().find_map(|category| {
+            category.items
+                .iter()
+                .position(|module| &module.type_data == type_data)
+                .map(|index| category.items.remove(index))
+        })
+    }
+
+    pub fn items(&self) -> impl Iterator<Item=&ProgramModule<T>> {
+        self.categories.iter().flat_map(|category| category.items.iter())
+    }
+
+    pub fn len(&self) -> usize {
+        self.categories.iter().map(|category| category.items.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn filter(&self, query: &str) -> Self {
+        if query.trim().is_empty() {
+            return self.clone();
+        }
+        Self {
+            categories: self.categories
+                .iter()
+                .map(|category| category.filter(query))
+                .filter(|category| !category.items.is_empty())
+                .collect(),
+            limits: self.limits.clone(),
+            budget: self.budget,
+        }
+    }
+}
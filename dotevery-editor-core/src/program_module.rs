@@ -1,31 +1,69 @@
+use std::sync::Arc;
+
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::logic::dotevery_editor::{DotEveryEditorErrorMessage, DotEveryEditorOperationIndex, DotEveryEditorResult};
-// use crate::logic::program_module_list::ProgramModuleList;
+use crate::dotevery_editor::{DotEveryEditorErrorMessage, DotEveryEditorOperationIndex, DotEveryEditorResult};
+// use crate::program_module_list::ProgramModuleList;
 use crate::util::Isomorphism;
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ProgramModuleOption<T: 'static + Clone + PartialEq> {
     StringSign(String),
     StringInput(String),
-    ProgramModule(Option<ProgramModule<T>>),
+    ProgramModule(Option<Arc<ProgramModule<T>>>),
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ProgramModuleChildItems<T: 'static + Clone + PartialEq> {
     None,
-    BlockVertical(Vec<ProgramModule<T>>),
-    BlockHorizontal(Vec<ProgramModule<T>>),
+    BlockVertical(Vec<Arc<ProgramModule<T>>>),
+    BlockHorizontal(Vec<Arc<ProgramModule<T>>>),
+}
+
+// restrictions on a single module, kept in the document so that starter programs can fix some blocks
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ModuleLock {
+    pub no_move: bool,
+    pub no_delete: bool,
+    pub no_edit: bool,
+    // nothing can be added to or taken out of the slots and the child list
+    pub no_children: bool,
+}
+
+impl ModuleLock {
+    pub fn all() -> Self {
+        Self { no_move: true, no_delete: true, no_edit: true, no_children: true }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.no_move || self.no_delete || self.no_edit || self.no_children
+    }
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ProgramModule<Type: 'static + Clone + PartialEq = ()> {
     pub(crate) id: Uuid,
     pub(crate) parent: Option<Uuid>,
     pub options: Vec<ProgramModuleOption<Type>>,
     pub child: ProgramModuleChildItems<Type>,
     pub type_data: Type,
+    // view state, the children are hidden but still part of the program
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub collapsed: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub lock: ModuleLock,
+    // a disabled module stays in the program but should be skipped by the compiler
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub disabled: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub comment: Option<String>,
     // pub(crate) rect_changed_callback: Option<Callback<(Uuid, Rect)>>,
 }
 
@@ -48,7 +86,7 @@ impl<T: 'static + Clone + PartialEq> ProgramModule<T> {
     pub(crate) fn new_with_id(id: Uuid, mut options: Vec<ProgramModuleOption<T>>, mut child: ProgramModuleChildItems<T>, type_data: T) -> Self {
         for option in &mut options {
             if let ProgramModuleOption::ProgramModule(Some(module)) = option {
-                module.parent = Some(id);
+                Arc::make_mut(module).parent = Some(id);
             }
         }
         match &mut child {
@@ -65,6 +103,10 @@ impl<T: 'static + Clone + PartialEq> ProgramModule<T> {
             child,
             // rect_changed_callback: None,
             type_data,
+            collapsed: false,
+            lock: ModuleLock::default(),
+            disabled: false,
+            comment: None,
         }
     }
 
@@ -73,17 +115,21 @@ impl<T: 'static + Clone + PartialEq> ProgramModule<T> {
             id: self.id.clone(),
             parent: self.parent.clone(),
             options: self.options.iter().map(|option| match option {
-                ProgramModuleOption::ProgramModule(Some(module)) => ProgramModuleOption::ProgramModule(Some(module.isomorphic_transform())),
+                ProgramModuleOption::ProgramModule(Some(module)) => ProgramModuleOption::ProgramModule(Some(Arc::new(module.isomorphic_transform()))),
                 ProgramModuleOption::ProgramModule(None) => ProgramModuleOption::ProgramModule(None),
                 ProgramModuleOption::StringSign(s) => ProgramModuleOption::StringSign(s.clone()),
                 ProgramModuleOption::StringInput(s) => ProgramModuleOption::StringInput(s.clone()),
             }).collect(),
             child: match &self.child {
-                ProgramModuleChildItems::BlockVertical(list) => ProgramModuleChildItems::BlockVertical(list.iter().map(Self::isomorphic_transform).collect()),
-                ProgramModuleChildItems::BlockHorizontal(list) => ProgramModuleChildItems::BlockHorizontal(list.iter().map(Self::isomorphic_transform).collect()),
+                ProgramModuleChildItems::BlockVertical(list) => ProgramModuleChildItems::BlockVertical(list.iter().map(|module| Arc::new(module.isomorphic_transform())).collect()),
+                ProgramModuleChildItems::BlockHorizontal(list) => ProgramModuleChildItems::BlockHorizontal(list.iter().map(|module| Arc::new(module.isomorphic_transform())).collect()),
                 ProgramModuleChildItems::None => ProgramModuleChildItems::None,
             },
             type_data: Default::default(),
+            collapsed: self.collapsed,
+            lock: self.lock,
+            disabled: self.disabled,
+            comment: self.comment.clone(),
         }
     }
 
@@ -91,6 +137,7 @@ impl<T: 'static + Clone + PartialEq> ProgramModule<T> {
         if self.id == target {
             let mut module = module.clone();
             module.parent = Some(self.id);
+            let module = Arc::new(module);
             match index {
                 DotEveryEditorOperationIndex::OptionAbsolute(index) => {
                     if let Some(m) = self.options.get_mut(index) {
@@ -154,7 +201,7 @@ impl<T: 'static + Clone + PartialEq> ProgramModule<T> {
                     Err(DotEveryEditorErrorMessage::IndexOutOfRange)
                 }
                 DotEveryEditorOperationIndex::Child(index) => {
-                    fn add_inner<T: Clone + PartialEq>(list: &mut Vec<ProgramModule<T>>, index: usize, module: ProgramModule<T>) -> DotEveryEditorResult<()> {
+                    fn add_inner<T: Clone + PartialEq>(list: &mut Vec<Arc<ProgramModule<T>>>, index: usize, module: Arc<ProgramModule<T>>) -> DotEveryEditorResult<()> {
                         if list.len() >= index {
                             list.insert(index, module);
                             Ok(())
@@ -170,14 +217,12 @@ impl<T: 'static + Clone + PartialEq> ProgramModule<T> {
                 }
             }
         } else {
-            for option in &mut self.options {
-                if let ProgramModuleOption::ProgramModule(Some(m)) = option {
-                    match m.add(target, index, module) {
-                        Ok(_) => return Ok(()),
-                        Err(msg)if msg != DotEveryEditorErrorMessage::NotFound => return Err(msg),
-                        _ => {}
-                    }
-                }
+            let option = self.options.iter_mut().find_map(|option| match option {
+                ProgramModuleOption::ProgramModule(Some(m)) if m.contains(target) => Some(m),
+                _ => None,
+            });
+            if let Some(m) = option {
+                return Arc::make_mut(m).add(target, index, module);
             }
             match &mut self.child {
                 ProgramModuleChildItems::None => Err(DotEveryEditorErrorMessage::NotFound),
@@ -189,17 +234,59 @@ impl<T: 'static + Clone + PartialEq> ProgramModule<T> {
         }
     }
 
-    fn add_to_list<'a>(target: Uuid, index: DotEveryEditorOperationIndex, module: &'a ProgramModule<T>) -> impl 'a + Fn(&mut Vec<ProgramModule<T>>) -> Option<DotEveryEditorResult<()>> {
-        move |list| list.iter_mut().find_map(|m| match m.add(target, index, module) {
-            Err(DotEveryEditorErrorMessage::NotFound) => None,
-            result => Some(result),
-        })
+    pub fn can_accept(&self, index: DotEveryEditorOperationIndex) -> DotEveryEditorResult<()> {
+        let option = match index {
+            DotEveryEditorOperationIndex::OptionAbsolute(index) => self.options.get(index),
+            DotEveryEditorOperationIndex::OptionInputFiltered(index) => self.options.iter()
+                .filter(|option| !matches!(option, ProgramModuleOption::StringSign(_)))
+                .nth(index),
+            DotEveryEditorOperationIndex::OptionProgramModuleFiltered(index) => self.options.iter()
+                .filter(|option| matches!(option, ProgramModuleOption::ProgramModule(_)))
+                .nth(index),
+            DotEveryEditorOperationIndex::Child(index) => {
+                return match &self.child {
+                    ProgramModuleChildItems::None => Err(DotEveryEditorErrorMessage::ChildDoesNotExpectProgramModule),
+                    ProgramModuleChildItems::BlockVertical(list) | ProgramModuleChildItems::BlockHorizontal(list) if index <= list.len() => Ok(()),
+                    _ => Err(DotEveryEditorErrorMessage::IndexOutOfRange),
+                };
+            }
+        };
+        match option {
+            Some(ProgramModuleOption::ProgramModule(None)) => Ok(()),
+            Some(ProgramModuleOption::ProgramModule(Some(_))) => Err(DotEveryEditorErrorMessage::CanNotReplace),
+            Some(_) => Err(DotEveryEditorErrorMessage::OptionDoesNotExpectProgramModule),
+            None => Err(DotEveryEditorErrorMessage::IndexOutOfRange),
+        }
+    }
+
+    fn add_to_list<'a>(target: Uuid, index: DotEveryEditorOperationIndex, module: &'a ProgramModule<T>) -> impl 'a + Fn(&mut Vec<Arc<ProgramModule<T>>>) -> Option<DotEveryEditorResult<()>> {
+        move |list| list.iter_mut().find(|m| m.contains(target)).map(|m| Arc::make_mut(m).add(target, index, module))
+    }
+
+    // only the modules on the path to a changed module are copied, the rest of the tree stays shared
+    fn contains(&self, id: Uuid) -> bool {
+        self.get_module(id).is_ok()
     }
 
     pub fn id(&self) -> Uuid {
         self.id
     }
 
+    pub fn parent(&self) -> Option<Uuid> {
+        self.parent
+    }
+
+    pub fn set_parent(&mut self, parent: Option<Uuid>) {
+        self.parent = parent;
+    }
+
+    pub fn sign_text(&self) -> String {
+        self.options.iter().filter_map(|option| match option {
+            ProgramModuleOption::StringSign(s) => Some(s.as_str()),
+            _ => None,
+        }).collect::<Vec<_>>().join(" ")
+    }
+
     pub fn get_module(&self, id: Uuid) -> DotEveryEditorResult<&ProgramModule<T>> {
         if self.id == id {
             Ok(self)
@@ -239,16 +326,12 @@ impl<T: 'static + Clone + PartialEq> ProgramModule<T> {
                 .iter_mut()
                 .find_map(|option|
                     match option {
-                        ProgramModuleOption::ProgramModule(Some(module)) =>
-                            match module.get_module_mut(id) {
-                                Err(DotEveryEditorErrorMessage::NotFound) => None,
-                                result => Some(result),
-                            }
+                        ProgramModuleOption::ProgramModule(Some(module)) if module.contains(id) => Some(module),
                         _ => None
                     }
                 );
-            if let Some(result) = result {
-                result
+            if let Some(module) = result {
+                Arc::make_mut(module).get_module_mut(id)
             } else {
                 match &mut self.child {
                     ProgramModuleChildItems::None => Err(DotEveryEditorErrorMessage::NotFound),
@@ -288,7 +371,7 @@ impl<T: 'static + Clone + PartialEq> ProgramModule<T> {
         result
     }
 
-    fn get_module_from_list(id: Uuid, list: &Vec<ProgramModule<T>>) -> Option<DotEveryEditorResult<&ProgramModule<T>>> {
+    fn get_module_from_list(id: Uuid, list: &[Arc<ProgramModule<T>>]) -> Option<DotEveryEditorResult<&ProgramModule<T>>> {
         list.iter()
             .find_map(
                 |module| {
@@ -299,15 +382,10 @@ impl<T: 'static + Clone + PartialEq> ProgramModule<T> {
                 })
     }
 
-    fn get_module_mut_from_list(id: Uuid, list: &mut Vec<ProgramModule<T>>) -> Option<DotEveryEditorResult<&mut ProgramModule<T>>> {
+    fn get_module_mut_from_list(id: Uuid, list: &mut [Arc<ProgramModule<T>>]) -> Option<DotEveryEditorResult<&mut ProgramModule<T>>> {
         list.iter_mut()
-            .find_map(
-                |module| {
-                    match module.get_module_mut(id) {
-                        Err(DotEveryEditorErrorMessage::NotFound) => None,
-                        result => Some(result),
-                    }
-                })
+            .find(|module| module.contains(id))
+            .map(|module| Arc::make_mut(module).get_module_mut(id))
     }
 
     pub fn remove(&mut self, id: Uuid) -> DotEveryEditorResult<()> {
@@ -322,15 +400,10 @@ impl<T: 'static + Clone + PartialEq> ProgramModule<T> {
             self.options[index] = ProgramModuleOption::ProgramModule(None);
             return Ok(());
         }
-        let result = self.options.iter_mut().find_map(|option|
-            if let ProgramModuleOption::ProgramModule(Some(module)) = option {
-                match module.remove(id) {
-                    Err(DotEveryEditorErrorMessage::NotFound) => None,
-                    result => Some(result),
-                }
-            } else {
-                None
-            });
+        let result = self.options.iter_mut().find_map(|option| match option {
+            ProgramModuleOption::ProgramModule(Some(module)) if module.contains(id) => Some(Arc::make_mut(module).remove(id)),
+            _ => None,
+        });
         if let Some(result) = result {
             debug_assert_ne!(result, Err(DotEveryEditorErrorMessage::NotFound));
             return result;
@@ -349,16 +422,13 @@ impl<T: 'static + Clone + PartialEq> ProgramModule<T> {
         }
     }
 
-    fn remove_module_from_list(id: Uuid) -> impl Fn(&mut Vec<ProgramModule<T>>) -> Option<DotEveryEditorResult<()>> {
+    fn remove_module_from_list(id: Uuid) -> impl Fn(&mut Vec<Arc<ProgramModule<T>>>) -> Option<DotEveryEditorResult<()>> {
         move |list| {
             if let Some(index) = list.iter().position(|module| module.id == id) {
                 list.remove(index);
                 Some(Ok(()))
             } else {
-                list.iter_mut().find_map(|module| match module.remove(id) {
-                    Err(DotEveryEditorErrorMessage::NotFound) => None,
-                    result => Some(result),
-                })
+                list.iter_mut().find(|module| module.contains(id)).map(|module| Arc::make_mut(module).remove(id))
             }
         }
     }
@@ -366,7 +436,7 @@ impl<T: 'static + Clone + PartialEq> ProgramModule<T> {
     pub fn deep_clone(&self) -> Self {
         let options = self.options.iter().map(|option| {
             match option {
-                ProgramModuleOption::ProgramModule(Some(module)) => ProgramModuleOption::ProgramModule(Some(module.deep_clone())),
+                ProgramModuleOption::ProgramModule(Some(module)) => ProgramModuleOption::ProgramModule(Some(Arc::new(module.deep_clone()))),
                 other => other.clone()
             }
         }).collect();
@@ -379,11 +449,15 @@ impl<T: 'static + Clone + PartialEq> ProgramModule<T> {
         };
 
         let mut new_module = Self::new(options, child, self.type_data.clone());
+        new_module.collapsed = self.collapsed;
+        new_module.lock = self.lock;
+        new_module.disabled = self.disabled;
+        new_module.comment = self.comment.clone();
 
         let id = new_module.id;
         for option in &mut new_module.options {
             if let ProgramModuleOption::ProgramModule(Some(module)) = option {
-                module.parent = Some(id);
+                Arc::make_mut(module).parent = Some(id);
             }
         }
         match &mut new_module.child {
@@ -396,16 +470,61 @@ impl<T: 'static + Clone + PartialEq> ProgramModule<T> {
         new_module
     }
 
-    fn set_parent_to_list(id: Uuid) -> impl Fn(&mut Vec<ProgramModule<T>>) {
-        move |list| list.iter_mut().for_each(|module| module.parent = Some(id))
+    // the lock belongs to the original placement, duplicates can be edited freely
+    pub fn unlocked(mut self) -> Self {
+        self.unlock();
+        self
+    }
+
+    fn unlock(&mut self) {
+        self.lock = ModuleLock::default();
+        for option in &mut self.options {
+            if let ProgramModuleOption::ProgramModule(Some(module)) = option {
+                Arc::make_mut(module).unlock();
+            }
+        }
+        if let ProgramModuleChildItems::BlockVertical(list) | ProgramModuleChildItems::BlockHorizontal(list) = &mut self.child {
+            for module in list.iter_mut() {
+                Arc::make_mut(module).unlock();
+            }
+        }
+    }
+
+    // whether this module or one of its descendants has the lock
+    pub fn any_lock(&self, locked: impl Copy + Fn(&ModuleLock) -> bool) -> bool {
+        if locked(&self.lock) { return true; }
+        let options = self.options.iter().any(|option| match option {
+            ProgramModuleOption::ProgramModule(Some(module)) => module.any_lock(locked),
+            _ => false,
+        });
+        options || match &self.child {
+            ProgramModuleChildItems::None => false,
+            ProgramModuleChildItems::BlockVertical(list) | ProgramModuleChildItems::BlockHorizontal(list) => list.iter().any(|module| module.any_lock(locked)),
+        }
+    }
+
+    // this module and all of its descendants
+    pub fn module_count(&self) -> usize {
+        let options = self.options.iter().map(|option| match option {
+            ProgramModuleOption::ProgramModule(Some(module)) => module.module_count(),
+            _ => 0,
+        }).sum::<usize>();
+        let child = match &self.child {
+            ProgramModuleChildItems::None => 0,
+            ProgramModuleChildItems::BlockVertical(list) | ProgramModuleChildItems::BlockHorizontal(list) => list.iter().map(|module| module.module_count()).sum(),
+        };
+        1 + options + child
+    }
+
+    fn set_parent_to_list(id: Uuid) -> impl Fn(&mut Vec<Arc<ProgramModule<T>>>) {
+        move |list| list.iter_mut().for_each(|module| Arc::make_mut(module).parent = Some(id))
     }
 
-    fn deep_clone_list(list: &Vec<ProgramModule<T>>) -> Vec<ProgramModule<T>> {
-        list.iter().map(ProgramModule::deep_clone).collect()
+    fn deep_clone_list(list: &[Arc<ProgramModule<T>>]) -> Vec<Arc<ProgramModule<T>>> {
+        list.iter().map(|module| Arc::new(module.deep_clone())).collect()
     }
 
-    fn list_isomorphisms(input: (&Vec<ProgramModule<T>>, &Vec<ProgramModule<T>>)) -> bool {
-        let (a, b) = input;
+    fn list_isomorphisms(a: &[Arc<ProgramModule<T>>], b: &[Arc<ProgramModule<T>>]) -> bool {
         a.len() == b.len() && b.iter().zip(b).all(|(a, b)| a.isomorphisms(b))
     }
 }
@@ -457,14 +576,14 @@ impl<T: 'static + Clone + PartialEq> Isomorphism for ProgramModule<T> {
             }
             ProgramModuleChildItems::BlockVertical(list) => {
                 if let ProgramModuleChildItems::BlockVertical(other) = &other.child {
-                    Self::list_isomorphisms((list, other))
+                    Self::list_isomorphisms(list, other)
                 } else {
                     false
                 }
             }
             ProgramModuleChildItems::BlockHorizontal(list) => {
                 if let ProgramModuleChildItems::BlockHorizontal(other) = &other.child {
-                    Self::list_isomorphisms((list, other))
+                    Self::list_isomorphisms(list, other)
                 } else {
                     false
                 }
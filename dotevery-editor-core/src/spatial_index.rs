@@ -0,0 +1,104 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use uuid::Uuid;
+
+use crate::util::Rect;
+
+const DEFAULT_CELL_SIZE: f64 = 64.0;
+
+#[derive(Clone, Debug)]
+pub struct SpatialIndex {
+    cell_size: f64,
+    rects: HashMap<Uuid, Rect>,
+    cells: HashMap<(i64, i64), HashSet<Uuid>>,
+}
+
+impl Default for SpatialIndex {
+    fn default() -> Self {
+        Self::new(DEFAULT_CELL_SIZE)
+    }
+}
+
+impl SpatialIndex {
+    pub fn new(cell_size: f64) -> Self {
+        Self {
+            cell_size,
+            rects: HashMap::new(),
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.rects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    pub fn get(&self, id: &Uuid) -> Option<&Rect> {
+        self.rects.get(id)
+    }
+
+    pub fn insert(&mut self, id: Uuid, rect: Rect) {
+        self.remove(&id);
+        for cell in self.cells_in(rect.x, rect.y, rect.x + rect.w, rect.y + rect.h) {
+            self.cells.entry(cell).or_default().insert(id);
+        }
+        self.rects.insert(id, rect);
+    }
+
+    pub fn remove(&mut self, id: &Uuid) -> Option<Rect> {
+        let rect = self.rects.remove(id)?;
+        for cell in self.cells_in(rect.x, rect.y, rect.x + rect.w, rect.y + rect.h) {
+            if let Some(set) = self.cells.get_mut(&cell) {
+                set.remove(id);
+                if set.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+        Some(rect)
+    }
+
+    pub fn clear(&mut self) {
+        self.rects.clear();
+        self.cells.clear();
+    }
+
+    pub fn query(&self, x: f64, y: f64) -> Vec<Uuid> {
+        self.nearest(x, y, 0f64)
+    }
+
+    pub fn nearest(&self, x: f64, y: f64, max_distance: f64) -> Vec<Uuid> {
+        let max_distance = f64::max(max_distance, 0f64);
+        let mut candidates = self.cells_in(x - max_distance, y - max_distance, x + max_distance, y + max_distance)
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .filter_map(|id| {
+                let rect = &self.rects[id];
+                let distance = rect.distance(x, y);
+                if distance <= max_distance {
+                    Some((*id, distance, rect.area()))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_by(|(_, a_distance, a_area), (_, b_distance, b_area)| {
+            a_distance.partial_cmp(b_distance).unwrap_or(Ordering::Equal)
+                .then(a_area.partial_cmp(b_area).unwrap_or(Ordering::Equal))
+        });
+        candidates.into_iter().map(|(id, _, _)| id).collect()
+    }
+
+    fn cells_in(&self, left: f64, top: f64, right: f64, bottom: f64) -> impl Iterator<Item=(i64, i64)> {
+        let cell_size = self.cell_size;
+        let (left, right) = ((left / cell_size).floor() as i64, (right / cell_size).floor() as i64);
+        let (top, bottom) = ((top / cell_size).floor() as i64, (bottom / cell_size).floor() as i64);
+        (left..=right).flat_map(move |x| (top..=bottom).map(move |y| (x, y)))
+    }
+}
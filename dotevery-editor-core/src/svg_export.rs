@@ -0,0 +1,154 @@
+use crate::dotevery_editor::DotEveryEditor;
+use crate::layout::{block_path, hexagon_path, rounded_path, EditorLayout, LayoutMetrics, ModuleLayout, OptionLayout, TextMetrics};
+use crate::program_module::{ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
+use crate::util::Rect;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ProgramModuleShape {
+    Block,
+    Rounded,
+    Hexagonal,
+}
+
+// how the modules of a type are drawn, shared by the editor and the exported images
+pub trait ModuleStyle: 'static + Clone + PartialEq {
+    fn color(&self) -> Option<String> {
+        None
+    }
+
+    fn shape(&self) -> ProgramModuleShape {
+        ProgramModuleShape::Block
+    }
+}
+
+impl ModuleStyle for () {}
+
+// everything is written as inline styles, so the image looks the same without the editor stylesheet
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgExportStyle {
+    pub font: String,
+    pub margin: f64,
+    pub background: Option<String>,
+    pub fill: String,
+    pub stroke: String,
+    pub text: String,
+    pub input_fill: String,
+    pub input_stroke: String,
+    pub slot_fill: String,
+    pub slot_stroke: String,
+}
+
+impl Default for SvgExportStyle {
+    fn default() -> Self {
+        Self {
+            font: "14px sans-serif".to_string(),
+            margin: 8f64,
+            background: None,
+            fill: "#ddd".to_string(),
+            stroke: "rgba(0,0,0,0.3)".to_string(),
+            text: "#000".to_string(),
+            input_fill: "#fff".to_string(),
+            input_stroke: "#888".to_string(),
+            slot_fill: "rgba(255,255,255,0.6)".to_string(),
+            slot_stroke: "#aaa".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgExport {
+    pub svg: String,
+    pub width: f64,
+    pub height: f64,
+}
+
+pub fn export_svg<T: ModuleStyle>(editor: &DotEveryEditor<T>, metrics: &LayoutMetrics, text: &dyn TextMetrics, style: &SvgExportStyle) -> SvgExport {
+    let layout = EditorLayout::with_text_metrics(editor, metrics, text);
+    let mut body = String::new();
+    for (layout, module) in layout.list.modules.iter().zip(&editor.list) {
+        write_module(&mut body, layout, module, metrics, style);
+    }
+    document(&layout.bounds, &body, style)
+}
+
+// a single module with its descendants, placed at the origin
+pub fn export_module_svg<T: ModuleStyle>(module: &ProgramModule<T>, metrics: &LayoutMetrics, text: &dyn TextMetrics, style: &SvgExportStyle) -> SvgExport {
+    let layout = ModuleLayout::with_text_metrics(module, metrics, text, 0f64, 0f64);
+    let mut body = String::new();
+    write_module(&mut body, &layout, module, metrics, style);
+    document(&layout.rect, &body, style)
+}
+
+fn document(bounds: &Rect, body: &str, style: &SvgExportStyle) -> SvgExport {
+    let margin = style.margin;
+    let (x, y, w, h) = (bounds.x - margin, bounds.y - margin, bounds.w + margin * 2f64, bounds.h + margin * 2f64);
+    let background = match &style.background {
+        Some(color) => format!(r#"<rect x="{}" y="{}" width="{}" height="{}" style="fill:{}"/>"#, x, y, w, h, escape(color)),
+        None => String::new(),
+    };
+    let svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}" style="font:{}">{}{}</svg>"#,
+        w, h, x, y, w, h, escape(&style.font), background, body
+    );
+    SvgExport { svg, width: w, height: h }
+}
+
+fn write_module<T: ModuleStyle>(out: &mut String, layout: &ModuleLayout, module: &ProgramModule<T>, metrics: &LayoutMetrics, style: &SvgExportStyle) {
+    let path = if layout.nested {
+        match module.type_data.shape() {
+            ProgramModuleShape::Hexagonal => hexagon_path(&layout.rect),
+            _ => rounded_path(&layout.rect),
+        }
+    } else {
+        block_path(layout, metrics)
+    };
+    let fill = module.type_data.color().unwrap_or_else(|| style.fill.clone());
+    out.push_str("<g>");
+    out.push_str(&format!(r#"<path d="{}" style="fill:{};stroke:{};stroke-width:1"/>"#, path, escape(&fill), escape(&style.stroke)));
+    if let (Some(rect), Some(comment)) = (&layout.comment, &module.comment) {
+        out.push_str(&text_element(rect.x, rect.y + rect.h / 2f64, comment, &style.text));
+    }
+    for (option_layout, option) in layout.options.iter().zip(&module.options) {
+        match (option_layout, option) {
+            (OptionLayout::Sign(rect), ProgramModuleOption::StringSign(s)) => {
+                out.push_str(&text_element(rect.x, rect.y + rect.h / 2f64, s, &style.text));
+            }
+            (OptionLayout::Input(rect), ProgramModuleOption::StringInput(s)) => {
+                out.push_str(&format!(
+                    r#"<rect x="{}" y="{}" width="{}" height="{}" rx="4" style="fill:{};stroke:{}"/>"#,
+                    rect.x, rect.y, rect.w, rect.h, escape(&style.input_fill), escape(&style.input_stroke)
+                ));
+                out.push_str(&text_element(rect.x + metrics.gap, rect.y + rect.h / 2f64, s, &style.text));
+            }
+            (OptionLayout::Module(nested), ProgramModuleOption::ProgramModule(Some(nested_module))) => write_module(out, nested, nested_module, metrics, style),
+            (option_layout, _) => {
+                out.push_str(&format!(r#"<path d="{}" style="fill:{};stroke:{}"/>"#, rounded_path(option_layout.rect()), escape(&style.slot_fill), escape(&style.slot_stroke)));
+            }
+        }
+    }
+    if let (Some(child), ProgramModuleChildItems::BlockVertical(list)) | (Some(child), ProgramModuleChildItems::BlockHorizontal(list)) = (&layout.child, &module.child) {
+        for (layout, module) in child.modules.iter().zip(list) {
+            write_module(out, layout, module, metrics, style);
+        }
+    }
+    out.push_str("</g>");
+}
+
+fn text_element(x: f64, y: f64, s: &str, color: &str) -> String {
+    format!(r#"<text x="{}" y="{}" style="fill:{};dominant-baseline:central">{}</text>"#, x, y, escape(color), escape(s))
+}
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::MessageCatalog;
+
+use crate::palette::{Palette, PaletteCategory};
+use crate::program_module::{ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
+
+pub trait FromTemplateTag: Sized {
+    fn from_template_tag(tag: &str) -> Option<Self>;
+
+    // the tag of the type without the value of tuple variants, sign translations are registered under it
+    fn type_tag(&self) -> Option<String> {
+        None
+    }
+}
+
+impl FromTemplateTag for () {
+    fn from_template_tag(_tag: &str) -> Option<Self> {
+        Some(())
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum TemplateErrorMessage {
+    JsonParseError(String),
+    TomlParseError(String),
+    UnknownTypeTag(String),
+}
+
+pub type TemplateResult<T> = Result<T, TemplateErrorMessage>;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ModuleOptionTemplate {
+    Sign {
+        text: String,
+        // locale -> displayed text, the program keeps `text`
+        #[serde(default)]
+        locales: HashMap<String, String>,
+    },
+    Input {
+        #[serde(default)]
+        value: String,
+    },
+    Slot,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChildLayoutTemplate {
+    None,
+    Vertical,
+    Horizontal,
+}
+
+impl Default for ChildLayoutTemplate {
+    fn default() -> Self {
+        ChildLayoutTemplate::None
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModuleTemplate {
+    #[serde(rename = "type")]
+    pub type_tag: String,
+    #[serde(default)]
+    pub options: Vec<ModuleOptionTemplate>,
+    #[serde(default)]
+    pub child: ChildLayoutTemplate,
+    // the most modules of this type in the program
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl ModuleTemplate {
+    pub fn instantiate<T: 'static + Clone + PartialEq + FromTemplateTag>(&self) -> TemplateResult<ProgramModule<T>> {
+        let type_data = T::from_template_tag(&self.type_tag)
+            .ok_or_else(|| TemplateErrorMessage::UnknownTypeTag(self.type_tag.clone()))?;
+        Ok(self.instantiate_with(type_data))
+    }
+
+    pub fn instantiate_with<T: 'static + Clone + PartialEq>(&self, type_data: T) -> ProgramModule<T> {
+        let options = self.options.iter().map(|option| match option {
+            ModuleOptionTemplate::Sign { text, .. } => ProgramModuleOption::StringSign(text.clone()),
+            ModuleOptionTemplate::Input { value } => ProgramModuleOption::StringInput(value.clone()),
+            ModuleOptionTemplate::Slot => ProgramModuleOption::ProgramModule(None),
+        }).collect();
+        let child = match self.child {
+            ChildLayoutTemplate::None => ProgramModuleChildItems::None,
+            ChildLayoutTemplate::Vertical => ProgramModuleChildItems::BlockVertical(Vec::new()),
+            ChildLayoutTemplate::Horizontal => ProgramModuleChildItems::BlockHorizontal(Vec::new()),
+        };
+        ProgramModule::new(options, child, type_data)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PaletteCategoryTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub color: String,
+    #[serde(default)]
+    pub modules: Vec<ModuleTemplate>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PaletteTemplate {
+    #[serde(default)]
+    pub categories: Vec<PaletteCategoryTemplate>,
+    #[serde(default)]
+    pub budget: Option<usize>,
+}
+
+impl PaletteTemplate {
+    pub fn from_json(source: &str) -> TemplateResult<Self> {
+        serde_json::from_str(source).map_err(|err| TemplateErrorMessage::JsonParseError(err.to_string()))
+    }
+
+    pub fn from_toml(source: &str) -> TemplateResult<Self> {
+        toml::from_str(source).map_err(|err| TemplateErrorMessage::TomlParseError(err.to_string()))
+    }
+
+    pub fn parse(source: &str) -> TemplateResult<Self> {
+        if source.trim_start().starts_with('{') {
+            Self::from_json(source)
+        } else {
+            Self::from_toml(source)
+        }
+    }
+
+    // registers the localized sign texts of all modules under their type tags
+    pub fn register_signs(&self, catalog: &mut MessageCatalog) {
+        for module in self.categories.iter().flat_map(|category| category.modules.iter()) {
+            let tag = module.type_tag.split(':').next().unwrap_or_default();
+            for (i, option) in module.options.iter().enumerate() {
+                if let ModuleOptionTemplate::Sign { locales, .. } = option {
+                    for (locale, localized) in locales {
+                        catalog.insert_sign(tag, i, locale, localized);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn find(&self, type_tag: &str) -> Option<&ModuleTemplate> {
+        self.categories
+            .iter()
+            .flat_map(|category| category.modules.iter())
+            .find(|module| module.type_tag == type_tag)
+    }
+
+    pub fn to_palette<T: 'static + Clone + PartialEq + FromTemplateTag>(&self) -> TemplateResult<Palette<T>> {
+        let mut limits = Vec::new();
+        let categories = self.categories.iter().map(|category| {
+            let items = category.modules
+                .iter()
+                .map(|template| {
+                    let module = template.instantiate::<T>()?;
+                    if let Some(limit) = template.limit {
+                        limits.push((module.type_data.clone(), limit));
+                    }
+                    Ok(module)
+                })
+                .collect::<TemplateResult<Vec<_>>>()?;
+            Ok(PaletteCategory::new(category.name.clone(), category.color.clone(), items))
+        }).collect::<TemplateResult<Vec<_>>>()?;
+        let mut palette = Palette::new(categories);
+        for (type_data, limit) in limits {
+            palette.set_limit(type_data, Some(limit));
+        }
+        palette.budget = self.budget;
+        Ok(palette)
+    }
+}
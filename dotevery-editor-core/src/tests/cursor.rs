@@ -0,0 +1,166 @@
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::cursor::{CursorKey, CursorOperation, CursorPosition, EditorCursor};
+use crate::dotevery_editor::{DotEveryEditor, DotEveryEditorOperationIndex};
+use crate::program_module::{ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
+
+fn editor() -> DotEveryEditor<i32> {
+    let print = ProgramModule::new(vec![ProgramModuleOption::StringSign("print".to_string()), ProgramModuleOption::ProgramModule(None)], ProgramModuleChildItems::None, 0);
+    let literal = ProgramModule::new(vec![ProgramModuleOption::StringInput("1".to_string())], ProgramModuleChildItems::None, 1);
+    let case = ProgramModule::new(
+        vec![ProgramModuleOption::StringSign("case".to_string()), ProgramModuleOption::ProgramModule(Some(Arc::new(literal)))],
+        ProgramModuleChildItems::BlockVertical(vec![Arc::new(print.deep_clone())]),
+        2);
+    DotEveryEditor::new(vec![print, case])
+}
+
+fn press(cursor: &mut EditorCursor, editor: &mut DotEveryEditor<i32>, keys: &[CursorKey]) {
+    for key in keys {
+        for operation in cursor.handle_key(editor, *key) {
+            match operation {
+                CursorOperation::Move(src, target, index, module) => editor.move_module(src, target, index, &module).unwrap(),
+                CursorOperation::Remove(id) => editor.remove(id).unwrap(),
+            }
+        }
+    }
+}
+
+#[test]
+fn cursor_positions_test() {
+    let editor = editor();
+    let print = editor.list[0].id();
+    let case = editor.list[1].id();
+    let literal = editor.list[1].get_modules_by_type(&1)[0].id();
+    let inner = editor.list[1].get_modules_by_type(&0)[0].id();
+    assert_eq!(EditorCursor::positions(&editor), vec![
+        CursorPosition::Module(print),
+        CursorPosition::Slot(print, DotEveryEditorOperationIndex::OptionAbsolute(1)),
+        CursorPosition::Module(case),
+        CursorPosition::Module(literal),
+        CursorPosition::Input(literal, 0),
+        CursorPosition::Module(inner),
+        CursorPosition::Slot(inner, DotEveryEditorOperationIndex::OptionAbsolute(1)),
+        CursorPosition::Slot(case, DotEveryEditorOperationIndex::Child(1)),
+        CursorPosition::Slot(Uuid::nil(), DotEveryEditorOperationIndex::Child(2)),
+    ]);
+}
+
+#[test]
+fn cursor_navigation_test() {
+    let mut editor = editor();
+    let print = editor.list[0].id();
+    let case = editor.list[1].id();
+    let literal = editor.list[1].get_modules_by_type(&1)[0].id();
+    let mut cursor = EditorCursor::new();
+    assert_eq!(cursor.position(), None);
+
+    press(&mut cursor, &mut editor, &[CursorKey::Up]);
+    assert_eq!(cursor.position(), Some(CursorPosition::Module(print)));
+    press(&mut cursor, &mut editor, &[CursorKey::Up]);
+    assert_eq!(cursor.position(), Some(CursorPosition::Module(print)));
+    press(&mut cursor, &mut editor, &[CursorKey::Right]);
+    assert_eq!(cursor.position(), Some(CursorPosition::Slot(print, DotEveryEditorOperationIndex::OptionAbsolute(1))));
+    press(&mut cursor, &mut editor, &[CursorKey::Right]);
+    assert_eq!(cursor.position(), Some(CursorPosition::Slot(print, DotEveryEditorOperationIndex::OptionAbsolute(1))));
+    press(&mut cursor, &mut editor, &[CursorKey::Down, CursorKey::Down, CursorKey::Down]);
+    assert_eq!(cursor.position(), Some(CursorPosition::Input(literal, 0)));
+    press(&mut cursor, &mut editor, &[CursorKey::Left]);
+    assert_eq!(cursor.position(), Some(CursorPosition::Module(literal)));
+    press(&mut cursor, &mut editor, &[CursorKey::Left]);
+    assert_eq!(cursor.position(), Some(CursorPosition::Module(case)));
+    press(&mut cursor, &mut editor, &[CursorKey::Left]);
+    assert_eq!(cursor.position(), Some(CursorPosition::Module(case)));
+    press(&mut cursor, &mut editor, &[CursorKey::Down; 10]);
+    assert_eq!(cursor.position(), Some(CursorPosition::Slot(Uuid::nil(), DotEveryEditorOperationIndex::Child(2))));
+}
+
+#[test]
+fn cursor_edit_input_test() {
+    let mut editor = editor();
+    let literal = editor.list[1].get_modules_by_type(&1)[0].id();
+    let mut cursor = EditorCursor::new();
+    press(&mut cursor, &mut editor, &[CursorKey::Down, CursorKey::Enter]);
+    assert!(!cursor.editing());
+
+    cursor.set_position(Some(CursorPosition::Input(literal, 0)));
+    press(&mut cursor, &mut editor, &[CursorKey::Enter]);
+    assert!(cursor.editing());
+    press(&mut cursor, &mut editor, &[CursorKey::Up, CursorKey::Delete]);
+    assert!(cursor.editing());
+    assert_eq!(cursor.position(), Some(CursorPosition::Input(literal, 0)));
+    press(&mut cursor, &mut editor, &[CursorKey::Enter]);
+    assert!(!cursor.editing());
+}
+
+#[test]
+fn cursor_pick_up_and_drop_test() {
+    let mut editor = editor();
+    let print = editor.list[0].id();
+    let case = editor.list[1].id();
+    let mut cursor = EditorCursor::new();
+
+    cursor.set_position(Some(CursorPosition::Module(print)));
+    press(&mut cursor, &mut editor, &[CursorKey::PickUp]);
+    assert_eq!(cursor.holding(), Some(print));
+    press(&mut cursor, &mut editor, &[CursorKey::Right, CursorKey::Drop]);
+    assert_eq!(cursor.holding(), Some(print));
+    assert_eq!(editor.list.len(), 2);
+
+    cursor.set_position(Some(CursorPosition::Slot(case, DotEveryEditorOperationIndex::Child(1))));
+    press(&mut cursor, &mut editor, &[CursorKey::Drop]);
+    assert_eq!(cursor.holding(), None);
+    assert!(editor.get_module(print).is_err());
+    assert_eq!(editor.list.iter().map(|module| module.type_data).collect::<Vec<_>>(), vec![2]);
+    let moved = match &editor.list[0].child {
+        ProgramModuleChildItems::BlockVertical(list) => list[1].id(),
+        _ => unreachable!(),
+    };
+    assert_eq!(cursor.position(), Some(CursorPosition::Module(moved)));
+
+    cursor.set_position(Some(CursorPosition::Module(case)));
+    press(&mut cursor, &mut editor, &[CursorKey::PickUp, CursorKey::Down, CursorKey::Down, CursorKey::Drop]);
+    assert_eq!(cursor.holding(), Some(case));
+    assert_eq!(editor.list.len(), 1);
+    press(&mut cursor, &mut editor, &[CursorKey::Escape]);
+    assert_eq!(cursor.holding(), None);
+}
+
+#[test]
+fn cursor_drop_before_module_test() {
+    let mut editor = editor();
+    let case = editor.list[1].id();
+    let inner = editor.list[1].get_modules_by_type(&0)[0].id();
+    let mut cursor = EditorCursor::new();
+
+    cursor.set_position(Some(CursorPosition::Module(case)));
+    press(&mut cursor, &mut editor, &[CursorKey::PickUp]);
+    cursor.set_position(Some(CursorPosition::Module(editor.list[0].id())));
+    press(&mut cursor, &mut editor, &[CursorKey::Drop]);
+    assert_eq!(editor.list.iter().map(|module| module.type_data).collect::<Vec<_>>(), vec![2, 0]);
+    assert!(editor.get_module(inner).is_err());
+}
+
+#[test]
+fn cursor_delete_test() {
+    let mut editor = editor();
+    let print = editor.list[0].id();
+    let case = editor.list[1].id();
+    let literal = editor.list[1].get_modules_by_type(&1)[0].id();
+    let mut cursor = EditorCursor::new();
+
+    cursor.set_position(Some(CursorPosition::Module(literal)));
+    press(&mut cursor, &mut editor, &[CursorKey::Delete]);
+    assert!(editor.get_module(literal).is_err());
+    assert_eq!(cursor.position(), Some(CursorPosition::Slot(case, DotEveryEditorOperationIndex::OptionAbsolute(1))));
+
+    cursor.set_position(Some(CursorPosition::Module(print)));
+    press(&mut cursor, &mut editor, &[CursorKey::Delete]);
+    assert_eq!(cursor.position(), Some(CursorPosition::Module(case)));
+    press(&mut cursor, &mut editor, &[CursorKey::Delete]);
+    assert!(editor.list.is_empty());
+    assert_eq!(cursor.position(), Some(CursorPosition::Slot(Uuid::nil(), DotEveryEditorOperationIndex::Child(0))));
+    press(&mut cursor, &mut editor, &[CursorKey::Delete, CursorKey::Up]);
+    assert_eq!(cursor.position(), Some(CursorPosition::Slot(Uuid::nil(), DotEveryEditorOperationIndex::Child(0))));
+}
@@ -0,0 +1,249 @@
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::dotevery_editor::{DotEveryEditor, DotEveryEditorErrorMessage, DotEveryEditorOperationIndex};
+use crate::program_module::{ModuleLock, ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
+use crate::tests::block;
+use crate::util::Isomorphism;
+
+#[test]
+fn dotevery_editor_add_test() {
+    let mut editor = DotEveryEditor::new(vec![block(0)]);
+    let root = editor.list[0].id();
+    assert_eq!(editor.add(Uuid::nil(), DotEveryEditorOperationIndex::Child(0), &block(1)), Ok(()));
+    assert_eq!(editor.add(editor.id(), DotEveryEditorOperationIndex::Child(2), &block(2)), Ok(()));
+    assert_eq!(editor.list.iter().map(|module| module.type_data).collect::<Vec<_>>(), vec![1, 0, 2]);
+    assert_eq!(editor.add(editor.id(), DotEveryEditorOperationIndex::Child(4), &block(3)), Err(DotEveryEditorErrorMessage::IndexOutOfRange));
+    assert_eq!(editor.add(editor.id(), DotEveryEditorOperationIndex::OptionAbsolute(0), &block(3)), Err(DotEveryEditorErrorMessage::IndexOutOfRange));
+
+    assert_eq!(editor.add(root, DotEveryEditorOperationIndex::Child(0), &block(4)), Ok(()));
+    assert_eq!(editor.add(root, DotEveryEditorOperationIndex::OptionProgramModuleFiltered(0), &block(5)), Ok(()));
+    let root = editor.get_module(root).unwrap();
+    assert_eq!(root.get_modules_by_type(&4).len(), 1);
+    assert_eq!(root.get_modules_by_type(&5).len(), 1);
+    assert_eq!(editor.add(Uuid::new_v4(), DotEveryEditorOperationIndex::Child(0), &block(6)), Err(DotEveryEditorErrorMessage::NotFound));
+}
+
+#[test]
+fn dotevery_editor_copy_and_remove_test() {
+    let mut editor = DotEveryEditor::new(vec![block(0), block(1)]);
+    let first = editor.list[0].id();
+    let second = editor.list[1].id();
+    assert_eq!(editor.add(first, DotEveryEditorOperationIndex::Child(0), &block(2)), Ok(()));
+
+    assert_eq!(editor.copy(first, second, DotEveryEditorOperationIndex::Child(0)), Ok(()));
+    let copied = match &editor.get_module(second).unwrap().child {
+        ProgramModuleChildItems::BlockVertical(list) => list[0].clone(),
+        _ => unreachable!(),
+    };
+    assert_ne!(copied.id(), first);
+    assert!(copied.isomorphisms(editor.get_module(first).unwrap()));
+
+    assert_eq!(editor.remove(first), Ok(()));
+    assert!(editor.get_module(first).is_err());
+    assert_eq!(editor.remove(copied.id()), Ok(()));
+    assert_eq!(editor.get_modules_by_type(&2).len(), 0);
+    assert_eq!(editor.remove(first), Err(DotEveryEditorErrorMessage::NotFound));
+    assert_eq!(editor.list.len(), 1);
+}
+
+#[test]
+fn dotevery_editor_can_add_and_move_test() {
+    let mut editor = DotEveryEditor::new(vec![block(0), block(1)]);
+    let first = editor.list[0].id();
+    let second = editor.list[1].id();
+    assert_eq!(editor.can_add(Uuid::nil(), DotEveryEditorOperationIndex::Child(2), &block(2)), Ok(()));
+    assert_eq!(editor.can_add(Uuid::nil(), DotEveryEditorOperationIndex::Child(3), &block(2)), Err(DotEveryEditorErrorMessage::IndexOutOfRange));
+    assert_eq!(editor.can_add(first, DotEveryEditorOperationIndex::OptionAbsolute(0), &block(2)), Ok(()));
+    assert_eq!(editor.can_add(first, DotEveryEditorOperationIndex::OptionAbsolute(1), &block(2)), Err(DotEveryEditorErrorMessage::IndexOutOfRange));
+    assert_eq!(editor.can_add(first, DotEveryEditorOperationIndex::Child(1), &block(2)), Err(DotEveryEditorErrorMessage::IndexOutOfRange));
+    assert_eq!(editor.can_add(Uuid::new_v4(), DotEveryEditorOperationIndex::Child(0), &block(2)), Err(DotEveryEditorErrorMessage::NotFound));
+    let dragging = editor.list[0].clone();
+    assert_eq!(editor.can_add(first, DotEveryEditorOperationIndex::Child(0), &dragging), Err(DotEveryEditorErrorMessage::CanNotAddIntoItself));
+
+    assert_eq!(editor.add(first, DotEveryEditorOperationIndex::OptionAbsolute(0), &block(2)), Ok(()));
+    assert_eq!(editor.can_add(first, DotEveryEditorOperationIndex::OptionAbsolute(0), &block(3)), Err(DotEveryEditorErrorMessage::CanNotReplace));
+    assert_eq!(editor.can_add(first, DotEveryEditorOperationIndex::OptionProgramModuleFiltered(0), &block(3)), Err(DotEveryEditorErrorMessage::CanNotReplace));
+
+    let moved = editor.get_module(second).unwrap().deep_clone();
+    assert_eq!(editor.move_module(second, first, DotEveryEditorOperationIndex::Child(0), &moved), Ok(()));
+    assert_eq!(editor.list.len(), 1);
+    assert!(editor.get_module(second).is_err());
+    assert!(editor.get_module(moved.id()).is_ok());
+
+    let failed = block(4);
+    assert_eq!(editor.move_module(moved.id(), first, DotEveryEditorOperationIndex::OptionAbsolute(0), &failed), Err(DotEveryEditorErrorMessage::CanNotReplace));
+    assert!(editor.get_module(moved.id()).is_ok());
+    let inner = editor.get_module(moved.id()).unwrap().deep_clone();
+    assert_eq!(editor.move_module(first, moved.id(), DotEveryEditorOperationIndex::Child(0), &inner), Err(DotEveryEditorErrorMessage::CanNotAddIntoItself));
+
+    assert_eq!(editor.move_module(Uuid::new_v4(), Uuid::nil(), DotEveryEditorOperationIndex::Child(1), &block(5)), Ok(()));
+    assert_eq!(editor.list.len(), 2);
+}
+
+#[test]
+fn dotevery_editor_move_following_modules_test() {
+    let mut editor = DotEveryEditor::new(vec![block(0), block(1), block(2), block(3)]);
+    let target = editor.list[0].id();
+    let second = editor.list[1].id();
+    assert_eq!(editor.add(target, DotEveryEditorOperationIndex::OptionAbsolute(0), &block(4)), Ok(()));
+    let option = editor.get_modules_by_type(&4)[0].id();
+    assert_eq!(editor.get_following_modules(option).unwrap().len(), 1);
+    assert_eq!(editor.get_following_modules(Uuid::new_v4()), Err(DotEveryEditorErrorMessage::NotFound));
+
+    let run = editor.get_following_modules(second).unwrap().to_vec();
+    assert_eq!(run.iter().map(|module| module.type_data).collect::<Vec<_>>(), vec![1, 2, 3]);
+    let src = run.iter().map(ProgramModule::id).collect::<Vec<_>>();
+    let modules = run.iter().map(ProgramModule::deep_clone).collect::<Vec<_>>();
+    assert_eq!(editor.can_add_modules(target, DotEveryEditorOperationIndex::Child(0), &modules), Ok(()));
+    assert_eq!(editor.can_add_modules(target, DotEveryEditorOperationIndex::OptionAbsolute(0), &modules), Err(DotEveryEditorErrorMessage::TooManyModules));
+    assert_eq!(editor.can_add_modules(second, DotEveryEditorOperationIndex::Child(0), &run), Err(DotEveryEditorErrorMessage::CanNotAddIntoItself));
+    assert_eq!(editor.move_modules(&src, src[1], DotEveryEditorOperationIndex::Child(0), &modules), Err(DotEveryEditorErrorMessage::CanNotAddIntoItself));
+    assert_eq!(editor.list.len(), 4);
+
+    assert_eq!(editor.move_modules(&src, target, DotEveryEditorOperationIndex::Child(0), &modules), Ok(()));
+    assert_eq!(editor.list.len(), 1);
+    let children = match &editor.list[0].child {
+        ProgramModuleChildItems::BlockVertical(list) => list.iter().map(|module| module.type_data).collect::<Vec<_>>(),
+        _ => unreachable!(),
+    };
+    assert_eq!(children, vec![1, 2, 3]);
+}
+
+#[test]
+fn dotevery_editor_changed_subtrees_test() {
+    let mut editor = DotEveryEditor::new(vec![block(0), block(1)]);
+    let (a, b) = (editor.list[0].id(), editor.list[1].id());
+    let inner = block(2);
+    let inner_id = inner.id();
+    assert_eq!(editor.add(a, DotEveryEditorOperationIndex::Child(0), &inner), Ok(()));
+
+    assert_eq!(editor.changed_subtrees(&[inner_id, a]), Some(vec![a]));
+    assert_eq!(editor.changed_subtrees(&[a, inner_id, b]), Some(vec![a, b]));
+    assert_eq!(editor.changed_subtrees(&[inner_id]), Some(vec![inner_id]));
+    assert_eq!(editor.changed_subtrees(&[inner_id, Uuid::nil()]), None);
+    assert_eq!(editor.changed_subtrees(&[editor.id()]), None);
+    assert_eq!(editor.changed_subtrees(&[Uuid::new_v4()]), None);
+
+    let mut replacement = editor.get_module(inner_id).unwrap().clone();
+    replacement.type_data = 3;
+    replacement.set_parent(None);
+    assert_eq!(editor.replace_module(replacement), Ok(()));
+    let replaced = editor.get_module(inner_id).unwrap();
+    assert_eq!(replaced.type_data, 3);
+    assert_eq!(replaced.parent(), Some(a));
+    assert_eq!(editor.replace_module(block(4)), Err(DotEveryEditorErrorMessage::NotFound));
+}
+
+#[test]
+fn dotevery_editor_lock_test() {
+    let mut header = block(0);
+    header.lock = ModuleLock { no_move: true, no_delete: true, ..ModuleLock::default() };
+    let mut input = ProgramModule::new(vec![ProgramModuleOption::StringInput("1".to_string())], ProgramModuleChildItems::None, 1);
+    input.lock.no_edit = true;
+    let mut fixed = block(2);
+    fixed.lock.no_children = true;
+    let mut editor = DotEveryEditor::new(vec![header, input, fixed, block(3)]);
+    let ids = editor.list.iter().map(ProgramModule::id).collect::<Vec<_>>();
+    let (header, input, fixed, free) = (ids[0], ids[1], ids[2], ids[3]);
+
+    assert_eq!(editor.remove(header), Err(DotEveryEditorErrorMessage::Locked));
+    assert_eq!(editor.move_module(header, free, DotEveryEditorOperationIndex::Child(0), &editor.get_module(header).unwrap().clone()), Err(DotEveryEditorErrorMessage::Locked));
+    assert_eq!(editor.can_move(header), Err(DotEveryEditorErrorMessage::Locked));
+    assert_eq!(editor.list.len(), 4);
+
+    assert_eq!(editor.update_input(input, 0, "2".to_string()), Err(DotEveryEditorErrorMessage::Locked));
+    assert_eq!(editor.get_module(input).unwrap().options[0], ProgramModuleOption::StringInput("1".to_string()));
+    assert_eq!(editor.update_input(free, 0, "2".to_string()), Err(DotEveryEditorErrorMessage::IndexOutOfRange));
+
+    assert_eq!(editor.add(fixed, DotEveryEditorOperationIndex::Child(0), &block(4)), Err(DotEveryEditorErrorMessage::Locked));
+    assert_eq!(editor.can_add(fixed, DotEveryEditorOperationIndex::OptionAbsolute(0), &block(4)), Err(DotEveryEditorErrorMessage::Locked));
+    assert_eq!(editor.add(free, DotEveryEditorOperationIndex::Child(0), &block(4)), Ok(()));
+    let child = editor.get_modules_by_type(&4)[0].id();
+    assert_eq!(editor.move_module(child, fixed, DotEveryEditorOperationIndex::Child(0), &block(4)), Err(DotEveryEditorErrorMessage::Locked));
+
+    // a locked descendant protects the whole subtree
+    let mut undeletable = block(5);
+    undeletable.lock.no_delete = true;
+    assert_eq!(editor.add(free, DotEveryEditorOperationIndex::Child(1), &undeletable), Ok(()));
+    assert_eq!(editor.remove(free), Err(DotEveryEditorErrorMessage::Locked));
+    assert_eq!(editor.move_module(input, free, DotEveryEditorOperationIndex::Child(0), &editor.get_module(input).unwrap().clone()), Ok(()));
+    assert_eq!(editor.list.len(), 3);
+
+    assert_eq!(editor.copy(header, Uuid::nil(), DotEveryEditorOperationIndex::Child(0)), Ok(()));
+    assert_eq!(editor.list[0].lock, ModuleLock::default());
+    assert_eq!(editor.get_module(header).unwrap().deep_clone().lock, editor.get_module(header).unwrap().lock);
+}
+
+#[test]
+fn dotevery_editor_position_test() {
+    let mut editor = DotEveryEditor::new(vec![block(0), block(1)]);
+    let first = editor.list[0].id();
+    let second = editor.list[1].id();
+    let child = block(2);
+    let child_id = child.id();
+    let slot = block(3);
+    let slot_id = slot.id();
+    editor.add(first, DotEveryEditorOperationIndex::Child(0), &child).unwrap();
+    editor.add(child_id, DotEveryEditorOperationIndex::OptionAbsolute(0), &slot).unwrap();
+
+    assert_eq!(editor.position(second), Ok((editor.id(), DotEveryEditorOperationIndex::Child(1))));
+    assert_eq!(editor.position(child_id), Ok((first, DotEveryEditorOperationIndex::Child(0))));
+    assert_eq!(editor.position(slot_id), Ok((child_id, DotEveryEditorOperationIndex::OptionAbsolute(0))));
+    assert_eq!(editor.position(Uuid::new_v4()), Err(DotEveryEditorErrorMessage::NotFound));
+    assert_eq!(editor.duplicate_position(second), Ok((editor.id(), DotEveryEditorOperationIndex::Child(2))));
+    assert_eq!(editor.duplicate_position(slot_id), Ok((first, DotEveryEditorOperationIndex::Child(1))));
+}
+
+#[test]
+fn dotevery_editor_disable_and_comment_test() {
+    let mut editor = DotEveryEditor::new(vec![block(0), block(1)]);
+    let first = editor.list[0].id();
+    let second = editor.list[1].id();
+    editor.get_module_mut(second).unwrap().lock.no_edit = true;
+
+    assert_eq!(editor.set_disabled(first, true), Ok(()));
+    assert_eq!(editor.set_comment(first, Some("todo".to_string())), Ok(()));
+    assert_eq!(editor.set_disabled(second, true), Err(DotEveryEditorErrorMessage::Locked));
+    assert_eq!(editor.set_comment(second, Some("todo".to_string())), Err(DotEveryEditorErrorMessage::Locked));
+
+    assert_eq!(editor.copy(first, editor.id(), DotEveryEditorOperationIndex::Child(2)), Ok(()));
+    assert!(editor.list[2].disabled);
+    assert_eq!(editor.list[2].comment, Some("todo".to_string()));
+
+    assert_eq!(editor.set_comment(first, Some("  ".to_string())), Ok(()));
+    assert_eq!(editor.get_module(first).unwrap().comment, None);
+    assert!(!editor.get_module(second).unwrap().disabled);
+}
+
+#[test]
+fn dotevery_editor_structural_sharing_test() {
+    let mut editor = DotEveryEditor::new(vec![block(0)]);
+    let root = editor.list[0].id();
+    let (changed, kept) = (block(1), block(2));
+    let (changed_id, kept_id) = (changed.id(), kept.id());
+    editor.add(root, DotEveryEditorOperationIndex::Child(0), &changed).unwrap();
+    editor.add(root, DotEveryEditorOperationIndex::Child(1), &kept).unwrap();
+    editor.add(root, DotEveryEditorOperationIndex::OptionAbsolute(0), &block(3)).unwrap();
+    let children = |editor: &DotEveryEditor<i32>| match (&editor.list[0].child, &editor.list[0].options[0]) {
+        (ProgramModuleChildItems::BlockVertical(list), ProgramModuleOption::ProgramModule(Some(option))) => (Arc::clone(&list[0]), Arc::clone(&list[1]), Arc::clone(option)),
+        _ => unreachable!(),
+    };
+
+    let mut copy = editor.clone();
+    copy.get_module_mut(changed_id).unwrap().type_data = 4;
+    let (before, after) = (children(&editor), children(&copy));
+    assert!(!Arc::ptr_eq(&before.0, &after.0));
+    assert!(Arc::ptr_eq(&before.1, &after.1));
+    assert!(Arc::ptr_eq(&before.2, &after.2));
+
+    let updated = copy.get_module(changed_id).unwrap().clone();
+    assert_eq!(editor.replace_module(updated), Ok(()));
+    let replaced = children(&editor);
+    assert_eq!(replaced.0.type_data, 4);
+    assert!(Arc::ptr_eq(&replaced.1, &after.1));
+    assert!(Arc::ptr_eq(&replaced.2, &after.2));
+    assert_eq!(editor.remove(kept_id), Ok(()));
+    assert!(copy.get_module(kept_id).is_ok());
+}
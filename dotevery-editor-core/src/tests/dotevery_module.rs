@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use crate::dotevery_module::{DotEveryModule, ModuleCategory};
+use crate::i18n::MessageCatalog;
+use crate::program_module::{ProgramModuleChildItems, ProgramModuleOption};
+use crate::template::FromTemplateTag;
+
+#[derive(Clone, Debug, PartialEq, DotEveryModule)]
+#[module_category(name = "Statements", color = "#4c97ff")]
+#[module_category(name = "Operators", color = "#59c059")]
+enum TestType {
+    #[module(category = "Statements", sign(text = "print", locales(ja = "表示")), slot = "value")]
+    Print,
+    #[module(category = "Statements", sign = "case", input = "label", sign = ":", child = "vertical")]
+    Case,
+    #[module(category = "Operators", slot = "left", sign = "+", slot = "right")]
+    Add,
+    #[module(field_sign)]
+    Variable(String),
+    #[module(sign = "hidden", skip)]
+    Hidden,
+}
+
+#[test]
+fn derive_create_module_test() {
+    let print = TestType::Print.create_module();
+    assert_eq!(print.type_data, TestType::Print);
+    assert_eq!(print.options, vec![ProgramModuleOption::StringSign("print".to_string()), ProgramModuleOption::ProgramModule(None)]);
+    assert_eq!(print.child, ProgramModuleChildItems::None);
+
+    let case = TestType::Case.create_module();
+    assert_eq!(case.options, vec![
+        ProgramModuleOption::StringSign("case".to_string()),
+        ProgramModuleOption::StringInput(String::new()),
+        ProgramModuleOption::StringSign(":".to_string()),
+    ]);
+    assert_eq!(case.child, ProgramModuleChildItems::BlockVertical(Vec::new()));
+
+    let variable = TestType::Variable("x".to_string()).create_module();
+    assert_eq!(variable.options, vec![ProgramModuleOption::StringSign("x".to_string())]);
+    assert_eq!(variable.type_data, TestType::Variable("x".to_string()));
+}
+
+#[test]
+fn derive_palette_test() {
+    assert_eq!(TestType::palette_types(), vec![TestType::Print, TestType::Case, TestType::Add]);
+    let palette = TestType::palette();
+    assert_eq!(palette.categories.len(), 2);
+    assert_eq!(palette.categories[0].name, "Statements");
+    assert_eq!(palette.categories[0].color, "#4c97ff");
+    assert_eq!(palette.categories[0].items.iter().map(|module| module.type_data.clone()).collect::<Vec<_>>(), vec![TestType::Print, TestType::Case]);
+    assert_eq!(palette.categories[1].items.iter().map(|module| module.type_data.clone()).collect::<Vec<_>>(), vec![TestType::Add]);
+}
+
+#[test]
+fn derive_category_test() {
+    let statements = ModuleCategory { name: "Statements", color: "#4c97ff" };
+    assert_eq!(TestType::categories(), vec![statements, ModuleCategory { name: "Operators", color: "#59c059" }]);
+    assert_eq!(TestType::Case.category(), Some(statements));
+    assert_eq!(TestType::Add.category().map(|category| category.color), Some("#59c059"));
+    assert_eq!(TestType::Variable("x".to_string()).category(), None);
+}
+
+#[test]
+fn derive_template_tag_test() {
+    assert_eq!(TestType::from_template_tag("print"), Some(TestType::Print));
+    assert_eq!(TestType::from_template_tag("hidden"), Some(TestType::Hidden));
+    assert_eq!(TestType::from_template_tag("variable:count"), Some(TestType::Variable("count".to_string())));
+    assert_eq!(TestType::from_template_tag("while"), None);
+    assert_eq!(TestType::Case.type_tag(), Some("case".to_string()));
+    assert_eq!(TestType::Variable("count".to_string()).type_tag(), Some("variable".to_string()));
+}
+
+#[test]
+fn derive_sign_locales_test() {
+    let mut catalog = MessageCatalog::new("ja");
+    TestType::register_signs(&mut catalog);
+    let print = TestType::Print.create_module();
+    assert_eq!(print.options[0], ProgramModuleOption::StringSign("print".to_string()));
+    assert_eq!(catalog.localize(&print, &TestType::type_tag).options[0], ProgramModuleOption::StringSign("表示".to_string()));
+    assert_eq!(catalog.sign(Some("case"), 0, "print"), "print");
+}
+
+#[test]
+fn derive_accessor_test() {
+    let mut add = TestType::Add.create_module();
+    assert_eq!(TestType::add_left(&add), None);
+    if let ProgramModuleOption::ProgramModule(slot) = &mut add.options[0] {
+        *slot = Some(Arc::new(TestType::Variable("x".to_string()).create_module()));
+    }
+    assert_eq!(TestType::add_left(&add).map(|module| module.type_data.clone()), Some(TestType::Variable("x".to_string())));
+    assert_eq!(TestType::add_right(&add), None);
+    assert_eq!(TestType::print_value(&add), None);
+
+    let mut case = TestType::Case.create_module();
+    assert_eq!(TestType::case_label(&case), Some(""));
+    case.options[1] = ProgramModuleOption::StringInput("1".to_string());
+    assert_eq!(TestType::case_label(&case), Some("1"));
+}
@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use crate::dotevery_editor::DotEveryEditor;
+use crate::i18n::MessageCatalog;
+use crate::program_module::{ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
+
+fn catalog() -> MessageCatalog {
+    let mut catalog = MessageCatalog::new("ja").with_fallback("en");
+    catalog.insert("en", "add", "Add");
+    catalog.insert("ja", "add", "追加");
+    catalog.insert("en", "remove", "Remove");
+    catalog.insert("en", "blocks", "{count} blocks");
+    catalog.insert_sign("number", 0, "en", "number");
+    catalog
+}
+
+#[test]
+fn message_catalog_test() {
+    let mut catalog = catalog();
+    assert_eq!(catalog.get("add"), "追加");
+    assert_eq!(catalog.get("remove"), "Remove");
+    assert_eq!(catalog.get("unknown"), "unknown");
+    assert_eq!(catalog.format("blocks", &[("count", "3")]), "3 blocks");
+    assert_eq!(catalog.locales(), vec!["en".to_string(), "ja".to_string()]);
+
+    catalog.set_locale("en");
+    assert_eq!(catalog.locale(), "en");
+    assert_eq!(catalog.get("add"), "Add");
+
+    let mut other = MessageCatalog::new("en");
+    other.insert("en", "add", "Append");
+    catalog.merge(&other);
+    assert_eq!(catalog.get("add"), "Append");
+    assert_eq!(catalog.get("remove"), "Remove");
+}
+
+#[test]
+fn message_catalog_localize_test() {
+    let mut catalog = catalog();
+    let tag = |_: &()| Some("number".to_string());
+    let editor = DotEveryEditor::new(vec![
+        ProgramModule::<()>::new_default(vec![
+            ProgramModuleOption::StringSign("数値".to_string()),
+            ProgramModuleOption::ProgramModule(Some(Arc::new(ProgramModule::new_default(vec![ProgramModuleOption::StringSign("数値".to_string())], ProgramModuleChildItems::None)))),
+            ProgramModuleOption::StringSign("数値".to_string()),
+        ], ProgramModuleChildItems::None),
+    ]);
+    assert_eq!(catalog.localize_editor(&editor, &tag), editor);
+
+    catalog.set_locale("en");
+    assert_eq!(catalog.localize_editor(&editor, &|_: &()| None), editor);
+    let localized = catalog.localize_editor(&editor, &tag);
+    assert_eq!(localized.list[0].id(), editor.list[0].id());
+    assert_eq!(localized.list[0].options[0], ProgramModuleOption::StringSign("number".to_string()));
+    match &localized.list[0].options[1] {
+        ProgramModuleOption::ProgramModule(Some(module)) => assert_eq!(module.options[0], ProgramModuleOption::StringSign("number".to_string())),
+        _ => unreachable!(),
+    }
+    // only the sign at the registered index is translated
+    assert_eq!(localized.list[0].options[2], ProgramModuleOption::StringSign("数値".to_string()));
+    assert_eq!(editor.list[0].options[0], ProgramModuleOption::StringSign("数値".to_string()));
+}
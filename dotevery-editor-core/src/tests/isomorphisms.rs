@@ -1,4 +1,6 @@
-use crate::logic::program_module::{ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
+use std::sync::Arc;
+
+use crate::program_module::{ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
 use crate::util::Isomorphism;
 
 #[test]
@@ -10,8 +12,8 @@ fn module_isomorphisms_success_test() {
     assert!(b.isomorphisms(&a));
     assert!(a.isomorphisms(&a));
 
-    let a = ProgramModule::<i32>::new_default(vec![ProgramModuleOption::StringSign("test".to_string()), ProgramModuleOption::StringInput("test1".to_string()), ProgramModuleOption::ProgramModule(None), ProgramModuleOption::ProgramModule(Some(ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None)))], ProgramModuleChildItems::None);
-    let b = ProgramModule::<i32>::new_default(vec![ProgramModuleOption::StringSign("test".to_string()), ProgramModuleOption::StringInput("test2".to_string()), ProgramModuleOption::ProgramModule(None), ProgramModuleOption::ProgramModule(Some(ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None)))], ProgramModuleChildItems::None);
+    let a = ProgramModule::<i32>::new_default(vec![ProgramModuleOption::StringSign("test".to_string()), ProgramModuleOption::StringInput("test1".to_string()), ProgramModuleOption::ProgramModule(None), ProgramModuleOption::ProgramModule(Some(Arc::new(ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None))))], ProgramModuleChildItems::None);
+    let b = ProgramModule::<i32>::new_default(vec![ProgramModuleOption::StringSign("test".to_string()), ProgramModuleOption::StringInput("test2".to_string()), ProgramModuleOption::ProgramModule(None), ProgramModuleOption::ProgramModule(Some(Arc::new(ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None))))], ProgramModuleChildItems::None);
     assert_ne!(a, b);
     assert!(a.isomorphisms(&b));
     assert!(b.isomorphisms(&a));
@@ -41,7 +43,7 @@ fn module_isomorphisms_success_test() {
 
 #[test]
 fn module_isomorphisms_failed_test() {
-    let a = ProgramModule::<i32>::new_default(vec![ProgramModuleOption::ProgramModule(Some(ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None)))], ProgramModuleChildItems::None);
+    let a = ProgramModule::<i32>::new_default(vec![ProgramModuleOption::ProgramModule(Some(Arc::new(ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None))))], ProgramModuleChildItems::None);
     let b = ProgramModule::<i32>::new_default(vec![], ProgramModuleChildItems::None);
     assert!(!a.isomorphisms(&b));
     assert!(!b.isomorphisms(&a));
@@ -51,14 +53,14 @@ fn module_isomorphisms_failed_test() {
     assert!(!a.isomorphisms(&b));
     assert!(!b.isomorphisms(&a));
 
-    let a = ProgramModule::<i32>::new_default(vec![ProgramModuleOption::ProgramModule(Some(ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None)))], ProgramModuleChildItems::None);
+    let a = ProgramModule::<i32>::new_default(vec![ProgramModuleOption::ProgramModule(Some(Arc::new(ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None))))], ProgramModuleChildItems::None);
     let b = ProgramModule::<i32>::new_default(vec![ProgramModuleOption::ProgramModule(None)], ProgramModuleChildItems::None);
     assert!(!a.isomorphisms(&b));
     assert!(!b.isomorphisms(&a));
 
 
-    let a = ProgramModule::<i32>::new_default(vec![ProgramModuleOption::ProgramModule(Some(ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None)))], ProgramModuleChildItems::None);
-    let b = ProgramModule::<i32>::new_default(vec![ProgramModuleOption::ProgramModule(Some(ProgramModule::new_default(vec![ProgramModuleOption::StringSign("test".to_string())], ProgramModuleChildItems::None)))], ProgramModuleChildItems::None);
+    let a = ProgramModule::<i32>::new_default(vec![ProgramModuleOption::ProgramModule(Some(Arc::new(ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None))))], ProgramModuleChildItems::None);
+    let b = ProgramModule::<i32>::new_default(vec![ProgramModuleOption::ProgramModule(Some(Arc::new(ProgramModule::new_default(vec![ProgramModuleOption::StringSign("test".to_string())], ProgramModuleChildItems::None))))], ProgramModuleChildItems::None);
     assert!(!a.isomorphisms(&b));
     assert!(!b.isomorphisms(&a));
 
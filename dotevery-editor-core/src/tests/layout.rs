@@ -0,0 +1,206 @@
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::dotevery_editor::{DotEveryEditor, DotEveryEditorOperationIndex};
+use crate::layout::{block_path, ChildDirection, EditorLayout, LayoutMetrics, ModuleLayout, OptionLayout, TextMetrics};
+use crate::program_module::{ModuleLock, ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
+use crate::util::Rect;
+use crate::tests::sign;
+
+fn editor() -> DotEveryEditor {
+    DotEveryEditor::new(vec![
+        ProgramModule::new_default(vec![sign("if"), ProgramModuleOption::ProgramModule(None)], ProgramModuleChildItems::BlockVertical(vec![
+            Arc::new(ProgramModule::new_default(vec![sign("print"), ProgramModuleOption::StringInput("ab".to_string())], ProgramModuleChildItems::None)),
+        ])),
+        ProgramModule::new_default(vec![sign("x"), ProgramModuleOption::ProgramModule(Some(Arc::new(ProgramModule::new_default(vec![sign("1")], ProgramModuleChildItems::None))))], ProgramModuleChildItems::None),
+    ])
+}
+
+#[test]
+fn layout_module_geometry_test() {
+    let editor = editor();
+    let metrics = LayoutMetrics::default();
+    let layout = EditorLayout::new(&editor, &metrics);
+    let block = &layout.list.modules[0];
+    // padding 6 + "if" 16 + gap 4 + slot 40 + padding 6
+    assert_eq!(block.header, Rect { x: 6.0, y: 6.0, w: 72.0, h: 38.0 });
+    assert_eq!(block.options[1], OptionLayout::Slot(Rect { x: 32.0, y: 18.0, w: 40.0, h: 20.0 }));
+    let child = block.child.as_ref().unwrap();
+    assert_eq!(child.rect.x, 22.0);
+    assert_eq!(child.rect.y, 44.0);
+    assert_eq!(child.modules[0].rect.y, 44.0);
+    assert_eq!(block.rect.h, 38.0 + child.rect.h + 12.0);
+
+    let second = &layout.list.modules[1];
+    assert_eq!(second.rect.y, block.rect.y + block.rect.h);
+    match &second.options[1] {
+        OptionLayout::Module(nested) => {
+            assert!(nested.nested);
+            // line height 20 + nested padding 4 * 2
+            assert_eq!(nested.rect.h, 28.0);
+            assert_eq!(nested.rect.y, second.rect.y + 6.0 + 6.0);
+        }
+        _ => panic!("nested module is not laid out"),
+    }
+    assert_eq!(layout.bounds.h, layout.list.rect.h + 12.0);
+}
+
+#[test]
+fn layout_rects_test() {
+    let editor = editor();
+    let layout = EditorLayout::new(&editor, &LayoutMetrics::default());
+    let rects = layout.rects();
+    assert_eq!(rects.len(), 5);
+    assert_eq!(rects[0].0, Uuid::nil());
+    assert_eq!(rects[0].1, editor.id());
+    let block = editor.list[0].id();
+    let print = match &editor.list[0].child {
+        ProgramModuleChildItems::BlockVertical(list) => list[0].id(),
+        _ => unreachable!(),
+    };
+    assert!(rects.iter().any(|(id, parent, _)| *id == block && parent.is_nil()));
+    assert!(rects.iter().any(|(id, parent, _)| *id == print && *parent == block));
+    assert!(layout.contains(print));
+    assert!(!layout.contains(Uuid::new_v4()));
+}
+
+#[test]
+fn layout_drop_index_test() {
+    let editor = editor();
+    let layout = EditorLayout::new(&editor, &LayoutMetrics::default());
+    let block = editor.list[0].id();
+    let print = layout.list.modules[0].child.as_ref().unwrap().modules[0].id;
+    let second = editor.list[1].id();
+
+    assert_eq!(layout.drop_index(block, 40.0, 25.0), Some((block, DotEveryEditorOperationIndex::OptionAbsolute(1))));
+    let child = layout.get(block).unwrap().child.as_ref().unwrap().rect.clone();
+    assert_eq!(layout.drop_index(block, 30.0, child.y + child.h - 2.0), Some((block, DotEveryEditorOperationIndex::Child(1))));
+    let print_rect = layout.get(print).unwrap().rect.clone();
+    assert_eq!(layout.drop_index(print, 30.0, print_rect.y + 2.0), Some((block, DotEveryEditorOperationIndex::Child(0))));
+    assert_eq!(layout.drop_index(block, 10.0, 10.0), Some((Uuid::nil(), DotEveryEditorOperationIndex::Child(0))));
+    let second_rect = layout.get(second).unwrap().rect.clone();
+    assert_eq!(layout.drop_index(second, 10.0, second_rect.y + second_rect.h - 1.0), Some((Uuid::nil(), DotEveryEditorOperationIndex::Child(2))));
+    assert_eq!(layout.drop_index(Uuid::nil(), 10.0, 1000.0), Some((Uuid::nil(), DotEveryEditorOperationIndex::Child(2))));
+
+    let marker = layout.insertion_marker(Uuid::nil(), DotEveryEditorOperationIndex::Child(1)).unwrap();
+    assert_eq!(marker.y + marker.h / 2.0, second_rect.y);
+    assert_eq!(layout.insertion_marker(block, DotEveryEditorOperationIndex::OptionAbsolute(1)), Some(Rect { x: 32.0, y: 18.0, w: 40.0, h: 20.0 }));
+    assert_eq!(layout.insertion_marker(block, DotEveryEditorOperationIndex::Child(5)), None);
+}
+
+#[test]
+fn layout_block_path_test() {
+    let editor = DotEveryEditor::new(vec![ProgramModule::<()>::new_default(vec![sign("a")], ProgramModuleChildItems::None)]);
+    let metrics = LayoutMetrics::default();
+    let layout = EditorLayout::new(&editor, &metrics);
+    assert_eq!(block_path(&layout.list.modules[0], &metrics), "M 6 6 H 18 l 6 6 h 12 l 6 -6 H 58 V 44 H 42 l -6 6 h -12 l -6 -6 H 6 Z");
+}
+
+struct FixedTextMetrics;
+
+impl TextMetrics for FixedTextMetrics {
+    fn text_width(&self, text: &str) -> f64 {
+        text.len() as f64 * 10.0
+    }
+}
+
+#[test]
+fn layout_text_metrics_test() {
+    let module = ProgramModule::<()>::new_default(vec![sign("abc"), ProgramModuleOption::StringInput("あい".to_string())], ProgramModuleChildItems::None);
+    let metrics = LayoutMetrics::default();
+    let monospace = ModuleLayout::new(&module, &metrics, 0.0, 0.0);
+    assert_eq!(monospace.options[0].rect().w, 24.0);
+    assert_eq!(monospace.options[1].rect().w, 40.0);
+    let fixed = ModuleLayout::with_text_metrics(&module, &metrics, &FixedTextMetrics, 0.0, 0.0);
+    assert_eq!(fixed.options[0].rect().w, 30.0);
+    // "あい" is 6 bytes long, 60 + gap 4 * 2
+    assert_eq!(fixed.options[1].rect().w, 68.0);
+    assert_eq!(fixed.header.w, 6.0 + 30.0 + 4.0 + 68.0 + 6.0);
+}
+
+#[test]
+fn layout_horizontal_child_test() {
+    let module = ProgramModule::<()>::new_default(vec![sign("row")], ProgramModuleChildItems::BlockHorizontal(vec![
+        Arc::new(ProgramModule::new_default(vec![sign("a")], ProgramModuleChildItems::None)),
+        Arc::new(ProgramModule::new_default(vec![sign("bb")], ProgramModuleChildItems::None)),
+    ]));
+    let metrics = LayoutMetrics::default();
+    let layout = ModuleLayout::new(&module, &metrics, 0.0, 0.0);
+    let child = layout.child.as_ref().unwrap();
+    assert_eq!(child.direction, ChildDirection::Horizontal);
+    let (a, b) = (&child.modules[0].rect, &child.modules[1].rect);
+    assert_eq!(a.y, b.y);
+    assert_eq!(b.x, a.x + a.w + metrics.child_gap);
+    assert_eq!(child.rect.w, a.w + b.w + metrics.child_gap * 2.0 + metrics.slot_width);
+    assert_eq!(child.rect.h, f64::max(a.h, b.h));
+
+    let empty = ProgramModule::new_default(vec![sign("row")], ProgramModuleChildItems::<()>::BlockHorizontal(Vec::new()));
+    let child = ModuleLayout::new(&empty, &metrics, 0.0, 0.0).child.unwrap();
+    assert_eq!((child.rect.w, child.rect.h), (metrics.slot_width, metrics.empty_child_height));
+}
+
+#[test]
+fn layout_hit_test_test() {
+    let editor = editor();
+    let layout = EditorLayout::new(&editor, &LayoutMetrics::default());
+    let block = editor.list[0].id();
+    let print = layout.list.modules[0].child.as_ref().unwrap().modules[0].clone();
+    let nested = match &layout.list.modules[1].options[1] {
+        OptionLayout::Module(nested) => nested.clone(),
+        _ => unreachable!(),
+    };
+    assert_eq!(layout.hit_test(10.0, 10.0), Some(block));
+    let (x, y) = print.rect.center();
+    assert_eq!(layout.hit_test(x, y), Some(print.id));
+    let (x, y) = nested.rect.center();
+    assert_eq!(layout.hit_test(x, y), Some(nested.id));
+    assert_eq!(layout.hit_test(layout.bounds.w - 1.0, 1.0), Some(Uuid::nil()));
+    assert_eq!(layout.hit_test(-1.0, -1.0), None);
+}
+
+#[test]
+fn layout_lock_test() {
+    let mut editor = editor();
+    let metrics = LayoutMetrics::default();
+    let unlocked = EditorLayout::new(&editor, &metrics).list.modules[0].clone();
+    assert_eq!(unlocked.lock, None);
+    editor.list[0].lock = ModuleLock::all();
+    let layout = EditorLayout::new(&editor, &metrics);
+    let block = &layout.list.modules[0];
+    // after the slot, which ends at 72
+    let lock = block.lock.clone().unwrap();
+    assert_eq!(lock, Rect { x: 76.0, y: 18.0, w: 16.0, h: 20.0 });
+    assert_eq!(block.header.w, unlocked.header.w + 16.0 + 4.0);
+    assert!(lock.x + lock.w <= block.header.x + block.header.w);
+}
+
+#[test]
+fn layout_comment_test() {
+    let mut editor = editor();
+    let metrics = LayoutMetrics::default();
+    let plain = EditorLayout::new(&editor, &metrics).list.modules[0].clone();
+    assert_eq!(plain.comment, None);
+    editor.list[0].comment = Some("checks the input twice".to_string());
+    let layout = EditorLayout::new(&editor, &metrics);
+    let block = &layout.list.modules[0];
+    // 22 characters of 8, the options move down by one line
+    assert_eq!(block.comment, Some(Rect { x: 12.0, y: 18.0, w: 176.0, h: 20.0 }));
+    assert_eq!(block.options[1], OptionLayout::Slot(Rect { x: 32.0, y: 38.0, w: 40.0, h: 20.0 }));
+    assert_eq!(block.header.h, plain.header.h + 20.0);
+    assert_eq!(block.header.w, 176.0 + 12.0);
+    assert_eq!(layout.list.modules[1].rect.y, plain.rect.y + plain.rect.h + 20.0);
+}
+
+#[test]
+fn layout_collapsed_test() {
+    let mut editor = editor();
+    assert_eq!(editor.list[0].module_count(), 2);
+    editor.list[0].collapsed = true;
+    let metrics = LayoutMetrics::default();
+    let layout = EditorLayout::new(&editor, &metrics);
+    let block = &layout.list.modules[0];
+    assert!(block.child.is_none());
+    assert_eq!(block.rect, block.header);
+    assert_eq!(layout.drop_index(editor.list[0].id(), 10.0, block.rect.h - 1.0), Some((Uuid::nil(), DotEveryEditorOperationIndex::Child(1))));
+}
@@ -0,0 +1,49 @@
+use crate::program_module::{ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
+use crate::util::Rect;
+
+// fixtures shared by the test modules
+
+pub(crate) fn sign<T: 'static + Clone + PartialEq>(s: &str) -> ProgramModuleOption<T> {
+    ProgramModuleOption::StringSign(s.to_string())
+}
+
+// a sign followed by an empty slot
+pub(crate) fn module(sign: &str, type_data: i32) -> ProgramModule<i32> {
+    ProgramModule::new(vec![ProgramModuleOption::StringSign(sign.to_string()), ProgramModuleOption::ProgramModule(None)], ProgramModuleChildItems::None, type_data)
+}
+
+// an empty slot and a vertical child list
+pub(crate) fn block(type_data: i32) -> ProgramModule<i32> {
+    ProgramModule::new(vec![ProgramModuleOption::ProgramModule(None)], ProgramModuleChildItems::BlockVertical(Vec::new()), type_data)
+}
+
+pub(crate) fn rect(x: f64, y: f64, w: f64, h: f64) -> Rect {
+    Rect { x, y, w, h }
+}
+
+#[cfg(test)]
+mod isomorphisms;
+#[cfg(test)]
+mod program_module;
+#[cfg(test)]
+mod dotevery_editor;
+#[cfg(all(test, feature = "derive"))]
+mod dotevery_module;
+#[cfg(test)]
+mod palette;
+#[cfg(all(test, feature = "template"))]
+mod template;
+#[cfg(test)]
+mod cursor;
+#[cfg(test)]
+mod spatial_index;
+#[cfg(test)]
+mod viewport;
+#[cfg(test)]
+mod layout;
+#[cfg(test)]
+mod outline;
+#[cfg(test)]
+mod i18n;
+#[cfg(test)]
+mod svg_export;
@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use crate::dotevery_editor::DotEveryEditor;
+use crate::outline::{outline, outline_text, plain_text};
+use crate::program_module::{ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
+use crate::tests::sign;
+
+fn editor() -> DotEveryEditor {
+    DotEveryEditor::new(vec![
+        ProgramModule::new_default(vec![sign("if"), ProgramModuleOption::ProgramModule(Some(Arc::new(ProgramModule::new_default(vec![sign("x"), sign(">"), ProgramModuleOption::StringInput("1".to_string())], ProgramModuleChildItems::None))))], ProgramModuleChildItems::BlockVertical(vec![
+            Arc::new(ProgramModule::new_default(vec![sign("print"), ProgramModuleOption::StringInput("ab".to_string())], ProgramModuleChildItems::None)),
+        ])),
+        ProgramModule::new_default(vec![sign("end")], ProgramModuleChildItems::None),
+    ])
+}
+
+#[test]
+fn outline_test() {
+    let editor = editor();
+    let entries = outline(&editor);
+    assert_eq!(entries.iter().map(|entry| (entry.depth, entry.text.as_str())).collect::<Vec<_>>(), vec![(0, "if"), (1, "x >"), (1, "print"), (0, "end")]);
+    assert_eq!(entries[0].id, editor.list[0].id());
+    assert_eq!(entries[3].id, editor.list[1].id());
+    assert_eq!(outline_text(&ProgramModule::<()>::new_default(vec![ProgramModuleOption::StringInput("a".to_string())], ProgramModuleChildItems::None)), "");
+}
+
+#[test]
+fn outline_collapsed_test() {
+    let mut editor = editor();
+    editor.list[0].collapsed = true;
+    let entries = outline(&editor);
+    assert_eq!(entries.iter().map(|entry| (entry.depth, entry.text.as_str(), entry.collapsed)).collect::<Vec<_>>(), vec![(0, "if", true), (1, "x >", false), (0, "end", false)]);
+}
+
+#[test]
+fn plain_text_test() {
+    let mut editor = editor();
+    editor.list[0].collapsed = true;
+    assert_eq!(plain_text(&editor.list[0]), "if (x > 1)\n  print ab");
+    assert_eq!(plain_text(&ProgramModule::<()>::new_default(vec![sign("print"), ProgramModuleOption::ProgramModule(None)], ProgramModuleChildItems::None)), "print ()");
+}
@@ -0,0 +1,93 @@
+use uuid::Uuid;
+
+use crate::dotevery_editor::{DotEveryEditor, DotEveryEditorErrorMessage, DotEveryEditorOperationIndex};
+use crate::palette::{Palette, PaletteCategory};
+use crate::tests::module;
+
+fn palette() -> Palette<i32> {
+    Palette::new(vec![
+        PaletteCategory::new("Statements", "#4c97ff", vec![module("print", 0), module("switch", 1)]),
+        PaletteCategory::new("Operators", "#59c059", vec![module("＋", 2), module("mod", 3)]),
+    ])
+}
+
+#[test]
+fn palette_push_and_remove_test() {
+    let mut palette = palette();
+    assert_eq!(palette.len(), 4);
+
+    palette.push("Operators", module("×", 4));
+    assert_eq!(palette.category("Operators").unwrap().items.len(), 3);
+    assert_eq!(palette.categories.len(), 2);
+
+    palette.push("Variables", module("x", 5));
+    assert_eq!(palette.categories.len(), 3);
+    assert_eq!(palette.categories[2].name, "Variables");
+    assert_eq!(palette.len(), 6);
+
+    let removed = palette.remove_by_type(&1).unwrap();
+    assert_eq!(removed.type_data, 1);
+    assert_eq!(palette.category("Statements").unwrap().items.len(), 1);
+    assert_eq!(palette.remove_by_type(&1), None);
+    assert_eq!(palette.items().map(|module| module.type_data).collect::<Vec<_>>(), vec![0, 2, 3, 4, 5]);
+}
+
+#[test]
+fn palette_filter_test() {
+    let palette = palette();
+    assert_eq!(palette.filter(""), palette);
+    assert_eq!(palette.filter("  "), palette);
+
+    let filtered = palette.filter("PRI");
+    assert_eq!(filtered.categories.len(), 1);
+    assert_eq!(filtered.categories[0].name, "Statements");
+    assert_eq!(filtered.categories[0].color, "#4c97ff");
+    assert_eq!(filtered.items().map(|module| module.type_data).collect::<Vec<_>>(), vec![0]);
+
+    let filtered = palette.filter("mod");
+    assert_eq!(filtered.items().map(|module| module.type_data).collect::<Vec<_>>(), vec![3]);
+
+    assert!(palette.filter("while").is_empty());
+}
+
+#[test]
+fn palette_from_list_test() {
+    let palette = Palette::from(vec![module("print", 0), module("switch", 1)]);
+    assert_eq!(palette.categories.len(), 1);
+    assert_eq!(palette.len(), 2);
+}
+
+#[test]
+fn palette_limit_test() {
+    let mut palette = palette();
+    palette.set_limit(2, Some(2));
+    palette.budget = Some(4);
+    let filtered = palette.filter("＋");
+    assert_eq!(filtered.limit(&2), Some(2));
+    assert_eq!(filtered.budget, Some(4));
+
+    let print = module("print", 0);
+    let print_id = print.id();
+    let mut editor = DotEveryEditor::new(vec![print]);
+    editor.add(print_id, DotEveryEditorOperationIndex::OptionAbsolute(1), &module("＋", 2)).unwrap();
+    assert_eq!(palette.remaining(&editor, &2), Some(1));
+    assert_eq!(palette.remaining(&editor, &0), None);
+    assert_eq!(palette.remaining_budget(&editor), Some(2));
+
+    assert_eq!(palette.can_place(&editor, &[module("＋", 2)]), Ok(()));
+    assert_eq!(palette.can_place(&editor, &[module("＋", 2), module("＋", 2)]), Err(DotEveryEditorErrorMessage::LimitExceeded));
+    assert_eq!(palette.can_place(&editor, &[module("mod", 3), module("print", 0), module("print", 0)]), Err(DotEveryEditorErrorMessage::LimitExceeded));
+
+    editor.add(Uuid::nil(), DotEveryEditorOperationIndex::Child(1), &module("＋", 2)).unwrap();
+    assert_eq!(palette.remaining(&editor, &2), Some(0));
+    assert_eq!(palette.can_place(&editor, &[module("＋", 2)]), Err(DotEveryEditorErrorMessage::LimitExceeded));
+    assert_eq!(palette.can_place(&editor, &[module("mod", 3)]), Ok(()));
+    assert_eq!(palette.can_place(&editor, &[]), Ok(()));
+
+    editor.remove(print_id).unwrap();
+    assert_eq!(palette.remaining(&editor, &2), Some(1));
+    assert_eq!(palette.remaining_budget(&editor), Some(3));
+
+    palette.set_limit(2, None);
+    assert_eq!(palette.remaining(&editor, &2), None);
+}
@@ -1,7 +1,9 @@
+use std::sync::Arc;
+
 use uuid::Uuid;
 
-use crate::logic::dotevery_editor::{DotEveryEditorErrorMessage, DotEveryEditorOperationIndex};
-use crate::logic::program_module::{ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
+use crate::dotevery_editor::{DotEveryEditorErrorMessage, DotEveryEditorOperationIndex};
+use crate::program_module::{ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
 use crate::util::Isomorphism;
 
 #[test]
@@ -12,22 +14,22 @@ fn program_module_deep_clone_test() {
             ProgramModuleOption::StringInput("test2".to_string()),
             ProgramModuleOption::ProgramModule(
                 Some(
-                    ProgramModule::new(
+                    Arc::new(ProgramModule::new(
                         vec![
                             ProgramModuleOption::StringSign("test".to_string()),
                             ProgramModuleOption::StringInput("test2".to_string()),
                             ProgramModuleOption::ProgramModule(
                                 Some(
-                                    ProgramModule::new_default(
+                                    Arc::new(ProgramModule::new_default(
                                         Vec::new(),
-                                        ProgramModuleChildItems::None)))
+                                        ProgramModuleChildItems::None))))
                         ],
                         ProgramModuleChildItems::BlockVertical(
-                            (0..10).map(|_| { ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None) }).collect())
-                        , 256))),
+                            (0..10).map(|_| { ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None) }).map(Arc::new).collect())
+                        , 256)))),
             ProgramModuleOption::ProgramModule(None)
         ], ProgramModuleChildItems::BlockHorizontal(
-            ((0..10).map(|_| { ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None) }).collect())
+            ((0..10).map(|_| { ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None) }).map(Arc::new).collect())
         ), 128);
     let cloned = module.deep_clone();
     assert_eq!(module, module);
@@ -92,22 +94,22 @@ fn program_module_get_module_test() {
             ProgramModuleOption::StringInput("test2".to_string()),
             ProgramModuleOption::ProgramModule(
                 Some(
-                    ProgramModule::new_default(
+                    Arc::new(ProgramModule::new_default(
                         vec![
                             ProgramModuleOption::StringSign("test".to_string()),
                             ProgramModuleOption::StringInput("test2".to_string()),
                             ProgramModuleOption::ProgramModule(
                                 Some(
-                                    ProgramModule::new_default(
+                                    Arc::new(ProgramModule::new_default(
                                         Vec::new(),
-                                        ProgramModuleChildItems::None)))
+                                        ProgramModuleChildItems::None))))
                         ],
                         ProgramModuleChildItems::BlockVertical(
                             (
-                                (0..10).map(|_| { ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None) }).collect()))))),
+                                (0..10).map(|_| { ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None) }).map(Arc::new).collect())))))),
             ProgramModuleOption::ProgramModule(None)
         ], ProgramModuleChildItems::BlockHorizontal(
-            ((0..10).map(|_| { ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None) }).collect())
+            ((0..10).map(|_| { ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None) }).map(Arc::new).collect())
         ));
     assert_eq!(module.get_module(Uuid::new_v4()), Err(DotEveryEditorErrorMessage::NotFound));
     assert_eq!(module.get_module(module.id), Ok(&module.clone()));
@@ -116,12 +118,12 @@ fn program_module_get_module_test() {
             (list[3].id, list[3].clone())
         } else { unreachable!(); }
     } else { unreachable!(); };
-    assert_eq!(module.get_module(module_id), Ok(&module_obj));
+    assert_eq!(module.get_module(module_id), Ok(&*module_obj));
 
     let (module_id, module_obj) = if let ProgramModuleChildItems::BlockHorizontal(lists) = &module.child {
         (lists[3].id, lists[3].clone())
     } else { unreachable!(); };
-    assert_eq!(module.get_module(module_id), Ok(&module_obj));
+    assert_eq!(module.get_module(module_id), Ok(&*module_obj));
 }
 
 #[test]
@@ -133,22 +135,22 @@ fn program_module_add_test() {
                 ProgramModuleOption::StringInput("test2".to_string()),
                 ProgramModuleOption::ProgramModule(
                     Some(
-                        ProgramModule::new_default(
+                        Arc::new(ProgramModule::new_default(
                             vec![
                                 ProgramModuleOption::StringSign("test".to_string()),
                                 ProgramModuleOption::StringInput("test2".to_string()),
                                 ProgramModuleOption::ProgramModule(
                                     Some(
-                                        ProgramModule::new_default(
+                                        Arc::new(ProgramModule::new_default(
                                             Vec::new(),
-                                            ProgramModuleChildItems::None)))
+                                            ProgramModuleChildItems::None))))
                             ],
                             ProgramModuleChildItems::BlockVertical(
                                 (
-                                    (0..10).map(|_| { ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None) }).collect()))))),
+                                    (0..10).map(|_| { ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None) }).map(Arc::new).collect())))))),
                 ProgramModuleOption::ProgramModule(None)
             ], ProgramModuleChildItems::BlockHorizontal(
-                ((0..10).map(|_| { ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None) }).collect())
+                ((0..10).map(|_| { ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None) }).map(Arc::new).collect())
             ))
     };
     let mut module = new_module();
@@ -172,7 +174,7 @@ fn program_module_add_test() {
     let add = new_module();
     let mut module1 = add.clone();
     module1.parent = Some(module.id);
-    expect.options[3] = ProgramModuleOption::ProgramModule(Some(module1));
+    expect.options[3] = ProgramModuleOption::ProgramModule(Some(Arc::new(module1)));
     assert_eq!(module.add(module.id, DotEveryEditorOperationIndex::OptionAbsolute(3), &add), Ok(()));
     assert_eq!(module, expect);
     if let ProgramModuleOption::ProgramModule(Some(option)) = &module.options[3] {
@@ -184,7 +186,7 @@ fn program_module_add_test() {
     let add = new_module();
     let mut module1 = add.clone();
     module1.parent = Some(module.id);
-    expect.options[3] = ProgramModuleOption::ProgramModule(Some(module1));
+    expect.options[3] = ProgramModuleOption::ProgramModule(Some(Arc::new(module1)));
     assert_eq!(module.add(module.id, DotEveryEditorOperationIndex::OptionInputFiltered(2), &add), Ok(()));
     assert_eq!(module, expect);
     if let ProgramModuleOption::ProgramModule(Some(option)) = &module.options[3] {
@@ -196,7 +198,7 @@ fn program_module_add_test() {
     let add = new_module();
     let mut module1 = add.clone();
     module1.parent = Some(module.id);
-    expect.options[3] = ProgramModuleOption::ProgramModule(Some(module1));
+    expect.options[3] = ProgramModuleOption::ProgramModule(Some(Arc::new(module1)));
     assert_eq!(module.add(module.id, DotEveryEditorOperationIndex::OptionProgramModuleFiltered(1), &add), Ok(()));
     assert_eq!(module, expect);
     if let ProgramModuleOption::ProgramModule(Some(option)) = &module.options[3] {
@@ -210,10 +212,11 @@ fn program_module_add_test() {
         module.id
     } else { unreachable!(); };
     if let ProgramModuleOption::ProgramModule(Some(module)) = &mut expect.options[2] {
+        let module = Arc::make_mut(module);
         if let ProgramModuleChildItems::BlockVertical(list) = &mut module.child {
             let mut module1 = add.clone();
             module1.parent = Some(module.id);
-            list.insert(3, module1);
+            list.insert(3, Arc::new(module1));
         } else { unreachable!(); }
     } else { unreachable!(); }
     assert_eq!(module.add(id, DotEveryEditorOperationIndex::Child(3), &add), Ok(()));
@@ -225,7 +228,7 @@ fn program_module_add_test() {
     if let ProgramModuleChildItems::BlockHorizontal(lists) = &mut expect.child {
         let mut module1 = add.clone();
         module1.parent = Some(module.id);
-        lists.insert(3, module1);
+        lists.insert(3, Arc::new(module1));
     } else { unreachable!(); }
     assert_eq!(module.add(module.id, DotEveryEditorOperationIndex::Child(3), &add), Ok(()));
     assert_eq!(module, expect);
@@ -240,26 +243,26 @@ fn program_module_remove_test() {
                 ProgramModuleOption::StringInput("test2".to_string()),
                 ProgramModuleOption::ProgramModule(
                     Some(
-                        ProgramModule::new_default(
+                        Arc::new(ProgramModule::new_default(
                             vec![
                                 ProgramModuleOption::StringSign("test".to_string()),
                                 ProgramModuleOption::StringInput("test2".to_string()),
                                 ProgramModuleOption::ProgramModule(
                                     Some(
-                                        ProgramModule::new_default(
+                                        Arc::new(ProgramModule::new_default(
                                             vec![ProgramModuleOption::ProgramModule(
                                                 Some(
-                                                    ProgramModule::new_default(
+                                                    Arc::new(ProgramModule::new_default(
                                                         Vec::new(),
-                                                        ProgramModuleChildItems::None)))],
-                                            ProgramModuleChildItems::None)))
+                                                        ProgramModuleChildItems::None))))],
+                                            ProgramModuleChildItems::None))))
                             ],
                             ProgramModuleChildItems::BlockVertical(
                                 (
-                                    (0..10).map(|_| { ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None) }).collect()))))),
+                                    (0..10).map(|_| { ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None) }).map(Arc::new).collect())))))),
                 ProgramModuleOption::ProgramModule(None)
             ], ProgramModuleChildItems::BlockHorizontal(
-                ((0..10).map(|_| { ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None) }).collect())
+                ((0..10).map(|_| { ProgramModule::new_default(Vec::new(), ProgramModuleChildItems::None) }).map(Arc::new).collect())
             ))
     };
 
@@ -285,6 +288,7 @@ fn program_module_remove_test() {
     } else { unreachable!(); };
     let mut expect = module.clone();
     if let ProgramModuleOption::ProgramModule(Some(option)) = &mut expect.options[2] {
+        let option = Arc::make_mut(option);
         option.options[2] = ProgramModuleOption::ProgramModule(None);
     } else { unreachable!(); };
     assert_eq!(module.remove(id), Ok(()));
@@ -309,6 +313,7 @@ fn program_module_remove_test() {
     } else { unreachable!(); };
     let mut expect = module.clone();
     if let ProgramModuleOption::ProgramModule(Some(expect)) = &mut expect.options[2] {
+        let expect = Arc::make_mut(expect);
         if let ProgramModuleChildItems::BlockVertical(lists) = &mut expect.child {
             lists.remove(3);
         } else { unreachable!(); }
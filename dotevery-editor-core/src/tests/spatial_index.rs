@@ -0,0 +1,70 @@
+use uuid::Uuid;
+
+use crate::spatial_index::SpatialIndex;
+use crate::tests::rect;
+
+#[test]
+fn spatial_index_innermost_test() {
+    let mut index = SpatialIndex::new(50.0);
+    let root = Uuid::new_v4();
+    let outer = Uuid::new_v4();
+    let inner = Uuid::new_v4();
+    let sibling = Uuid::new_v4();
+    index.insert(root, rect(0.0, 0.0, 1000.0, 1000.0));
+    index.insert(outer, rect(10.0, 10.0, 300.0, 200.0));
+    index.insert(inner, rect(40.0, 60.0, 100.0, 40.0));
+    index.insert(sibling, rect(10.0, 220.0, 300.0, 40.0));
+    assert_eq!(index.len(), 4);
+
+    assert_eq!(index.query(50.0, 70.0), vec![inner, outer, root]);
+    assert_eq!(index.query(200.0, 70.0), vec![outer, root]);
+    assert_eq!(index.query(200.0, 230.0), vec![sibling, root]);
+    assert_eq!(index.query(900.0, 900.0), vec![root]);
+    assert!(index.query(1100.0, 100.0).is_empty());
+    assert!(index.query(-10.0, -10.0).is_empty());
+}
+
+#[test]
+fn spatial_index_update_and_remove_test() {
+    let mut index = SpatialIndex::default();
+    let a = Uuid::new_v4();
+    index.insert(a, rect(0.0, 0.0, 10.0, 10.0));
+    assert_eq!(index.query(5.0, 5.0), vec![a]);
+
+    index.insert(a, rect(500.0, 500.0, 10.0, 10.0));
+    assert!(index.query(5.0, 5.0).is_empty());
+    assert_eq!(index.query(505.0, 505.0), vec![a]);
+    assert_eq!(index.len(), 1);
+
+    assert!(index.remove(&a).is_some());
+    assert!(index.remove(&a).is_none());
+    assert!(index.query(505.0, 505.0).is_empty());
+    assert!(index.is_empty());
+}
+
+#[test]
+fn spatial_index_nearest_test() {
+    let mut index = SpatialIndex::new(20.0);
+    let a = Uuid::new_v4();
+    let b = Uuid::new_v4();
+    index.insert(a, rect(0.0, 0.0, 100.0, 20.0));
+    index.insert(b, rect(0.0, 50.0, 100.0, 20.0));
+
+    assert!(index.query(50.0, 30.0).is_empty());
+    assert_eq!(index.nearest(50.0, 30.0, 15.0), vec![a]);
+    assert_eq!(index.nearest(50.0, 40.0, 15.0), vec![b]);
+    assert_eq!(index.nearest(50.0, 30.0, 25.0), vec![a, b]);
+    assert!(index.nearest(50.0, 35.0, 10.0).is_empty());
+    assert_eq!(index.nearest(130.0, 10.0, 40.0), vec![a]);
+}
+
+#[test]
+fn rect_test() {
+    let r = rect(10.0, 20.0, 30.0, 40.0);
+    assert!(r.encloses(20.0, 50.0));
+    assert!(!r.encloses(20.0, 70.0));
+    assert_eq!(r.area(), 1200.0);
+    assert_eq!(r.distance(20.0, 30.0), 0.0);
+    assert_eq!(r.distance(0.0, 30.0), 10.0);
+    assert_eq!(r.distance(43.0, 64.0), 5.0);
+}
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use crate::dotevery_editor::DotEveryEditor;
+use crate::layout::LayoutMetrics;
+use crate::program_module::{ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
+use crate::svg_export::{export_module_svg, export_svg, ModuleStyle, SvgExportStyle};
+use crate::tests::sign;
+
+#[derive(Clone, PartialEq)]
+struct Colored;
+
+impl ModuleStyle for Colored {
+    fn color(&self) -> Option<String> {
+        Some("#4c97ff".to_string())
+    }
+}
+
+#[test]
+fn export_module_svg_snapshot_test() {
+    let module = ProgramModule::new(vec![sign("say"), ProgramModuleOption::StringInput("<hi>".to_string()), ProgramModuleOption::ProgramModule(None)], ProgramModuleChildItems::None, Colored);
+    let metrics = LayoutMetrics::default();
+    let image = export_module_svg(&module, &metrics, &metrics, &SvgExportStyle::default());
+    assert_eq!(image.svg, r##"<svg xmlns="http://www.w3.org/2000/svg" width="140" height="54" viewBox="-8 -8 140 54" style="font:14px sans-serif"><g><path d="M 0 0 H 12 l 6 6 h 12 l 6 -6 H 124 V 38 H 36 l -6 6 h -12 l -6 -6 H 0 Z" style="fill:#4c97ff;stroke:rgba(0,0,0,0.3);stroke-width:1"/><text x="6" y="22" style="fill:#000;dominant-baseline:central">say</text><rect x="34" y="12" width="40" height="20" rx="4" style="fill:#fff;stroke:#888"/><text x="38" y="22" style="fill:#000;dominant-baseline:central">&lt;hi&gt;</text><path d="M 88 12 H 108 A 10 10 0 0 1 108 32 H 88 A 10 10 0 0 1 88 12 Z" style="fill:rgba(255,255,255,0.6);stroke:#aaa"/></g></svg>"##);
+    assert_eq!((image.width, image.height), (140.0, 54.0));
+}
+
+#[test]
+fn export_svg_test() {
+    let mut block = ProgramModule::new(vec![sign("if")], ProgramModuleChildItems::BlockVertical(vec![
+        Arc::new(ProgramModule::new(vec![sign("print")], ProgramModuleChildItems::None, Colored)),
+    ]), Colored);
+    let metrics = LayoutMetrics::default();
+    let style = SvgExportStyle { background: Some("white".to_string()), ..SvgExportStyle::default() };
+    let image = export_svg(&DotEveryEditor::new(vec![block.clone()]), &metrics, &metrics, &style);
+    assert!(image.svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg""#));
+    assert!(image.svg.contains(r#"style="fill:white""#));
+    assert!(image.svg.contains(">print</text>"));
+    assert_eq!(image.svg.matches("<g>").count(), 2);
+
+    block.collapsed = true;
+    let collapsed = export_svg(&DotEveryEditor::new(vec![block]), &metrics, &metrics, &style);
+    assert!(!collapsed.svg.contains(">print</text>"));
+    assert!(collapsed.height < image.height);
+}
@@ -0,0 +1,172 @@
+use crate::i18n::MessageCatalog;
+use crate::program_module::{ProgramModuleChildItems, ProgramModuleOption};
+use crate::template::{ChildLayoutTemplate, FromTemplateTag, ModuleOptionTemplate, PaletteTemplate, TemplateErrorMessage};
+
+#[derive(Clone, Debug, PartialEq)]
+enum TestType {
+    Print,
+    Case,
+    Add,
+}
+
+impl FromTemplateTag for TestType {
+    fn from_template_tag(tag: &str) -> Option<Self> {
+        match tag {
+            "print" => Some(TestType::Print),
+            "case" => Some(TestType::Case),
+            "add" => Some(TestType::Add),
+            _ => None,
+        }
+    }
+
+    fn type_tag(&self) -> Option<String> {
+        let tag = match self {
+            TestType::Print => "print",
+            TestType::Case => "case",
+            TestType::Add => "add",
+        };
+        Some(tag.to_string())
+    }
+}
+
+const TOML_SOURCE: &str = r##"
+[[categories]]
+name = "Statements"
+color = "#4c97ff"
+
+[[categories.modules]]
+type = "print"
+options = [{ kind = "sign", text = "print" }, { kind = "slot" }]
+
+[[categories.modules]]
+type = "case"
+child = "vertical"
+options = [{ kind = "sign", text = "case" }, { kind = "input", value = "0" }, { kind = "sign", text = ":" }]
+
+[[categories]]
+name = "Operators"
+
+[[categories.modules]]
+type = "add"
+options = [{ kind = "slot" }, { kind = "sign", text = "+" }, { kind = "slot" }]
+"##;
+
+const JSON_SOURCE: &str = r##"
+{
+    "categories": [
+        {
+            "name": "Statements",
+            "color": "#4c97ff",
+            "modules": [
+                { "type": "print", "options": [{ "kind": "sign", "text": "print" }, { "kind": "slot" }] },
+                {
+                    "type": "case",
+                    "child": "vertical",
+                    "options": [{ "kind": "sign", "text": "case" }, { "kind": "input", "value": "0" }, { "kind": "sign", "text": ":" }]
+                }
+            ]
+        },
+        {
+            "name": "Operators",
+            "modules": [
+                { "type": "add", "options": [{ "kind": "slot" }, { "kind": "sign", "text": "+" }, { "kind": "slot" }] }
+            ]
+        }
+    ]
+}
+"##;
+
+#[test]
+fn template_parse_test() {
+    let from_toml = PaletteTemplate::parse(TOML_SOURCE).unwrap();
+    let from_json = PaletteTemplate::parse(JSON_SOURCE).unwrap();
+    assert_eq!(from_toml, from_json);
+
+    assert_eq!(from_toml.categories.len(), 2);
+    assert_eq!(from_toml.categories[1].color, "");
+    let case = from_toml.find("case").unwrap();
+    assert_eq!(case.child, ChildLayoutTemplate::Vertical);
+    assert_eq!(case.options[1], ModuleOptionTemplate::Input { value: "0".to_string() });
+    assert_eq!(from_toml.find("print").unwrap().child, ChildLayoutTemplate::None);
+    assert!(from_toml.find("sub").is_none());
+}
+
+#[test]
+fn template_to_palette_test() {
+    let palette = PaletteTemplate::parse(TOML_SOURCE).unwrap().to_palette::<TestType>().unwrap();
+    assert_eq!(palette.categories.len(), 2);
+    assert_eq!(palette.categories[0].name, "Statements");
+    assert_eq!(palette.categories[0].color, "#4c97ff");
+    assert_eq!(palette.items().map(|module| module.type_data.clone()).collect::<Vec<_>>(), vec![TestType::Print, TestType::Case, TestType::Add]);
+
+    let print = &palette.categories[0].items[0];
+    assert_eq!(print.options, vec![ProgramModuleOption::StringSign("print".to_string()), ProgramModuleOption::ProgramModule(None)]);
+    assert_eq!(print.child, ProgramModuleChildItems::None);
+
+    let case = &palette.categories[0].items[1];
+    assert_eq!(case.options[1], ProgramModuleOption::StringInput("0".to_string()));
+    assert_eq!(case.child, ProgramModuleChildItems::BlockVertical(Vec::new()));
+
+    let first = PaletteTemplate::parse(TOML_SOURCE).unwrap().find("add").unwrap().instantiate::<TestType>().unwrap();
+    let second = PaletteTemplate::parse(TOML_SOURCE).unwrap().find("add").unwrap().instantiate::<TestType>().unwrap();
+    assert_ne!(first.id(), second.id());
+}
+
+#[test]
+fn template_limit_test() {
+    let source = r##"
+budget = 10
+
+[[categories]]
+name = "Operators"
+
+[[categories.modules]]
+type = "add"
+limit = 3
+options = [{ kind = "slot" }, { kind = "sign", text = "+" }, { kind = "slot" }]
+
+[[categories.modules]]
+type = "print"
+options = [{ kind = "sign", text = "print" }, { kind = "slot" }]
+"##;
+    let palette = PaletteTemplate::parse(source).unwrap().to_palette::<TestType>().unwrap();
+    assert_eq!(palette.budget, Some(10));
+    assert_eq!(palette.limit(&TestType::Add), Some(3));
+    assert_eq!(palette.limit(&TestType::Print), None);
+    assert_eq!(PaletteTemplate::parse(TOML_SOURCE).unwrap().to_palette::<TestType>().unwrap().budget, None);
+}
+
+#[test]
+fn template_error_test() {
+    let source = r#"{ "categories": [{ "name": "Unknown", "modules": [{ "type": "while", "options": [] }] }] }"#;
+    let template = PaletteTemplate::parse(source).unwrap();
+    assert_eq!(template.to_palette::<TestType>(), Err(TemplateErrorMessage::UnknownTypeTag("while".to_string())));
+
+    match PaletteTemplate::parse("{ \"categories\": ") {
+        Err(TemplateErrorMessage::JsonParseError(_)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match PaletteTemplate::parse("[[categories]]\nname = ") {
+        Err(TemplateErrorMessage::TomlParseError(_)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn template_localized_sign_test() {
+    let source = r#"{ "categories": [{ "name": "Statements", "modules": [{ "type": "print", "options": [{ "kind": "sign", "text": "print", "locales": { "ja": "表示" } }, { "kind": "slot" }] }] }] }"#;
+    let template = PaletteTemplate::parse(source).unwrap();
+    let print = template.find("print").unwrap().instantiate::<TestType>().unwrap();
+    assert_eq!(print.options[0], ProgramModuleOption::StringSign("print".to_string()));
+
+    let mut catalog = MessageCatalog::new("ja").with_fallback("en");
+    template.register_signs(&mut catalog);
+    assert_eq!(catalog.sign(Some("print"), 0, "print"), "表示");
+    assert_eq!(catalog.localize(&print, &TestType::type_tag).options[0], ProgramModuleOption::StringSign("表示".to_string()));
+    // the same text in another type or at another index keeps its own translation
+    assert_eq!(catalog.sign(Some("case"), 0, "print"), "print");
+    assert_eq!(catalog.sign(Some("print"), 1, "print"), "print");
+    assert_eq!(catalog.sign(None, 0, "print"), "print");
+    catalog.set_locale("en");
+    assert_eq!(catalog.sign(Some("print"), 0, "print"), "print");
+}
@@ -0,0 +1,85 @@
+use crate::util::Rect;
+use crate::viewport::{edge_scroll_speed, minimap_scale, snap_area, visible_items, MAX_SCALE, MIN_SCALE, ViewTransform};
+
+#[test]
+fn view_transform_test() {
+    let transform = ViewTransform::new(2.0, 100.0, 50.0);
+    assert_eq!(transform.to_content(120.0, 70.0), (10.0, 10.0));
+    assert_eq!(transform.to_page(10.0, 10.0), (120.0, 70.0));
+
+    let rect = transform.rect_to_content(&Rect { x: 120.0, y: 70.0, w: 40.0, h: 20.0 });
+    assert_eq!((rect.x, rect.y, rect.w, rect.h), (10.0, 10.0, 20.0, 10.0));
+    let rect = transform.rect_to_page(&rect);
+    assert_eq!((rect.x, rect.y, rect.w, rect.h), (120.0, 70.0, 40.0, 20.0));
+
+    assert_eq!(ViewTransform::new(100.0, 0.0, 0.0).scale, MAX_SCALE);
+    assert_eq!(ViewTransform::new(0.0, 0.0, 0.0).scale, MIN_SCALE);
+    assert_eq!(ViewTransform::new(f64::NAN, 0.0, 0.0).scale, 1.0);
+}
+
+#[test]
+fn view_transform_zoom_test() {
+    let transform = ViewTransform::new(1.0, 100.0, 50.0);
+    let zoomed = transform.zoomed_at(300.0, 250.0, 2.0);
+    assert_eq!(zoomed.scale, 2.0);
+    assert_eq!(zoomed.to_page(200.0, 200.0), (300.0, 250.0));
+    assert_eq!(zoomed.to_content(300.0, 250.0), transform.to_content(300.0, 250.0));
+    assert_eq!((zoomed.origin_x, zoomed.origin_y), (-100.0, -150.0));
+}
+
+#[test]
+fn edge_scroll_speed_test() {
+    assert_eq!(edge_scroll_speed(50.0, 0.0, 100.0, 10.0, 20.0), 0.0);
+    assert_eq!(edge_scroll_speed(5.0, 0.0, 100.0, 10.0, 20.0), -10.0);
+    assert_eq!(edge_scroll_speed(-30.0, 0.0, 100.0, 10.0, 20.0), -20.0);
+    assert_eq!(edge_scroll_speed(95.0, 0.0, 100.0, 10.0, 20.0), 10.0);
+    assert_eq!(edge_scroll_speed(150.0, 0.0, 100.0, 10.0, 20.0), 20.0);
+    assert_eq!(edge_scroll_speed(5.0, 0.0, 15.0, 10.0, 20.0), 0.0);
+}
+
+#[test]
+fn visible_items_test() {
+    let heights = [100.0, 100.0, 100.0, 100.0, 100.0];
+    let area = Rect { x: 0.0, y: 150.0, w: 300.0, h: 100.0 };
+    assert_eq!(visible_items(&heights, 0.0, 0.0, &area, 0.0), vec![false, true, true, false, false]);
+    assert_eq!(visible_items(&heights, 0.0, 0.0, &area, 60.0), vec![true, true, true, true, false]);
+    assert_eq!(visible_items(&heights, 200.0, 0.0, &area, 0.0), vec![true, false, false, false, false]);
+    assert!(visible_items(&[], 0.0, 0.0, &area, 0.0).is_empty());
+}
+
+#[test]
+fn visible_items_gap_test() {
+    // stack i spans 16 + 46i .. 46 + 46i
+    let heights = [30.0; 100];
+    let area = Rect { x: 0.0, y: 16.0 + 46.0 * 80.0, w: 300.0, h: 30.0 };
+    let visible = visible_items(&heights, 16.0, 16.0, &area, 0.0);
+    assert_eq!(visible.iter().enumerate().filter(|(_, v)| **v).map(|(i, _)| i).collect::<Vec<_>>(), vec![80]);
+    // without the gap the stacks drift far past the margin
+    let drifted = visible_items(&heights, 0.0, 0.0, &area, 400.0);
+    assert!(!drifted[80]);
+}
+
+#[test]
+fn snap_area_test() {
+    let area = Rect { x: 5.0, y: 150.0, w: 300.0, h: 100.0 };
+    assert_eq!(snap_area(&area, 200.0), Rect { x: 5.0, y: 0.0, w: 300.0, h: 400.0 });
+    assert_eq!(snap_area(&Rect { x: 5.0, y: 170.0, w: 300.0, h: 100.0 }, 200.0), snap_area(&area, 200.0));
+    assert_eq!(snap_area(&area, 0.0), area);
+}
+
+#[test]
+fn rect_intersects_test() {
+    let area = Rect { x: 0.0, y: 100.0, w: 300.0, h: 100.0 };
+    assert!(area.intersects(&Rect { x: 10.0, y: 50.0, w: 20.0, h: 60.0 }));
+    assert!(area.intersects(&Rect { x: -10.0, y: 0.0, w: 400.0, h: 400.0 }));
+    assert!(!area.intersects(&Rect { x: 10.0, y: 210.0, w: 20.0, h: 20.0 }));
+    assert!(!area.intersects(&Rect { x: 310.0, y: 150.0, w: 20.0, h: 20.0 }));
+}
+
+#[test]
+fn minimap_scale_test() {
+    assert_eq!(minimap_scale(&Rect { x: 0.0, y: 0.0, w: 400.0, h: 1000.0 }, 160.0, 200.0), 0.2);
+    assert_eq!(minimap_scale(&Rect { x: 0.0, y: 0.0, w: 800.0, h: 200.0 }, 160.0, 200.0), 0.2);
+    assert_eq!(minimap_scale(&Rect { x: 0.0, y: 0.0, w: 100.0, h: 100.0 }, 160.0, 200.0), 1.0);
+    assert_eq!(minimap_scale(&Rect { x: 0.0, y: 0.0, w: 0.0, h: 0.0 }, 160.0, 200.0), 1.0);
+}
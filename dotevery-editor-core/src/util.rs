@@ -0,0 +1,43 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub w: f64,
+    pub h: f64,
+}
+
+impl Rect {
+    pub fn encloses(&self, x: f64, y: f64) -> bool {
+        self.x < x && x < self.x + self.w && self.y < y && y < self.y + self.h
+    }
+
+    pub fn center(&self) -> (f64, f64) {
+        (self.x + self.w / 2f64, self.y + self.h / 2f64)
+    }
+
+    pub fn area(&self) -> f64 {
+        self.w * self.h
+    }
+
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.x <= other.x + other.w && other.x <= self.x + self.w && self.y <= other.y + other.h && other.y <= self.y + self.h
+    }
+
+    pub fn translated(&self, dx: f64, dy: f64) -> Rect {
+        Rect { x: self.x + dx, y: self.y + dy, w: self.w, h: self.h }
+    }
+
+    pub fn distance(&self, x: f64, y: f64) -> f64 {
+        let dx = f64::max(f64::max(self.x - x, x - (self.x + self.w)), 0f64);
+        let dy = f64::max(f64::max(self.y - y, y - (self.y + self.h)), 0f64);
+        f64::hypot(dx, dy)
+    }
+}
+
+pub trait Isomorphism {
+    fn isomorphisms(&self, other: &Self) -> bool;
+}
\ No newline at end of file
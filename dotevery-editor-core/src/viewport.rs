@@ -0,0 +1,123 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::util::Rect;
+
+pub const MIN_SCALE: f64 = 0.25;
+pub const MAX_SCALE: f64 = 4.0;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ViewTransform {
+    pub scale: f64,
+    pub origin_x: f64,
+    pub origin_y: f64,
+}
+
+impl Default for ViewTransform {
+    fn default() -> Self {
+        Self {
+            scale: 1f64,
+            origin_x: 0f64,
+            origin_y: 0f64,
+        }
+    }
+}
+
+impl ViewTransform {
+    pub fn new(scale: f64, origin_x: f64, origin_y: f64) -> Self {
+        Self {
+            scale: Self::clamp_scale(scale),
+            origin_x,
+            origin_y,
+        }
+    }
+
+    pub fn clamp_scale(scale: f64) -> f64 {
+        if scale.is_finite() {
+            MAX_SCALE.min(scale).max(MIN_SCALE)
+        } else {
+            1f64
+        }
+    }
+
+    pub fn to_content(&self, x: f64, y: f64) -> (f64, f64) {
+        ((x - self.origin_x) / self.scale, (y - self.origin_y) / self.scale)
+    }
+
+    pub fn to_page(&self, x: f64, y: f64) -> (f64, f64) {
+        (self.origin_x + x * self.scale, self.origin_y + y * self.scale)
+    }
+
+    pub fn rect_to_content(&self, rect: &Rect) -> Rect {
+        let (x, y) = self.to_content(rect.x, rect.y);
+        Rect {
+            x,
+            y,
+            w: rect.w / self.scale,
+            h: rect.h / self.scale,
+        }
+    }
+
+    pub fn rect_to_page(&self, rect: &Rect) -> Rect {
+        let (x, y) = self.to_page(rect.x, rect.y);
+        Rect {
+            x,
+            y,
+            w: rect.w * self.scale,
+            h: rect.h * self.scale,
+        }
+    }
+
+    pub fn zoomed_at(&self, x: f64, y: f64, scale: f64) -> Self {
+        let scale = Self::clamp_scale(scale);
+        let (content_x, content_y) = self.to_content(x, y);
+        Self {
+            scale,
+            origin_x: x - content_x * scale,
+            origin_y: y - content_y * scale,
+        }
+    }
+}
+
+pub fn edge_scroll_speed(position: f64, start: f64, end: f64, margin: f64, max_speed: f64) -> f64 {
+    if margin <= 0f64 || end - start < margin * 2f64 {
+        return 0f64;
+    }
+    if position < start + margin {
+        -max_speed * f64::min((start + margin - position) / margin, 1f64)
+    } else if position > end - margin {
+        max_speed * f64::min((position - (end - margin)) / margin, 1f64)
+    } else {
+        0f64
+    }
+}
+
+// whether each of the items stacked downwards from `top`, `gap` apart, intersects the area extended by `margin`
+pub fn visible_items(heights: &[f64], top: f64, gap: f64, area: &Rect, margin: f64) -> Vec<bool> {
+    let (start, end) = (area.y - margin, area.y + area.h + margin);
+    let mut y = top;
+    heights.iter().map(|h| {
+        let visible = y + h >= start && y <= end;
+        y += h + gap;
+        visible
+    }).collect()
+}
+
+// grows the area to multiples of `step`, so that small scrolls keep the same virtualized range
+pub fn snap_area(area: &Rect, step: f64) -> Rect {
+    if step <= 0f64 {
+        return area.clone();
+    }
+    let top = (area.y / step).floor() * step;
+    let bottom = ((area.y + area.h) / step).ceil() * step;
+    Rect { x: area.x, y: top, w: area.w, h: bottom - top }
+}
+
+// the scale that fits the content bounds into a minimap of the given size, never enlarging
+pub fn minimap_scale(bounds: &Rect, width: f64, height: f64) -> f64 {
+    if bounds.w <= 0f64 || bounds.h <= 0f64 {
+        return 1f64;
+    }
+    f64::min(f64::min(width / bounds.w, height / bounds.h), 1f64)
+}
@@ -0,0 +1,351 @@
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{Attribute, Data, DeriveInput, Error, Fields, Ident, Lit, Meta, MetaList, NestedMeta, parse_macro_input};
+
+enum OptionAttribute {
+    // text, locale -> displayed text
+    Sign(String, Vec<(String, String)>),
+    FieldSign,
+    Input(Option<Ident>),
+    Slot(Option<Ident>),
+}
+
+enum ChildAttribute {
+    None,
+    Vertical,
+    Horizontal,
+}
+
+struct VariantAttribute {
+    options: Vec<OptionAttribute>,
+    child: ChildAttribute,
+    category: Option<String>,
+    tag: Option<String>,
+    skip: bool,
+}
+
+struct CategoryAttribute {
+    name: String,
+    color: String,
+}
+
+#[proc_macro_derive(DotEveryModule, attributes(module, module_category))]
+pub fn derive_dotevery_module(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let variants = match &input.data {
+        Data::Enum(data) => &data.variants,
+        _ => return Err(Error::new_spanned(&input.ident, "DotEveryModule can only be derived for enums")),
+    };
+    let categories = input.attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("module_category"))
+        .map(parse_category_attribute)
+        .collect::<syn::Result<Vec<_>>>()?;
+    let variants = variants
+        .iter()
+        .map(|variant| Ok((variant, parse_variant_attribute(&variant.attrs)?)))
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let lib = quote! { ::dotevery_editor_core };
+
+    let mut create_arms = Vec::new();
+    let mut palette_types = Vec::new();
+    let mut palette_pushes = Vec::new();
+    let mut tag_arms = Vec::new();
+    let mut tag_prefixes = Vec::new();
+    let mut accessors = Vec::new();
+    let mut category_arms = Vec::new();
+    let mut type_tag_arms = Vec::new();
+    let mut sign_inserts = Vec::new();
+    for (variant, attribute) in &variants {
+        let variant_ident = &variant.ident;
+        let field_names = (0..variant.fields.len()).map(|i| format_ident!("__field{}", i)).collect::<Vec<_>>();
+        let pattern = match &variant.fields {
+            Fields::Unit => quote! { Self::#variant_ident },
+            Fields::Unnamed(_) => quote! { Self::#variant_ident(#(#field_names),*) },
+            Fields::Named(_) => quote! { Self::#variant_ident { .. } },
+        };
+        let any_pattern = match &variant.fields {
+            Fields::Unit => quote! { Self::#variant_ident },
+            Fields::Unnamed(_) => quote! { Self::#variant_ident(..) },
+            Fields::Named(_) => quote! { Self::#variant_ident { .. } },
+        };
+        let options = attribute.options.iter().map(|option| match option {
+            OptionAttribute::Sign(text, _) => Ok(quote! { #lib::program_module::ProgramModuleOption::StringSign(::std::string::String::from(#text)) }),
+            OptionAttribute::FieldSign => match &variant.fields {
+                Fields::Unnamed(fields) if !fields.unnamed.is_empty() => Ok(quote! { #lib::program_module::ProgramModuleOption::StringSign(::std::string::ToString::to_string(__field0)) }),
+                _ => Err(Error::new_spanned(variant_ident, "field_sign requires a tuple variant")),
+            },
+            OptionAttribute::Input(_) => Ok(quote! { #lib::program_module::ProgramModuleOption::StringInput(::std::string::String::new()) }),
+            OptionAttribute::Slot(_) => Ok(quote! { #lib::program_module::ProgramModuleOption::ProgramModule(::std::option::Option::None) }),
+        }).collect::<syn::Result<Vec<_>>>()?;
+        let child = match attribute.child {
+            ChildAttribute::None => quote! { #lib::program_module::ProgramModuleChildItems::None },
+            ChildAttribute::Vertical => quote! { #lib::program_module::ProgramModuleChildItems::BlockVertical(::std::vec::Vec::new()) },
+            ChildAttribute::Horizontal => quote! { #lib::program_module::ProgramModuleChildItems::BlockHorizontal(::std::vec::Vec::new()) },
+        };
+        create_arms.push(quote! {
+            #pattern => #lib::program_module::ProgramModule::new(::std::vec![#(#options),*], #child, ::std::clone::Clone::clone(self)),
+        });
+
+        if let Some(name) = &attribute.category {
+            let color = categories.iter().find(|category| &category.name == name).map(|category| category.color.as_str()).unwrap_or_default();
+            category_arms.push(quote! {
+                #any_pattern => ::std::option::Option::Some(#lib::dotevery_module::ModuleCategory { name: #name, color: #color }),
+            });
+        }
+
+        let tag = attribute.tag.clone().unwrap_or_else(|| to_snake_case(&variant_ident.to_string()));
+        type_tag_arms.push(quote! { #any_pattern => ::std::option::Option::Some(::std::string::String::from(#tag)), });
+        for (i, option) in attribute.options.iter().enumerate() {
+            if let OptionAttribute::Sign(_, locales) = option {
+                for (locale, text) in locales {
+                    sign_inserts.push(quote! { catalog.insert_sign(#tag, #i, #locale, #text); });
+                }
+            }
+        }
+        match &variant.fields {
+            Fields::Unit => {
+                if !attribute.skip {
+                    palette_types.push(quote! { Self::#variant_ident });
+                    let category = attribute.category.clone().unwrap_or_default();
+                    palette_pushes.push(quote! {
+                        palette.push(#category, #lib::dotevery_module::DotEveryModule::create_module(&Self::#variant_ident));
+                    });
+                }
+                tag_arms.push(quote! { #tag => ::std::option::Option::Some(Self::#variant_ident), });
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let prefix = format!("{}:", tag);
+                tag_prefixes.push(quote! {
+                    if let ::std::option::Option::Some(value) = tag.strip_prefix(#prefix) {
+                        return ::std::str::FromStr::from_str(value).ok().map(Self::#variant_ident);
+                    }
+                });
+            }
+            _ => {}
+        }
+
+        let variant_snake = to_snake_case(&variant_ident.to_string());
+        for (i, option) in attribute.options.iter().enumerate() {
+            match option {
+                OptionAttribute::Slot(Some(name)) => {
+                    let accessor = format_ident!("{}_{}", variant_snake, name);
+                    accessors.push(quote! {
+                        pub fn #accessor(module: &#lib::program_module::ProgramModule<Self>) -> ::std::option::Option<&#lib::program_module::ProgramModule<Self>> {
+                            match (&module.type_data, module.options.get(#i)) {
+                                (#any_pattern, ::std::option::Option::Some(#lib::program_module::ProgramModuleOption::ProgramModule(::std::option::Option::Some(inner)))) => ::std::option::Option::Some(&**inner),
+                                _ => ::std::option::Option::None,
+                            }
+                        }
+                    });
+                }
+                OptionAttribute::Input(Some(name)) => {
+                    let accessor = format_ident!("{}_{}", variant_snake, name);
+                    accessors.push(quote! {
+                        pub fn #accessor(module: &#lib::program_module::ProgramModule<Self>) -> ::std::option::Option<&str> {
+                            match (&module.type_data, module.options.get(#i)) {
+                                (#any_pattern, ::std::option::Option::Some(#lib::program_module::ProgramModuleOption::StringInput(value))) => ::std::option::Option::Some(value.as_str()),
+                                _ => ::std::option::Option::None,
+                            }
+                        }
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let category_names = categories.iter().map(|category| &category.name);
+    let category_colors = categories.iter().map(|category| &category.color);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #lib::dotevery_module::DotEveryModule for #ident #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn create_module(&self) -> #lib::program_module::ProgramModule<Self> {
+                match self {
+                    #(#create_arms)*
+                }
+            }
+
+            fn palette_types() -> ::std::vec::Vec<Self> {
+                ::std::vec![#(#palette_types),*]
+            }
+
+            fn categories() -> ::std::vec::Vec<#lib::dotevery_module::ModuleCategory> {
+                ::std::vec![
+                    #(#lib::dotevery_module::ModuleCategory { name: #category_names, color: #category_colors }),*
+                ]
+            }
+
+            #[allow(unreachable_patterns)]
+            fn category(&self) -> ::std::option::Option<#lib::dotevery_module::ModuleCategory> {
+                match self {
+                    #(#category_arms)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+
+            fn register_signs(catalog: &mut #lib::i18n::MessageCatalog) {
+                #(#sign_inserts)*
+            }
+
+            fn palette() -> #lib::palette::Palette<Self> {
+                let mut palette = #lib::palette::Palette::new(
+                    <Self as #lib::dotevery_module::DotEveryModule>::categories().into_iter()
+                        .map(|category| #lib::palette::PaletteCategory::new(category.name, category.color, ::std::vec::Vec::new()))
+                        .collect()
+                );
+                #(#palette_pushes)*
+                palette
+            }
+        }
+
+        impl #impl_generics #lib::template::FromTemplateTag for #ident #ty_generics #where_clause {
+            fn from_template_tag(tag: &str) -> ::std::option::Option<Self> {
+                #(#tag_prefixes)*
+                match tag {
+                    #(#tag_arms)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+
+            fn type_tag(&self) -> ::std::option::Option<::std::string::String> {
+                match self {
+                    #(#type_tag_arms)*
+                }
+            }
+        }
+
+        #[allow(dead_code)]
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #(#accessors)*
+        }
+    })
+}
+
+fn parse_category_attribute(attr: &Attribute) -> syn::Result<CategoryAttribute> {
+    let mut name = None;
+    let mut color = String::new();
+    for meta in nested_meta(attr)? {
+        match meta {
+            NestedMeta::Meta(Meta::NameValue(value)) if value.path.is_ident("name") => name = Some(lit_str(&value.lit)?),
+            NestedMeta::Meta(Meta::NameValue(value)) if value.path.is_ident("color") => color = lit_str(&value.lit)?,
+            meta => return Err(Error::new_spanned(meta, "expected `name = \"...\"` or `color = \"...\"`")),
+        }
+    }
+    match name {
+        Some(name) => Ok(CategoryAttribute { name, color }),
+        None => Err(Error::new_spanned(attr, "module_category requires `name = \"...\"`")),
+    }
+}
+
+fn parse_variant_attribute(attrs: &[Attribute]) -> syn::Result<VariantAttribute> {
+    let mut attribute = VariantAttribute {
+        options: Vec::new(),
+        child: ChildAttribute::None,
+        category: None,
+        tag: None,
+        skip: false,
+    };
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("module")) {
+        for meta in nested_meta(attr)? {
+            match meta {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("slot") => attribute.options.push(OptionAttribute::Slot(None)),
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("input") => attribute.options.push(OptionAttribute::Input(None)),
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("field_sign") => attribute.options.push(OptionAttribute::FieldSign),
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => attribute.skip = true,
+                NestedMeta::Meta(Meta::NameValue(value)) if value.path.is_ident("sign") => attribute.options.push(OptionAttribute::Sign(lit_str(&value.lit)?, Vec::new())),
+                NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("sign") => attribute.options.push(parse_sign_attribute(&list)?),
+                NestedMeta::Meta(Meta::NameValue(value)) if value.path.is_ident("slot") => attribute.options.push(OptionAttribute::Slot(Some(lit_ident(&value.lit)?))),
+                NestedMeta::Meta(Meta::NameValue(value)) if value.path.is_ident("input") => attribute.options.push(OptionAttribute::Input(Some(lit_ident(&value.lit)?))),
+                NestedMeta::Meta(Meta::NameValue(value)) if value.path.is_ident("category") => attribute.category = Some(lit_str(&value.lit)?),
+                NestedMeta::Meta(Meta::NameValue(value)) if value.path.is_ident("tag") => attribute.tag = Some(lit_str(&value.lit)?),
+                NestedMeta::Meta(Meta::NameValue(value)) if value.path.is_ident("child") => {
+                    attribute.child = match lit_str(&value.lit)?.as_str() {
+                        "none" => ChildAttribute::None,
+                        "vertical" => ChildAttribute::Vertical,
+                        "horizontal" => ChildAttribute::Horizontal,
+                        _ => return Err(Error::new_spanned(&value.lit, "expected \"none\", \"vertical\" or \"horizontal\"")),
+                    }
+                }
+                meta => return Err(Error::new_spanned(meta, "unknown module attribute")),
+            }
+        }
+    }
+    Ok(attribute)
+}
+
+// sign(text = "...", locales(ja = "...", ...))
+fn parse_sign_attribute(list: &MetaList) -> syn::Result<OptionAttribute> {
+    let mut text = None;
+    let mut locales = Vec::new();
+    for meta in &list.nested {
+        match meta {
+            NestedMeta::Meta(Meta::NameValue(value)) if value.path.is_ident("text") => text = Some(lit_str(&value.lit)?),
+            NestedMeta::Meta(Meta::List(inner)) if inner.path.is_ident("locales") => {
+                for locale in &inner.nested {
+                    match locale {
+                        NestedMeta::Meta(Meta::NameValue(value)) => match value.path.get_ident() {
+                            Some(ident) => locales.push((ident.to_string(), lit_str(&value.lit)?)),
+                            None => return Err(Error::new_spanned(&value.path, "expected a locale")),
+                        },
+                        meta => return Err(Error::new_spanned(meta, "expected `locale = \"...\"`")),
+                    }
+                }
+            }
+            meta => return Err(Error::new_spanned(meta, "expected `text = \"...\"` or `locales(...)`")),
+        }
+    }
+    match text {
+        Some(text) => Ok(OptionAttribute::Sign(text, locales)),
+        None => Err(Error::new_spanned(list, "sign requires `text = \"...\"`")),
+    }
+}
+
+fn nested_meta(attr: &Attribute) -> syn::Result<Vec<NestedMeta>> {
+    match attr.parse_meta()? {
+        Meta::List(list) => Ok(list.nested.into_iter().collect()),
+        meta => Err(Error::new_spanned(meta, "expected a list of attributes")),
+    }
+}
+
+fn lit_str(lit: &Lit) -> syn::Result<String> {
+    match lit {
+        Lit::Str(s) => Ok(s.value()),
+        lit => Err(Error::new_spanned(lit, "expected a string literal")),
+    }
+}
+
+fn lit_ident(lit: &Lit) -> syn::Result<Ident> {
+    let name = lit_str(lit)?;
+    syn::parse_str::<Ident>(&name)
+        .map(|_| Ident::new(&name, Span::call_site()))
+        .map_err(|_| Error::new_spanned(lit, "expected an identifier"))
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
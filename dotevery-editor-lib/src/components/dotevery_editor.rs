@@ -1,57 +1,239 @@
-use std::collections::HashMap;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
 
 use either::Either;
 use uuid::Uuid;
 use wasm_bindgen::__rt::std::collections::VecDeque;
 use wasm_bindgen::JsCast;
 use wasm_bindgen::prelude::*;
-use web_sys::Element;
+use web_sys::{AddEventListenerOptions, DataTransfer, Element, HtmlElement, HtmlInputElement};
 use yew::{Component, ComponentLink, Html};
 use yew::prelude::*;
+use yew::services::interval::IntervalTask;
+use yew::services::IntervalService;
 
 use crate::components::dotevery_editor_agent::{DotEveryEditorAgent, DotEveryEditorAgentInputMessage, DotEveryEditorAgentOutputMessage};
-use crate::components::drag_module_agent::{DragModuleAgent, DragModuleAgentInputMessage, DragModuleAgentOutputMessage};
+use crate::components::drag_module_agent::{DragMode, DragModuleAgent, DragModuleAgentInputMessage, DragModuleAgentOutputMessage};
 use crate::components::dragging_program_module::{DraggingProgramModuleComponent, DraggingProgramModuleProperties};
+use crate::components::minimap::{MinimapComponent, MinimapProperties};
+use crate::components::outline::{OutlineComponent, OutlineProperties};
 use crate::components::program_module::{get_page_offset, ProgramModuleComponent, ProgramModuleComponentImplTypeDefault, ProgramModuleComponentImplTypeListOnly, ProgramModuleDefault, ProgramModuleProperties};
+use crate::components::program_module_renderer::ProgramModuleRenderer;
+use crate::components::svg_program::{SvgProgramComponent, SvgProgramProperties};
 // use crate::components::program_module_list::{ProgramModuleListComponent, ProgramModuleListProperties};
+use crate::logic::cursor::{CursorKey, CursorPosition, EditorCursor};
 use crate::logic::dotevery_editor::DotEveryEditor;
-use crate::logic::dotevery_editor_controller::DotEveryEditorController;
+use crate::logic::dotevery_editor_controller::{ContextMenuItem, DotEveryEditorController};
+use crate::logic::editor_messages::editor_messages;
+use crate::logic::i18n::MessageCatalog;
+use crate::logic::palette::Palette;
 use crate::logic::program_module::{ProgramModule, ProgramModuleChildItems};
+use crate::logic::layout::{EditorLayout, LayoutMetrics};
+use crate::logic::outline::plain_text;
+use crate::logic::viewport::{edge_scroll_speed, snap_area, visible_items, ViewTransform};
 use crate::util::Rect;
 
 // use crate::logic::program_module_list::ProgramModuleList;
 
-#[derive(Clone, Properties, Default)]
+const ZOOM_STEP: f64 = 1.1;
+const AUTO_SCROLL_INTERVAL: Duration = Duration::from_millis(16);
+const AUTO_SCROLL_MARGIN: f64 = 40.0;
+const AUTO_SCROLL_SPEED: f64 = 16.0;
+const DEFAULT_DRAG_START_DISTANCE: f64 = 4.0;
+// modules farther than this from the viewport are rendered as placeholders
+const VIRTUALIZE_MARGIN: f64 = 400.0;
+const VIRTUALIZE_STEP: f64 = 200.0;
+// a .program_module_placeholder between top level stacks, min-height and both margins
+const STACK_GAP: f64 = 16.0;
+const NAVIGATE_MARGIN: f64 = 20.0;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DragThreshold {
+    pub distance: f64,
+    pub delay: Duration,
+}
+
+impl Default for DragThreshold {
+    fn default() -> Self {
+        Self {
+            distance: DEFAULT_DRAG_START_DISTANCE,
+            delay: Duration::from_millis(0),
+        }
+    }
+}
+
+impl DragThreshold {
+    pub fn new(distance: f64, delay: Duration) -> Self {
+        Self { distance, delay }
+    }
+
+    pub(crate) fn is_exceeded(&self, dx: i32, dy: i32) -> bool {
+        f64::hypot(dx as f64, dy as f64) >= self.distance
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum EditorRenderer {
+    Html,
+    Svg,
+}
+
+#[derive(Clone, Properties)]
 pub struct DotEveryEditorProperties {
     // pub(crate) dotevery_editor: DotEveryEditor,
+    #[prop_or_default]
+    pub drag_threshold: DragThreshold,
+    #[prop_or(EditorRenderer::Html)]
+    pub renderer: EditorRenderer,
+    #[prop_or(false)]
+    pub outline: bool,
+    #[prop_or(false)]
+    pub minimap: bool,
+    #[prop_or_else(default_messages)]
+    pub messages: Rc<MessageCatalog>,
+    // no dragging, palette, trash area or input editing, the cursor can still select and copy
+    // every editor shows the one document of the agents, DotEveryViewerComponent shows a program of its own
+    #[prop_or(false)]
+    pub read_only: bool,
+}
+
+pub(crate) fn default_messages() -> Rc<MessageCatalog> {
+    Rc::new(editor_messages())
+}
+
+impl Default for DotEveryEditorProperties {
+    fn default() -> Self {
+        Self {
+            drag_threshold: DragThreshold::default(),
+            renderer: EditorRenderer::Html,
+            outline: false,
+            minimap: false,
+            messages: default_messages(),
+            read_only: false,
+        }
+    }
 }
 
 impl DotEveryEditorProperties {
     pub fn create() -> Self {
-        Self {}
+        Self::default()
+    }
+
+    pub fn renderer(mut self, renderer: EditorRenderer) -> Self {
+        self.renderer = renderer;
+        self
+    }
+
+    pub fn drag_threshold(mut self, drag_threshold: DragThreshold) -> Self {
+        self.drag_threshold = drag_threshold;
+        self
+    }
+
+    pub fn outline(mut self, outline: bool) -> Self {
+        self.outline = outline;
+        self
+    }
+
+    pub fn minimap(mut self, minimap: bool) -> Self {
+        self.minimap = minimap;
+        self
+    }
+
+    pub fn messages(mut self, messages: MessageCatalog) -> Self {
+        self.messages = Rc::new(messages);
+        self
+    }
+
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
     }
 }
 
 pub struct DotEveryEditorComponent<Controller, Type = ()>
     where Controller: 'static + DotEveryEditorController<Type>,
-          Type: 'static + Clone + PartialEq {
+          Type: 'static + Clone + PartialEq + ProgramModuleRenderer {
     link: ComponentLink<Self>,
     props: DotEveryEditorProperties,
     trash_area_ref: NodeRef,
+    editor_ref: NodeRef,
+    viewport_ref: NodeRef,
+    content_ref: NodeRef,
     dragging_component_props: Option<DraggingProgramModuleProperties<Type>>,
     drag_module_agent_bridge: Box<dyn Bridge<DragModuleAgent<Controller, Type>>>,
     logic_agent_bridge: Box<dyn Bridge<DotEveryEditorAgent<Controller, Type>>>,
     logic_data: DotEveryEditor<Type>,
-    palette_data: Vec<ProgramModule<Type>>,
+    shared_list: Vec<Arc<ProgramModule<Type>>>,
+    stack_heights: HashMap<Uuid, f64>,
+    // measured tops in content coordinates, give the offset of the list and the gap between stacks
+    stack_tops: HashMap<Uuid, f64>,
+    visible_area: Option<Rect>,
+    view_area: Option<Rect>,
+    layout_metrics: LayoutMetrics,
+    // estimated positions for the outline, the minimap and the virtualized modules
+    layout: EditorLayout,
+    palette_data: Palette<Type>,
+    palette_category: usize,
+    palette_filter: String,
+    cursor: EditorCursor,
+    dragging: Rc<Cell<bool>>,
+    copying: bool,
+    trash_hovered: bool,
+    view_transform: ViewTransform,
+    // the transform the drag agent last received, the zoom changes view_transform before the DOM catches up
+    sent_view_transform: Option<ViewTransform>,
+    pending_scroll: Option<(f64, f64)>,
+    space_held: Rc<Cell<bool>>,
+    panned: bool,
+    panning: Option<(i32, i32)>,
+    pointer: (i32, i32),
+    auto_scroll_task: Option<IntervalTask>,
+    context_menu: Option<ContextMenu>,
+}
+
+struct ContextMenu {
+    id: Uuid,
+    x: i32,
+    y: i32,
+    items: Vec<ContextMenuItem>,
+    help: Option<String>,
+    show_help: bool,
+}
+
+pub enum ContextMenuAction {
+    Duplicate,
+    Delete,
+    ToggleCollapsed,
+    ToggleDisabled,
+    EditComment,
+    ShowHelp,
+    Custom(String),
 }
 
 pub enum DotEveryEditorMessage<Controller, Type>
     where Controller: 'static + DotEveryEditorController<Type>,
-          Type: 'static + Clone + PartialEq {
+          Type: 'static + Clone + PartialEq + ProgramModuleRenderer {
     Ignore,
-    MouseMove { mouse_x: i32, mouse_y: i32 },
-    NoDrag,
     UpdateChildRect { id: Uuid, rect: Rect },
+    SelectPaletteCategory(usize),
+    UpdatePaletteFilter(String),
+    KeyDown(CursorKey),
+    SpaceDown,
+    SpaceUp,
+    Zoom { x: i32, y: i32, delta: f64 },
+    StartPan { x: i32, y: i32 },
+    PointerMove { x: i32, y: i32 },
+    EndPan,
+    AutoScroll,
+    ViewportChanged,
+    VisibleAreaChanged,
+    NavigateToModule(Uuid),
+    NavigateToPoint { x: f64, y: f64 },
+    ContextMenuAction(ContextMenuAction),
+    CloseContextMenu,
     SendDragModuleAgentMessage(DragModuleAgentInputMessage<Type>),
     OutputFromLogicAgent(DotEveryEditorAgentOutputMessage<Type, Controller::Output>),
     OutputFromDragModuleAgent(DragModuleAgentOutputMessage<Type>),
@@ -59,7 +241,7 @@ pub enum DotEveryEditorMessage<Controller, Type>
 
 impl<Controller, T> Component for DotEveryEditorComponent<Controller, T>
     where Controller: 'static + DotEveryEditorController<T>,
-          T: 'static + Clone + PartialEq {
+          T: 'static + Clone + PartialEq + ProgramModuleRenderer {
     type Message = DotEveryEditorMessage<Controller, T>;
     type Properties = DotEveryEditorProperties;
 
@@ -82,11 +264,36 @@ impl<Controller, T> Component for DotEveryEditorComponent<Controller, T>
             link,
             props,
             trash_area_ref: NodeRef::default(),
+            editor_ref: NodeRef::default(),
+            viewport_ref: NodeRef::default(),
+            content_ref: NodeRef::default(),
             dragging_component_props: None,
             drag_module_agent_bridge,
             logic_agent_bridge,
             logic_data: DotEveryEditor::new(Vec::new()),
-            palette_data: Vec::new(),
+            shared_list: Vec::new(),
+            stack_heights: HashMap::new(),
+            stack_tops: HashMap::new(),
+            visible_area: None,
+            view_area: None,
+            layout_metrics: LayoutMetrics::default(),
+            layout: EditorLayout::new(&DotEveryEditor::<T>::new(Vec::new()), &LayoutMetrics::default()),
+            palette_data: Palette::default(),
+            palette_category: 0,
+            palette_filter: String::new(),
+            cursor: EditorCursor::default(),
+            dragging: Rc::new(Cell::new(false)),
+            copying: false,
+            trash_hovered: false,
+            view_transform: ViewTransform::default(),
+            sent_view_transform: None,
+            pending_scroll: None,
+            space_held: Rc::new(Cell::new(false)),
+            panned: false,
+            panning: None,
+            pointer: (0, 0),
+            auto_scroll_task: None,
+            context_menu: None,
         }
     }
 
@@ -101,8 +308,8 @@ impl<Controller, T> Component for DotEveryEditorComponent<Controller, T>
                 match msg {
                     DotEveryEditorAgentOutputMessage::ModuleUpdated(logic) => {
                         // clog!("update logic",format!("{:#?}",logic.list.iter().map(ProgramModule::isomorphic_transform).collect::<Vec<ProgramModule<()>>>()));
-                        if self.logic_data.id != logic.id {
-                            self.drag_module_agent_bridge.send(DragModuleAgentInputMessage::SetRootId(logic.id));
+                        if self.logic_data.id() != logic.id() {
+                            self.drag_module_agent_bridge.send(DragModuleAgentInputMessage::SetRootId(logic.id()));
                         }
                         // {
                         //     let logic = logic.clone();
@@ -120,40 +327,210 @@ impl<Controller, T> Component for DotEveryEditorComponent<Controller, T>
                         //     }
                         // }
                         self.logic_data = logic;
+                        self.update_shared_list();
                         // self.drag_module_agent_bridge.send(DragModuleAgentInputMessage::Clear);
                         true
                     }
+                    DotEveryEditorAgentOutputMessage::ModulesUpdated(modules) => {
+                        let mut changed = Vec::new();
+                        for module in modules {
+                            let id = module.id();
+                            if let Err(err) = self.logic_data.replace_module(module) {
+                                clog!(format!("{:?}", err));
+                                continue;
+                            }
+                            if let Some(i) = self.logic_data.list.iter().position(|module| module.get_module(id).is_ok()) {
+                                self.shared_list[i] = Arc::new(top_level_module(&self.logic_data.list[i]));
+                                changed.push(i);
+                            }
+                        }
+                        self.layout = EditorLayout::new(&self.logic_data, &self.layout_metrics);
+                        for i in changed {
+                            self.estimate_stack_height(i);
+                        }
+                        true
+                    }
+                    DotEveryEditorAgentOutputMessage::PaletteUpdated(_) if self.props.read_only => false,
                     DotEveryEditorAgentOutputMessage::PaletteUpdated(palette) => {
                         self.palette_data = palette;
                         true
                     }
+                    DotEveryEditorAgentOutputMessage::Selected(position) => {
+                        self.cursor.set_position(Some(position));
+                        true
+                    }
+                    DotEveryEditorAgentOutputMessage::ContextMenu { .. } if self.props.read_only => false,
+                    DotEveryEditorAgentOutputMessage::ContextMenu { id, x, y, items, help } => {
+                        self.context_menu = Some(ContextMenu { id, x, y, items, help, show_help: false });
+                        true
+                    }
                     _ => false
                 }
             Self::Message::OutputFromDragModuleAgent(msg) =>
                 match msg {
-                    DragModuleAgentOutputMessage::CreateDragComponent { offset_x, offset_y, module } => {
-                        self.dragging_component_props = Some(DraggingProgramModuleProperties { offset_x, offset_y, program_module: module, visibility: true });
+                    DragModuleAgentOutputMessage::CreateDragComponent { offset_x, offset_y, module, following, mode } => {
+                        self.dragging_component_props = Some(DraggingProgramModuleProperties { offset_x, offset_y, program_module: module, following, visibility: true, scale: self.view_transform.scale, renderer: self.props.renderer, messages: Rc::clone(&self.props.messages) });
+                        self.dragging.set(true);
+                        self.copying = mode == DragMode::Duplicate;
+                        self.auto_scroll_task = Some(IntervalService::spawn(AUTO_SCROLL_INTERVAL, self.link.callback(|_| Self::Message::AutoScroll)));
                         true
                     }
                     DragModuleAgentOutputMessage::EndDrag => {
                         if let Some(component) = &mut self.dragging_component_props {
                             component.visibility = false;
                         }
+                        self.dragging.set(false);
+                        self.copying = false;
+                        self.trash_hovered = false;
+                        self.auto_scroll_task = None;
+                        true
+                    }
+                    DragModuleAgentOutputMessage::HoverTrashArea(hovered) => {
+                        self.trash_hovered = hovered;
                         true
                     }
                     _ => false,
                 }
-            Self::Message::MouseMove { mouse_x, mouse_y } => {
-                self.drag_module_agent_bridge.send(DragModuleAgentInputMessage::UpdateMousePosition { x: mouse_x, y: mouse_y });
+            Self::Message::UpdateChildRect { id, rect } => {
+                if self.logic_data.list.iter().any(|module| module.id() == id) {
+                    let rect = self.view_transform.rect_to_content(&rect);
+                    self.stack_heights.insert(id, rect.h);
+                    self.stack_tops.insert(id, rect.y);
+                }
                 false
             }
-            Self::Message::NoDrag => {
-                self.drag_module_agent_bridge.send(DragModuleAgentInputMessage::EndDrag);
+            Self::Message::SelectPaletteCategory(index) => {
+                self.palette_category = index;
+                true
+            }
+            Self::Message::UpdatePaletteFilter(filter) => {
+                self.palette_filter = filter;
+                true
+            }
+            Self::Message::KeyDown(CursorKey::Enter) | Self::Message::KeyDown(CursorKey::PickUp) | Self::Message::KeyDown(CursorKey::Drop) | Self::Message::KeyDown(CursorKey::Delete) if self.props.read_only => false,
+            Self::Message::KeyDown(key) => {
+                self.context_menu = None;
+                let editing = self.cursor.editing();
+                for operation in self.cursor.handle_key(&self.logic_data, key) {
+                    self.logic_agent_bridge.send(operation.into());
+                }
+                if editing && !self.cursor.editing() {
+                    if let Some(element) = self.editor_ref.cast::<HtmlElement>() {
+                        if let Err(err) = element.focus() {
+                            clog!(err);
+                        }
+                    }
+                }
+                true
+            }
+            Self::Message::SpaceDown => {
+                self.space_held.set(true);
+                self.panned = false;
                 false
             }
-            Self::Message::UpdateChildRect { id, rect } => {
+            Self::Message::SpaceUp => {
+                if !self.space_held.get() { return false; }
+                self.space_held.set(false);
+                self.panning = None;
+                if !self.panned {
+                    let key = if self.cursor.holding().is_some() { CursorKey::Drop } else { CursorKey::PickUp };
+                    self.link.send_message(Self::Message::KeyDown(key));
+                }
+                false
+            }
+            Self::Message::Zoom { x, y, delta } => {
+                self.update_view_transform();
+                let factor = if delta < 0. { ZOOM_STEP } else { 1. / ZOOM_STEP };
+                let zoomed = self.view_transform.zoomed_at(x as f64, y as f64, self.view_transform.scale * factor);
+                if zoomed.scale == self.view_transform.scale { return false; }
+                self.pending_scroll = Some((self.view_transform.origin_x - zoomed.origin_x, self.view_transform.origin_y - zoomed.origin_y));
+                self.view_transform.scale = zoomed.scale;
+                true
+            }
+            Self::Message::StartPan { x, y } => {
+                self.panning = Some((x, y));
+                self.panned = true;
+                false
+            }
+            Self::Message::PointerMove { x, y } => {
+                self.pointer = (x, y);
+                if let Some((last_x, last_y)) = self.panning {
+                    self.scroll_viewport((last_x - x) as f64, (last_y - y) as f64);
+                    self.panning = Some((x, y));
+                }
+                false
+            }
+            Self::Message::EndPan => {
+                self.panning = None;
+                false
+            }
+            Self::Message::AutoScroll => {
+                if let Some(element) = self.viewport_ref.cast::<Element>() {
+                    let rect = element.get_bounding_client_rect();
+                    let (x, y) = self.pointer;
+                    let dx = edge_scroll_speed(x as f64, rect.left(), rect.right(), AUTO_SCROLL_MARGIN, AUTO_SCROLL_SPEED);
+                    let dy = edge_scroll_speed(y as f64, rect.top(), rect.bottom(), AUTO_SCROLL_MARGIN, AUTO_SCROLL_SPEED);
+                    if dx != 0. || dy != 0. {
+                        self.scroll_viewport(dx, dy);
+                    }
+                }
                 false
             }
+            Self::Message::ViewportChanged => {
+                self.update_view_transform();
+                self.update_visible_area()
+            }
+            Self::Message::VisibleAreaChanged => true,
+            Self::Message::NavigateToModule(id) => {
+                self.logic_agent_bridge.send(DotEveryEditorAgentInputMessage::Select(CursorPosition::Module(id)));
+                if let Some(layout) = self.layout.get(id) {
+                    let (x, y) = self.view_transform.to_page(layout.rect.x, layout.rect.y);
+                    if let Some(viewport) = self.viewport_page_rect() {
+                        self.scroll_viewport(x - viewport.x - NAVIGATE_MARGIN, y - viewport.y - NAVIGATE_MARGIN);
+                    }
+                }
+                false
+            }
+            Self::Message::NavigateToPoint { x, y } => {
+                let (x, y) = self.view_transform.to_page(x, y);
+                if let Some(viewport) = self.viewport_page_rect() {
+                    let (center_x, center_y) = viewport.center();
+                    self.scroll_viewport(x - center_x, y - center_y);
+                }
+                false
+            }
+            Self::Message::ContextMenuAction(ContextMenuAction::ShowHelp) => {
+                if let Some(menu) = &mut self.context_menu {
+                    menu.show_help = true;
+                }
+                true
+            }
+            Self::Message::ContextMenuAction(action) => {
+                let id = match self.context_menu.take() {
+                    Some(menu) => menu.id,
+                    None => return false,
+                };
+                // every action is an ordinary agent operation, so the controller and the locks can still refuse it
+                let operation = match (action, self.logic_data.get_module(id)) {
+                    (ContextMenuAction::Duplicate, _) => Some(DotEveryEditorAgentInputMessage::Duplicate(id)),
+                    (ContextMenuAction::Delete, _) => Some(DotEveryEditorAgentInputMessage::Remove(id)),
+                    (ContextMenuAction::ToggleCollapsed, Ok(module)) => Some(DotEveryEditorAgentInputMessage::SetCollapsed { id, collapsed: !module.collapsed }),
+                    (ContextMenuAction::ToggleDisabled, Ok(module)) => Some(DotEveryEditorAgentInputMessage::SetDisabled { id, disabled: !module.disabled }),
+                    (ContextMenuAction::EditComment, Ok(module)) => {
+                        let current = module.comment.clone().unwrap_or_default();
+                        web_sys::window()
+                            .and_then(|window| window.prompt_with_message_and_default(&self.props.messages.get("editor.comment"), &current).ok().flatten())
+                            .map(|comment| DotEveryEditorAgentInputMessage::SetComment { id, comment: Some(comment) })
+                    }
+                    (ContextMenuAction::Custom(action), _) => Some(DotEveryEditorAgentInputMessage::ContextAction { id, action }),
+                    _ => None,
+                };
+                if let Some(operation) = operation {
+                    self.logic_agent_bridge.send(operation);
+                }
+                true
+            }
+            Self::Message::CloseContextMenu => self.context_menu.take().is_some(),
         }
     }
 
@@ -165,75 +542,241 @@ impl<Controller, T> Component for DotEveryEditorComponent<Controller, T>
     fn view(&self) -> Html {
         // clog!("view",format!("{:#?}",self.logic_data.list.iter().map(ProgramModule::isomorphic_transform).collect::<Vec<ProgramModule<()>>>()));
         let dragging = if let Some(dragging) = &self.dragging_component_props {
-            let dragging = dragging.clone();
+            let mut dragging = dragging.clone();
+            dragging.scale = self.view_transform.scale;
             html! {
                 <DraggingProgramModuleComponent<Controller, T> with dragging/>
             }
         } else {
             html! {}
         };
-        let mouse_move = self.link.callback(|e: MouseEvent| {
-            if e.buttons() == 1 {
-                Self::Message::MouseMove {
-                    mouse_x: e.page_x(),
-                    mouse_y: e.page_y(),
+        // let mut module = ProgramModule::new_default_with_id(Uuid::nil(), Vec::new(), ProgramModuleChildItems::BlockVertical(self.logic_data.list.iter().map(ProgramModule::deep_clone).collect()));
+        // module.parent = Some(self.logic_data.id);
+        let program = match self.props.renderer {
+            EditorRenderer::Html => {
+                let module = ProgramModuleProperties {
+                    program_module: Either::Right(ProgramModuleDefault {
+                        list: self.shared_list.clone(),
+                        virtual_heights: self.virtual_heights(),
+                        parent: self.logic_data.id(),
+                    }),
+                    rect_changed_callback: self.link.callback(|(id, rect)| { Self::Message::UpdateChildRect { id, rect } }),
+                    cursor: self.cursor.clone(),
+                    drag_threshold: self.props.drag_threshold,
+                    messages: Rc::clone(&self.props.messages),
+                    read_only: self.props.read_only,
+                };
+                html! {<ProgramModuleComponent<Controller, T, ProgramModuleComponentImplTypeDefault> with module/>}
+            }
+            EditorRenderer::Svg => {
+                let visible_area = self.visible_area.as_ref().map(|area| Rect { x: area.x - VIRTUALIZE_MARGIN, y: area.y - VIRTUALIZE_MARGIN, w: area.w + 2f64 * VIRTUALIZE_MARGIN, h: area.h + 2f64 * VIRTUALIZE_MARGIN });
+                let program = SvgProgramProperties { editor: self.logic_data.clone(), drag_threshold: self.props.drag_threshold, visible_area, messages: Rc::clone(&self.props.messages), read_only: self.props.read_only };
+                html! {<SvgProgramComponent<Controller, T> with program/>}
+            }
+        };
+        let palette = if self.props.read_only {
+            html! {}
+        } else {
+            let trash_class = if self.trash_hovered {
+                "program_module_palette program_module_palette_trash_hovered"
+            } else {
+                "program_module_palette"
+            };
+            html! {
+                <div ref=self.trash_area_ref.clone() class=trash_class>
+                    {self.view_palette()}
+                </div>
+            }
+        };
+        let class = if self.copying {
+            "dotevery_editor dotevery_editor_dragging dotevery_editor_copying"
+        } else if self.dragging.get() {
+            "dotevery_editor dotevery_editor_dragging"
+        } else if self.props.read_only {
+            "dotevery_editor dotevery_editor_read_only"
+        } else {
+            "dotevery_editor"
+        };
+        // the selected module as text, the selection inside an input is left to the browser
+        let selected_text = match self.cursor.position() {
+            Some(CursorPosition::Module(id)) => self.logic_data.get_module(id).ok().map(|module| plain_text(&self.props.messages.localize(module, &T::sign_tag))),
+            _ => None,
+        };
+        let copy = self.link.callback(move |e: Event| {
+            // ClipboardEvent needs web_sys_unstable_apis, so clipboardData is read off the plain event
+            let data = js_sys::Reflect::get(&e, &JsValue::from_str("clipboardData")).ok().and_then(|data| data.dyn_into::<DataTransfer>().ok());
+            if let (Some(text), Some(data)) = (&selected_text, data) {
+                if data.set_data("text/plain", text).is_ok() {
+                    e.prevent_default();
                 }
+            }
+            Self::Message::Ignore
+        });
+        let editing = self.cursor.editing();
+        let holding = self.cursor.holding().is_some();
+        let key_down = self.link.callback(move |e: KeyboardEvent| {
+            let on_input = e.target().map(|target| target.has_type::<HtmlInputElement>()).unwrap_or(false);
+            if on_input && !editing {
+                return Self::Message::Ignore;
+            }
+            let key = match e.key().as_str() {
+                "Enter" if holding && !editing => Some(CursorKey::Drop),
+                "Enter" => Some(CursorKey::Enter),
+                "Escape" => Some(CursorKey::Escape),
+                _ if editing => None,
+                "ArrowUp" => Some(CursorKey::Up),
+                "ArrowDown" => Some(CursorKey::Down),
+                "ArrowLeft" => Some(CursorKey::Left),
+                "ArrowRight" => Some(CursorKey::Right),
+                " " => {
+                    e.prevent_default();
+                    return if e.repeat() { Self::Message::Ignore } else { Self::Message::SpaceDown };
+                }
+                "Delete" | "Backspace" => Some(CursorKey::Delete),
+                _ => None,
+            };
+            if let Some(key) = key {
+                e.prevent_default();
+                Self::Message::KeyDown(key)
             } else {
-                Self::Message::NoDrag
+                Self::Message::Ignore
             }
         });
-        // let mut module = ProgramModule::new_default_with_id(Uuid::nil(), Vec::new(), ProgramModuleChildItems::BlockVertical(self.logic_data.list.iter().map(ProgramModule::deep_clone).collect()));
-        // module.parent = Some(self.logic_data.id);
-        let module = ProgramModuleProperties {
-            program_module: Either::Right(ProgramModuleDefault {
-                list: self.logic_data.list.iter().map(|module| {
-                    let mut module = module.clone();
-                    module.parent = Some(Uuid::nil());
-                    module
-                }).collect(),
-                parent: self.logic_data.id,
-            }),
-            rect_changed_callback: self.link.callback(|(id, rect)| { Self::Message::UpdateChildRect { id, rect } }),
-        };
-        let palette = self.palette_data.iter().map(|p| {
-            let module = ProgramModuleProperties {
-                program_module: Either::Left(p.clone()),
-                rect_changed_callback: self.link.callback(|_| { Self::Message::Ignore }),
+        let key_up = self.link.callback(move |e: KeyboardEvent| {
+            if e.key() == " " && !editing {
+                Self::Message::SpaceUp
+            } else {
+                Self::Message::Ignore
+            }
+        });
+        let wheel = self.link.callback(|e: WheelEvent| {
+            if e.ctrl_key() {
+                e.prevent_default();
+                Self::Message::Zoom { x: e.page_x(), y: e.page_y(), delta: e.delta_y() }
+            } else {
+                Self::Message::Ignore
+            }
+        });
+        let side = if self.props.outline || self.props.minimap {
+            let outline = if self.props.outline {
+                let selected = self.cursor.position().map(|position| match position {
+                    CursorPosition::Module(id) | CursorPosition::Input(id, _) | CursorPosition::Slot(id, _) => id,
+                });
+                let outline = OutlineProperties {
+                    editor: self.logic_data.clone(),
+                    selected,
+                    onselect: self.link.callback(Self::Message::NavigateToModule),
+                    messages: Rc::clone(&self.props.messages),
+                };
+                html! {<OutlineComponent<T> with outline/>}
+            } else {
+                html! {}
+            };
+            let minimap = if self.props.minimap {
+                let minimap = MinimapProperties {
+                    layout: self.layout.clone(),
+                    view_area: self.view_area.clone(),
+                    onnavigate: self.link.callback(|(x, y)| Self::Message::NavigateToPoint { x, y }),
+                };
+                html! {<MinimapComponent with minimap/>}
+            } else {
+                html! {}
             };
             html! {
-                <ProgramModuleComponent<Controller, T, ProgramModuleComponentImplTypeListOnly> with module/>
+                <div class="dotevery_editor_side">
+                    {outline}
+                    {minimap}
+                </div>
             }
-        });
+        } else {
+            html! {}
+        };
+        let content_style = format!("transform:scale({});", self.view_transform.scale);
+        let viewport = html! {
+            <div ref=self.viewport_ref.clone() class="dotevery_editor_viewport" onwheel=wheel onscroll=self.link.callback(|_| Self::Message::ViewportChanged)>
+                <div ref=self.content_ref.clone() class="dotevery_editor_content" style=content_style>
+                    {program}
+                </div>
+            </div>
+        };
         html! {
-            <div onmousemove=mouse_move class="dotevery_editor">
+            <div ref=self.editor_ref.clone() tabindex="0" onkeydown=key_down onkeyup=key_up oncopy=copy class=class>
                 // {"DotEvery.Editor"}
                 // {self.logic_data.id}
                 <div class="editor_window">
-                    <div ref=self.trash_area_ref.clone() class="program_module_palette">
-                        {for palette}
-                    </div>
-                    <ProgramModuleComponent<Controller, T, ProgramModuleComponentImplTypeDefault> with module/>
+                    {palette}
+                    {viewport}
+                    {side}
                 </div>
                 {dragging}
+                {self.view_context_menu()}
             </div>
         }
     }
 
     fn rendered(&mut self, first_render: bool) {
-        if first_render {
+        // nothing can be dragged, so the window listeners are only needed by editable instances
+        if first_render && !self.props.read_only {
             let window = web_sys::window().unwrap();
             let callback = self.link.callback(|m| m);
-            let closure = Closure::wrap(Box::new(move |e: MouseEvent| {
-                // clog!("mousemove", e.page_x(), e.page_y());
+            let closure = Closure::wrap(Box::new(move |e: PointerEvent| {
+                // clog!("pointermove", e.page_x(), e.page_y());
+                callback.emit(Self::Message::PointerMove { x: e.client_x(), y: e.client_y() });
                 callback.emit(Self::Message::SendDragModuleAgentMessage(DragModuleAgentInputMessage::UpdateMousePosition { x: e.page_x(), y: e.page_y() }));
-                if e.buttons() != 1 {
+                if e.buttons() == 0 {
                     callback.emit(Self::Message::SendDragModuleAgentMessage(DragModuleAgentInputMessage::EndDrag));
                 }
             }) as Box<dyn FnMut(_)>);
-            if let Err(err) = window.add_event_listener_with_callback("mousemove", closure.as_ref().unchecked_ref()) {
-                clog!("add mousemove event failed",err);
+            if let Err(err) = window.add_event_listener_with_callback("pointermove", closure.as_ref().unchecked_ref()) {
+                clog!("add pointermove event failed",err);
             }
             closure.forget();
+            for event in &["pointerup", "pointercancel"] {
+                let callback = self.link.callback(|m| m);
+                let closure = Closure::wrap(Box::new(move |e: PointerEvent| {
+                    callback.emit(Self::Message::SendDragModuleAgentMessage(DragModuleAgentInputMessage::UpdateMousePosition { x: e.page_x(), y: e.page_y() }));
+                    callback.emit(Self::Message::SendDragModuleAgentMessage(DragModuleAgentInputMessage::EndDrag));
+                    callback.emit(Self::Message::EndPan);
+                }) as Box<dyn FnMut(_)>);
+                if let Err(err) = window.add_event_listener_with_callback(event, closure.as_ref().unchecked_ref()) {
+                    clog!(format!("add {} event failed", event), err);
+                }
+                closure.forget();
+            }
+            let dragging = Rc::clone(&self.dragging);
+            let closure = Closure::wrap(Box::new(move |e: Event| {
+                if dragging.get() {
+                    e.prevent_default();
+                }
+            }) as Box<dyn FnMut(_)>);
+            if let Err(err) = window.add_event_listener_with_callback_and_add_event_listener_options("touchmove", closure.as_ref().unchecked_ref(), AddEventListenerOptions::new().passive(false)) {
+                clog!("add touchmove event failed",err);
+            }
+            closure.forget();
+        }
+        if first_render {
+            if let Some(element) = self.viewport_ref.cast::<Element>() {
+                let callback = self.link.callback(|m| m);
+                let space_held = Rc::clone(&self.space_held);
+                let closure = Closure::wrap(Box::new(move |e: PointerEvent| {
+                    if space_held.get() {
+                        e.prevent_default();
+                        e.stop_propagation();
+                        callback.emit(Self::Message::StartPan { x: e.client_x(), y: e.client_y() });
+                    }
+                }) as Box<dyn FnMut(_)>);
+                if let Err(err) = element.add_event_listener_with_callback_and_bool("pointerdown", closure.as_ref().unchecked_ref(), true) {
+                    clog!("add pointerdown event failed",err);
+                }
+                closure.forget();
+            }
+        }
+        if let Some((dx, dy)) = self.pending_scroll.take() {
+            self.scroll_viewport(dx, dy);
+        }
+        self.update_view_transform();
+        if self.update_visible_area() {
+            self.link.send_message(Self::Message::VisibleAreaChanged);
         }
         if let Some(element) = self.trash_area_ref.cast::<Element>() {
             let rect = element.get_bounding_client_rect();
@@ -246,4 +789,279 @@ impl<Controller, T> Component for DotEveryEditorComponent<Controller, T>
             });
         }
     }
-}
\ No newline at end of file
+}
+
+impl<Controller, T> DotEveryEditorComponent<Controller, T>
+    where Controller: 'static + DotEveryEditorController<T>,
+          T: 'static + Clone + PartialEq + ProgramModuleRenderer {
+    // top level modules that did not change keep their Arc, so their components skip re-rendering
+    fn update_shared_list(&mut self) {
+        let old = std::mem::take(&mut self.shared_list);
+        let mut changed = Vec::new();
+        self.shared_list = self.logic_data.list.iter().enumerate().map(|(i, module)| {
+            old.iter()
+                .find(|old| old.id() == module.id() && old.options == module.options && old.child == module.child && old.type_data == module.type_data && old.collapsed == module.collapsed)
+                .cloned()
+                .unwrap_or_else(|| {
+                    changed.push(i);
+                    Arc::new(top_level_module(module))
+                })
+        }).collect();
+        self.layout = EditorLayout::new(&self.logic_data, &self.layout_metrics);
+        let ids = self.logic_data.list.iter().map(ProgramModule::id).collect::<HashSet<_>>();
+        self.stack_heights.retain(|id, _| ids.contains(id));
+        self.stack_tops.retain(|id, _| ids.contains(id));
+        for i in changed {
+            self.estimate_stack_height(i);
+        }
+    }
+
+    // replaced by the measured height once the module has been rendered
+    fn estimate_stack_height(&mut self, i: usize) {
+        if let Some(layout) = self.layout.list.modules.get(i) {
+            self.stack_heights.insert(layout.id, layout.rect.h);
+        }
+    }
+
+    // None for the top level modules that are rendered, the placeholder height for the others
+    fn virtual_heights(&self) -> Vec<Option<f64>> {
+        let area = match &self.visible_area {
+            Some(area) => area,
+            None => return vec![None; self.logic_data.list.len()],
+        };
+        let heights = self.logic_data.list.iter()
+            .map(|module| self.stack_heights.get(&module.id()).cloned().unwrap_or(0f64))
+            .collect::<Vec<_>>();
+        let focused = self.cursor.position().map(|position| match position {
+            CursorPosition::Module(id) | CursorPosition::Input(id, _) | CursorPosition::Slot(id, _) => id,
+        });
+        let (top, gap) = self.stack_spacing();
+        visible_items(&heights, top, gap, area, VIRTUALIZE_MARGIN).into_iter().zip(&self.logic_data.list).zip(heights)
+            .map(|((visible, module), height)| {
+                if visible || focused.map(|id| module.get_module(id).is_ok()).unwrap_or(false) { None } else { Some(height) }
+            })
+            .collect()
+    }
+
+    // the top of the first stack and the gap between stacks, measured where two neighbours have been rendered
+    fn stack_spacing(&self) -> (f64, f64) {
+        let list = &self.logic_data.list;
+        let top = list.first().and_then(|module| self.stack_tops.get(&module.id())).cloned().unwrap_or(STACK_GAP);
+        let gap = list.windows(2)
+            .filter_map(|pair| {
+                let (first, second) = (pair[0].id(), pair[1].id());
+                match (self.stack_tops.get(&first), self.stack_heights.get(&first), self.stack_tops.get(&second)) {
+                    (Some(top), Some(height), Some(next)) => Some(next - top - height),
+                    _ => None,
+                }
+            })
+            .next()
+            .unwrap_or(STACK_GAP);
+        (top, gap)
+    }
+
+    fn viewport_page_rect(&self) -> Option<Rect> {
+        self.viewport_ref.cast::<Element>().map(|element| {
+            let rect = element.get_bounding_client_rect();
+            let offset = get_page_offset();
+            Rect { x: rect.x() + offset.0, y: rect.y() + offset.1, w: rect.width(), h: rect.height() }
+        })
+    }
+
+    // true when the view has to be rendered again, the minimap follows every scroll
+    fn update_visible_area(&mut self) -> bool {
+        let view_area = match self.viewport_page_rect() {
+            Some(rect) => self.view_transform.rect_to_content(&rect),
+            None => return false,
+        };
+        let area = snap_area(&view_area, VIRTUALIZE_STEP);
+        let mut changed = self.props.minimap && self.view_area.as_ref() != Some(&view_area);
+        self.view_area = Some(view_area);
+        if self.visible_area.as_ref() != Some(&area) {
+            self.visible_area = Some(area);
+            changed = true;
+        }
+        changed
+    }
+
+    fn scroll_viewport(&self, dx: f64, dy: f64) {
+        if let Some(element) = self.viewport_ref.cast::<Element>() {
+            element.scroll_by_with_x_and_y(dx, dy);
+        }
+    }
+
+    fn update_view_transform(&mut self) {
+        if let Some(element) = self.content_ref.cast::<Element>() {
+            let rect = element.get_bounding_client_rect();
+            let offset = get_page_offset();
+            let transform = ViewTransform::new(self.view_transform.scale, rect.x() + offset.0, rect.y() + offset.1);
+            self.view_transform = transform;
+            if self.sent_view_transform != Some(transform) {
+                self.sent_view_transform = Some(transform);
+                self.drag_module_agent_bridge.send(DragModuleAgentInputMessage::SetViewTransform(transform));
+            }
+        }
+    }
+
+    fn view_context_menu(&self) -> Html {
+        let menu = match &self.context_menu {
+            Some(menu) => menu,
+            None => return html! {},
+        };
+        let module = match self.logic_data.get_module(menu.id) {
+            Ok(module) => module,
+            Err(_) => return html! {},
+        };
+        let messages = &self.props.messages;
+        let button = |label: String, action: fn() -> ContextMenuAction| html! {
+            <button class="dotevery_editor_context_menu_item" onclick=self.link.callback(move |_| DotEveryEditorMessage::ContextMenuAction(action()))>{label}</button>
+        };
+        let mut items = vec![button(messages.get("editor.duplicate"), || ContextMenuAction::Duplicate)];
+        if !module.lock.no_delete {
+            items.push(button(messages.get("editor.delete"), || ContextMenuAction::Delete));
+        }
+        if module.child != ProgramModuleChildItems::None {
+            let label = if module.collapsed { "editor.expand" } else { "editor.collapse" };
+            items.push(button(messages.get(label), || ContextMenuAction::ToggleCollapsed));
+        }
+        if !module.lock.no_edit {
+            let label = if module.disabled { "editor.enable" } else { "editor.disable" };
+            items.push(button(messages.get(label), || ContextMenuAction::ToggleDisabled));
+            items.push(button(messages.get("editor.comment"), || ContextMenuAction::EditComment));
+        }
+        if menu.help.is_some() {
+            items.push(button(messages.get("editor.help"), || ContextMenuAction::ShowHelp));
+        }
+        let custom = menu.items.iter().map(|item| {
+            let action = item.action.clone();
+            html! {
+                <button class="dotevery_editor_context_menu_item" onclick=self.link.callback(move |_| DotEveryEditorMessage::ContextMenuAction(ContextMenuAction::Custom(action.clone())))>{messages.get(&item.label)}</button>
+            }
+        });
+        let help = match (&menu.help, menu.show_help) {
+            (Some(help), true) => html! {<div class="dotevery_editor_context_menu_help">{messages.get(help)}</div>},
+            _ => html! {},
+        };
+        let style = format!("left:{}px;top:{}px;", menu.x, menu.y);
+        let close = self.link.callback(|e: MouseEvent| {
+            e.prevent_default();
+            DotEveryEditorMessage::CloseContextMenu
+        });
+        html! {
+            <>
+                <div class="dotevery_editor_context_menu_backdrop" onpointerdown=self.link.callback(|_| DotEveryEditorMessage::CloseContextMenu) oncontextmenu=close/>
+                <div class="dotevery_editor_context_menu" style=style>
+                    {for items}
+                    {for custom}
+                    {help}
+                </div>
+            </>
+        }
+    }
+
+    fn view_palette(&self) -> Html {
+        let module_to_component = |module: &ProgramModule<T>| {
+            let remaining = self.palette_data.remaining(&self.logic_data, &module.type_data);
+            let (class, badge) = match remaining {
+                Some(0) => ("program_module_palette_item program_module_palette_item_exhausted", html! {
+                    <span class="program_module_palette_remaining">{"×0"}</span>
+                }),
+                Some(remaining) => ("program_module_palette_item", html! {
+                    <span class="program_module_palette_remaining">{format!("×{}", remaining)}</span>
+                }),
+                None => ("program_module_palette_item", html! {}),
+            };
+            let module = ProgramModuleProperties {
+                program_module: Either::Left(Arc::new(module.clone())),
+                rect_changed_callback: self.link.callback(|_| { DotEveryEditorMessage::Ignore }),
+                cursor: EditorCursor::default(),
+                drag_threshold: self.props.drag_threshold,
+                messages: Rc::clone(&self.props.messages),
+                read_only: false,
+            };
+            html! {
+                <div class=class>
+                    <ProgramModuleComponent<Controller, T, ProgramModuleComponentImplTypeListOnly> with module/>
+                    {badge}
+                </div>
+            }
+        };
+        let budget = match self.palette_data.remaining_budget(&self.logic_data) {
+            Some(remaining) => html! {
+                <div class="program_module_palette_budget">{self.props.messages.format("editor.budget", &[("count", &remaining.to_string())])}</div>
+            },
+            None => html! {},
+        };
+        let filter = html! {
+            <>
+                <input class="program_module_palette_filter" type="search" placeholder=self.props.messages.get("editor.search") value=self.palette_filter.clone()
+                    oninput=self.link.callback(|e: InputData| DotEveryEditorMessage::UpdatePaletteFilter(e.value))/>
+                {budget}
+            </>
+        };
+        if !self.palette_filter.trim().is_empty() {
+            let sections = self.palette_data.filter(&self.palette_filter).categories.into_iter().map(|category| {
+                let style = format!("border-color:{};", category.color);
+                html! {
+                    <div class="program_module_palette_section" style=style>
+                        <div class="program_module_palette_section_header">{self.props.messages.get(&category.name)}</div>
+                        {for category.items.iter().map(&module_to_component)}
+                    </div>
+                }
+            });
+            return html! {
+                <>
+                    {filter}
+                    <div class="program_module_palette_items">
+                        {for sections}
+                    </div>
+                </>
+            };
+        }
+        let selected = usize::min(self.palette_category, self.palette_data.categories.len().saturating_sub(1));
+        let tabs = if self.palette_data.categories.len() > 1 {
+            let tabs = self.palette_data.categories.iter().enumerate().map(|(i, category)| {
+                let class = if i == selected {
+                    "program_module_palette_tab program_module_palette_tab_selected"
+                } else {
+                    "program_module_palette_tab"
+                };
+                let style = format!("border-color:{};", category.color);
+                html! {
+                    <button class=class style=style onclick=self.link.callback(move |_| DotEveryEditorMessage::SelectPaletteCategory(i))>{self.props.messages.get(&category.name)}</button>
+                }
+            });
+            html! {
+                <div class="program_module_palette_tabs">
+                    {for tabs}
+                </div>
+            }
+        } else {
+            html! {}
+        };
+        let items = if let Some(category) = self.palette_data.categories.get(selected) {
+            let style = format!("border-color:{};", category.color);
+            html! {
+                <div class="program_module_palette_items" style=style>
+                    {for category.items.iter().map(&module_to_component)}
+                </div>
+            }
+        } else {
+            html! {}
+        };
+        html! {
+            <>
+                {filter}
+                {tabs}
+                {items}
+            </>
+        }
+    }
+}
+
+// the root list is registered as nil in the drag agent
+fn top_level_module<T: 'static + Clone + PartialEq>(module: &ProgramModule<T>) -> ProgramModule<T> {
+    let mut module = module.clone();
+    module.set_parent(Some(Uuid::nil()));
+    module
+}
@@ -8,9 +8,11 @@ use yew::agent::{Agent, AgentLink, Context, HandlerId};
 use crate::components::dotevery_editor_agent::DotEveryEditorAgentInputMessage::*;
 use crate::components::dotevery_editor_agent::DotEveryEditorAgentOutputMessage::*;
 use crate::components::dotevery_editor_agent_bridge::DotEveryEditorAgentBridge;
+use crate::logic::cursor::{CursorOperation, CursorPosition};
 use crate::logic::dotevery_editor::{DotEveryEditor, DotEveryEditorErrorMessage, DotEveryEditorOperationIndex};
-use crate::logic::dotevery_editor_controller::{DotEveryEditorCommand, DotEveryEditorController};
-use crate::logic::program_module::{ProgramModule, ProgramModuleOption};
+use crate::logic::dotevery_editor_controller::{ContextMenuItem, DotEveryEditorCommand, DotEveryEditorController, DotEveryEditorOperation};
+use crate::logic::palette::Palette;
+use crate::logic::program_module::ProgramModule;
 
 // use crate::logic::program_module_list::ProgramModuleList;
 
@@ -19,7 +21,7 @@ pub struct DotEveryEditorAgent<Controller, Type>
           Type: 'static + Clone + PartialEq {
     link: AgentLink<Self>,
     logic: Arc<RwLock<DotEveryEditor<Type>>>,
-    palette: Arc<RwLock<Vec<ProgramModule<Type>>>>,
+    palette: Arc<RwLock<Palette<Type>>>,
     manager: Option<HandlerId>,
     controller_proxy: Option<HandlerId>,
     controller: Controller,
@@ -43,15 +45,61 @@ pub enum DotEveryEditorAgentInputMessage<Type: 'static + Clone + PartialEq, IN>
     Add(Uuid, DotEveryEditorOperationIndex, ProgramModule<Type>),
     //src,dest,index
     Copy(Uuid, Uuid, DotEveryEditorOperationIndex),
+    //src,dest,index,modules
+    Move(Vec<Uuid>, Uuid, DotEveryEditorOperationIndex, Vec<ProgramModule<Type>>),
     Remove(Uuid),
     UpdateInput { id: Uuid, index: usize, value: String },
+    SetCollapsed { id: Uuid, collapsed: bool },
+    SetDisabled { id: Uuid, disabled: bool },
+    SetComment { id: Uuid, comment: Option<String> },
+    // copies the module right after itself
+    Duplicate(Uuid),
+    // the menu is sent to the manager, x and y are client coordinates
+    OpenContextMenu { id: Uuid, x: i32, y: i32 },
+    ContextAction { id: Uuid, action: String },
+    CanAdd(Uuid, DotEveryEditorOperationIndex, Vec<ProgramModule<Type>>),
+    GetFollowingModules(Uuid),
+    // modules that are not in the document, like palette items, can always be moved
+    CanMove(Vec<Uuid>),
+    Select(CursorPosition),
+}
+
+impl<Type: 'static + Clone + PartialEq, IN> From<CursorOperation<Type>> for DotEveryEditorAgentInputMessage<Type, IN> {
+    fn from(operation: CursorOperation<Type>) -> Self {
+        match operation {
+            CursorOperation::Move(src, dest, index, module) => Move(vec![src], dest, index, vec![module]),
+            CursorOperation::Remove(id) => Remove(id),
+        }
+    }
+}
+
+impl<Type: 'static + Clone + PartialEq, IN> From<DotEveryEditorOperation<Type>> for DotEveryEditorAgentInputMessage<Type, IN> {
+    fn from(operation: DotEveryEditorOperation<Type>) -> Self {
+        match operation {
+            DotEveryEditorOperation::Add(dest, index, module) => Add(dest, index, module),
+            DotEveryEditorOperation::Copy(src, dest, index) => Copy(src, dest, index),
+            DotEveryEditorOperation::Move(src, dest, index, modules) => Move(src, dest, index, modules),
+            DotEveryEditorOperation::Remove(id) => Remove(id),
+            DotEveryEditorOperation::UpdateInput { id, index, value } => UpdateInput { id, index, value },
+            DotEveryEditorOperation::SetCollapsed { id, collapsed } => SetCollapsed { id, collapsed },
+            DotEveryEditorOperation::SetDisabled { id, disabled } => SetDisabled { id, disabled },
+            DotEveryEditorOperation::SetComment { id, comment } => SetComment { id, comment },
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
 pub enum DotEveryEditorAgentOutputMessage<Type: 'static + Clone + PartialEq, OUT> {
     ModuleUpdated(DotEveryEditor<Type>),
-    PaletteUpdated(Vec<ProgramModule<Type>>),
+    // subtrees replaced in place, the rest of the tree is unchanged
+    ModulesUpdated(Vec<ProgramModule<Type>>),
+    PaletteUpdated(Palette<Type>),
     ResponseFromController(HandlerId, OUT),
+    CanAddResult(Uuid, DotEveryEditorOperationIndex, bool),
+    FollowingModules(Uuid, Vec<ProgramModule<Type>>),
+    CanMoveResult(Vec<Uuid>, bool),
+    Selected(CursorPosition),
+    ContextMenu { id: Uuid, x: i32, y: i32, items: Vec<ContextMenuItem>, help: Option<String> },
 }
 
 impl<Controller, T> Agent for DotEveryEditorAgent<Controller, T>
@@ -64,7 +112,7 @@ impl<Controller, T> Agent for DotEveryEditorAgent<Controller, T>
 
     fn create(link: AgentLink<Self>) -> Self {
         let data = Arc::new(RwLock::new(DotEveryEditor::new(Vec::new())));
-        let palette = Arc::new(RwLock::new(Vec::new()));
+        let palette = Arc::new(RwLock::new(Palette::default()));
         let bridge = DotEveryEditorAgentBridge::<Controller, T>::create(
             link.callback(|msg| Self::Message::MsgFromController(msg)),
             link.callback(|(id, msg)| Self::Message::ResponseFromController(id, msg)),
@@ -126,48 +174,145 @@ impl<Controller, T> Agent for DotEveryEditorAgent<Controller, T>
             //     }
             // }
             Add(id, index, module) => {
+                if !self.controller.can_add(id, index, &module) { return; }
+                if let Err(err) = self.check_limits(std::slice::from_ref(&module)) {
+                    self.handle_error(err);
+                    return;
+                }
                 let result = self.logic.write().unwrap().add(id, index, &module);
                 // clog!("add operation");
                 if let Err(err) = result {
                     self.handle_error(err);
                 } else {
                     // clog!("add operation succeed");
-                    self.link.send_message(Self::Message::ModuleUpdated);
-                    if let Some(manager) = self.manager {
-                        self.link.respond(manager, ModuleUpdated(self.logic.read().unwrap().clone()));
-                    } else {
-                        clog!("manager is not found");
-                    }
+                    self.notify_changed(&[id]);
                 }
             }
             Copy(src, dest, index) => {
+                let copied = self.logic.read().unwrap().get_module(src).cloned();
+                let module = match copied {
+                    Ok(module) => module,
+                    Err(err) => return self.handle_error(err),
+                };
+                if !self.controller.can_add(dest, index, &module) { return; }
+                if let Err(err) = self.check_limits(&[module]) {
+                    self.handle_error(err);
+                    return;
+                }
                 let result = self.logic.write().unwrap().copy(src, dest, index);
                 if let Err(err) = result {
                     self.handle_error(err);
                 } else {
-                    self.link.send_message(Self::Message::ModuleUpdated);
-                    if let Some(manager) = self.manager {
-                        self.link.respond(manager, ModuleUpdated(self.logic.read().unwrap().clone()));
-                    }
+                    self.notify_changed(&[dest]);
+                }
+            }
+            Move(src, dest, index, modules) => {
+                if !self.controller_can_add(dest, index, &modules) { return; }
+                if let Err(err) = self.check_limits(&self.added_modules(&src, &modules)) {
+                    self.handle_error(err);
+                    return;
+                }
+                let mut changed = self.parents(&src);
+                changed.push(dest);
+                let result = self.logic.write().unwrap().move_modules(&src, dest, index, &modules);
+                if let Err(err) = result {
+                    self.handle_error(err);
+                } else {
+                    self.notify_changed(&changed);
                 }
             }
             Remove(id) => {
+                let changed = self.parents(&[id]);
                 let result = self.logic.write().unwrap().remove(id);
                 if let Err(err) = result {
                     self.handle_error(err);
                 } else {
-                    self.link.send_message(Self::Message::ModuleUpdated);
-                    if let Some(manager) = self.manager {
-                        self.link.respond(manager, ModuleUpdated(self.logic.read().unwrap().clone()));
-                    }
+                    self.notify_changed(&changed);
                 }
             }
             UpdateInput { id, index, value } => {
-                let mut logic = self.logic.write().unwrap();
-                if let Ok(module) = logic.get_module_mut(id) {
-                    if let Some(ProgramModuleOption::StringInput(s)) = module.options.get_mut(index) {
-                        *s = value;
+                let result = self.logic.write().unwrap().update_input(id, index, value);
+                if let Err(err) = result {
+                    self.handle_error(err);
+                } else {
+                    self.notify_changed(&[id]);
+                }
+            }
+            SetCollapsed { id, collapsed } => {
+                let updated = match self.logic.write().unwrap().get_module_mut(id) {
+                    Ok(module) if module.collapsed != collapsed => {
+                        module.collapsed = collapsed;
+                        true
                     }
+                    _ => false,
+                };
+                if updated {
+                    self.notify_changed(&[id]);
+                }
+            }
+            SetDisabled { id, disabled } => {
+                let result = self.logic.write().unwrap().set_disabled(id, disabled);
+                if let Err(err) = result {
+                    self.handle_error(err);
+                } else {
+                    self.notify_changed(&[id]);
+                }
+            }
+            SetComment { id, comment } => {
+                let result = self.logic.write().unwrap().set_comment(id, comment);
+                if let Err(err) = result {
+                    self.handle_error(err);
+                } else {
+                    self.notify_changed(&[id]);
+                }
+            }
+            Duplicate(src) => {
+                let position = self.logic.read().unwrap().duplicate_position(src);
+                match position {
+                    Ok((dest, index)) => self.handle_input(Copy(src, dest, index), id),
+                    Err(err) => self.handle_error(err),
+                }
+            }
+            OpenContextMenu { id: module_id, x, y } => {
+                let menu = match self.logic.read().unwrap().get_module(module_id) {
+                    Ok(module) => ContextMenu { id: module_id, x, y, items: self.controller.context_menu(module), help: self.controller.help(module) },
+                    Err(_) => return,
+                };
+                if let Some(manager) = self.manager {
+                    self.link.respond(manager, menu);
+                }
+            }
+            ContextAction { id: module_id, action } => {
+                for operation in self.controller.context_action(module_id, &action) {
+                    self.handle_input(operation.into(), id);
+                }
+            }
+            CanAdd(target, index, modules) => {
+                let ids = modules.iter().map(ProgramModule::id).collect::<Vec<_>>();
+                let valid = self.logic.read().unwrap().can_add_modules(target, index, &modules).is_ok()
+                    && self.controller_can_add(target, index, &modules)
+                    && self.check_limits(&self.added_modules(&ids, &modules)).is_ok();
+                self.link.respond(id, CanAddResult(target, index, valid));
+            }
+            GetFollowingModules(module_id) => {
+                let modules = self.logic.read().unwrap().get_following_modules(module_id).unwrap_or_default();
+                self.link.respond(id, FollowingModules(module_id, modules));
+            }
+            CanMove(ids) => {
+                let movable = {
+                    let logic = self.logic.read().unwrap();
+                    ids.iter().all(|id| matches!(logic.can_move(*id), Ok(()) | Err(DotEveryEditorErrorMessage::NotFound)))
+                };
+                self.link.respond(id, CanMoveResult(ids, movable));
+            }
+            Select(position) => {
+                let module_id = match position {
+                    CursorPosition::Module(id) | CursorPosition::Input(id, _) | CursorPosition::Slot(id, _) => id,
+                };
+                if self.logic.read().unwrap().get_module(module_id).is_err() { return; }
+                self.controller.select(position);
+                if let Some(manager) = self.manager {
+                    self.link.respond(manager, Selected(position));
                 }
             }
         }
@@ -177,6 +322,42 @@ impl<Controller, T> Agent for DotEveryEditorAgent<Controller, T>
 impl<Controller, T> DotEveryEditorAgent<Controller, T>
     where Controller: 'static + DotEveryEditorController<T>,
           T: 'static + Clone + PartialEq {
+    fn controller_can_add(&self, target: Uuid, index: DotEveryEditorOperationIndex, modules: &[ProgramModule<T>]) -> bool {
+        modules.iter().all(|module| self.controller.can_add(target, index, module))
+    }
+
+    // palette limits and the block budget apply to every operation that brings new modules into the program
+    fn check_limits(&self, added: &[ProgramModule<T>]) -> Result<(), DotEveryEditorErrorMessage> {
+        self.palette.read().unwrap().can_place(&self.logic.read().unwrap(), added)
+    }
+
+    // modules whose source is not in the program, like palette items
+    fn added_modules(&self, src: &[Uuid], modules: &[ProgramModule<T>]) -> Vec<ProgramModule<T>> {
+        let logic = self.logic.read().unwrap();
+        src.iter().zip(modules).filter(|(id, _)| logic.get_module(**id).is_err()).map(|(_, module)| module.clone()).collect()
+    }
+
+    fn parents(&self, ids: &[Uuid]) -> Vec<Uuid> {
+        let logic = self.logic.read().unwrap();
+        ids.iter().filter_map(|id| logic.get_module(*id).ok().map(|module| module.parent().unwrap_or_else(|| logic.id()))).collect()
+    }
+
+    // sends only the subtrees containing `changed`, the whole tree when the root list changed
+    fn notify_changed(&mut self, changed: &[Uuid]) {
+        self.link.send_message(DotEveryEditorAgentMessage::ModuleUpdated);
+        let manager = match self.manager {
+            Some(manager) => manager,
+            None => return,
+        };
+        let logic = self.logic.read().unwrap();
+        let subtrees = logic.changed_subtrees(changed)
+            .and_then(|ids| ids.into_iter().map(|id| logic.get_module(id).ok().cloned()).collect::<Option<Vec<_>>>());
+        match subtrees {
+            Some(subtrees) => self.link.respond(manager, ModulesUpdated(subtrees)),
+            None => self.link.respond(manager, ModuleUpdated(logic.clone())),
+        }
+    }
+
     fn handle_error(&mut self, error: DotEveryEditorErrorMessage) {
         // clog!(format!("{:?}", error));
         // clog!(format!("{:?}", self.logic));
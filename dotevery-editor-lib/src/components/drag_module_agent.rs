@@ -1,5 +1,4 @@
-use std::collections::{HashMap, HashSet};
-use std::collections::VecDeque;
+use std::collections::HashMap;
 
 use bimap::{BiHashMap, Overwritten};
 use serde::{Deserialize, Serialize};
@@ -7,12 +6,33 @@ use uuid::Uuid;
 use yew::{Bridge, Bridged};
 use yew::agent::{Agent, AgentLink, Context, HandlerId};
 
-use crate::components::dotevery_editor_agent::{DotEveryEditorAgent, DotEveryEditorAgentInputMessage};
+use crate::components::dotevery_editor_agent::{DotEveryEditorAgent, DotEveryEditorAgentInputMessage, DotEveryEditorAgentOutputMessage};
 use crate::logic::dotevery_editor::DotEveryEditorOperationIndex;
 use crate::logic::dotevery_editor_controller::DotEveryEditorController;
+use crate::logic::layout::EditorLayout;
 use crate::logic::program_module::ProgramModule;
+use crate::logic::spatial_index::SpatialIndex;
+use crate::logic::viewport::ViewTransform;
 use crate::util::Rect;
 
+const DEFAULT_SNAP_DISTANCE: f64 = 16.0;
+
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum DragMode {
+    Move,
+    MoveFollowing,
+    Duplicate,
+}
+
+// waits for the following modules and for the lock check before the drag starts
+struct PendingDrag<T: 'static + Clone + PartialEq> {
+    offset_x: i32,
+    offset_y: i32,
+    module: ProgramModule<T>,
+    following: Vec<ProgramModule<T>>,
+    mode: DragMode,
+}
+
 #[derive(Copy, Clone, PartialEq)]
 enum HoveringModuleType {
     Module(Uuid),
@@ -30,23 +50,35 @@ pub(crate) struct DragModuleAgent<Controller, Type>
     dragging_component: Option<HandlerId>,
     logic_agent_bridge: Box<dyn Bridge<DotEveryEditorAgent<Controller, Type>>>,
     dragging_module: Option<ProgramModule<Type>>,
+    dragging_following: Vec<ProgramModule<Type>>,
+    dragging_mode: DragMode,
+    pending_drag: Option<PendingDrag<Type>>,
     dragging_module_offset_x: i32,
     dragging_module_offset_y: i32,
     hovering_module: HoveringModuleType,
     hovering_index: Option<DotEveryEditorOperationIndex>,
-    rectangles: HashMap<Uuid, Rect>,
+    spatial_index: SpatialIndex,
+    snap_distance: f64,
+    view_transform: ViewTransform,
+    mouse_x: i32,
+    mouse_y: i32,
     uuid_map: BiHashMap<Uuid, HandlerId>,
     parent_map: HashMap<Uuid, Uuid>,
-    children_map: HashMap<Uuid, HashSet<Uuid>>,
+    layout: Option<(EditorLayout, f64, f64)>,
+    layout_owner: Option<HandlerId>,
+    layout_hovering: Option<(Uuid, DotEveryEditorOperationIndex)>,
 }
 
-pub(crate) enum DragModuleMessage {
-    Ignore
+pub(crate) enum DragModuleMessage<T: 'static + Clone + PartialEq> {
+    Ignore,
+    CanAddResult(Uuid, DotEveryEditorOperationIndex, bool),
+    FollowingModules(Uuid, Vec<ProgramModule<T>>),
+    CanMoveResult(Vec<Uuid>, bool),
 }
 
 #[derive(Serialize, Deserialize)]
 pub enum DragModuleAgentInputMessage<T: 'static + Clone + PartialEq> {
-    TryStartDrag { offset_x: i32, offset_y: i32, module: ProgramModule<T> },
+    TryStartDrag { offset_x: i32, offset_y: i32, module: ProgramModule<T>, mode: DragMode },
     EndDrag,
     SetRootId(Uuid),
     SetDraggingComponentId,
@@ -56,16 +88,22 @@ pub enum DragModuleAgentInputMessage<T: 'static + Clone + PartialEq> {
     UpdateRect { x: f64, y: f64, w: f64, h: f64 },
     SetTrashArea { x: f64, y: f64, w: f64, h: f64 },
     UpdateHoveringIndex(Option<DotEveryEditorOperationIndex>),
+    SetSnapDistance(f64),
+    SetLayout { layout: EditorLayout, x: f64, y: f64 },
+    SetViewTransform(ViewTransform),
     // Clear,
 }
 
 #[derive(Serialize, Deserialize)]
 pub enum DragModuleAgentOutputMessage<T: 'static + Clone + PartialEq> {
-    CreateDragComponent { offset_x: i32, offset_y: i32, module: ProgramModule<T> },
+    CreateDragComponent { offset_x: i32, offset_y: i32, module: ProgramModule<T>, following: Vec<ProgramModule<T>>, mode: DragMode },
     EndDrag,
     UpdateDraggingModulePosition { x: i32, y: i32 },
     LeaveHoveringModule,
     MoveHoveringModule { x: i32, y: i32, module_w: f64, module_h: f64 },
+    UpdateDropValidity { index: DotEveryEditorOperationIndex, valid: bool },
+    HoverTrashArea(bool),
+    UpdateLayoutHovering(Option<(Uuid, DotEveryEditorOperationIndex)>),
     RequestRegisterUuid,
     RequestUpdateRect,
 }
@@ -74,12 +112,17 @@ impl<Controller, T> Agent for DragModuleAgent<Controller, T>
     where Controller: 'static + DotEveryEditorController<T>,
           T: 'static + Clone + PartialEq {
     type Reach = Context<Self>;
-    type Message = DragModuleMessage;
+    type Message = DragModuleMessage<T>;
     type Input = DragModuleAgentInputMessage<T>;
     type Output = DragModuleAgentOutputMessage<T>;
 
     fn create(link: AgentLink<Self>) -> Self {
-        let callback = link.callback(|_| Self::Message::Ignore);
+        let callback = link.callback(|msg| match msg {
+            DotEveryEditorAgentOutputMessage::CanAddResult(target, index, valid) => Self::Message::CanAddResult(target, index, valid),
+            DotEveryEditorAgentOutputMessage::FollowingModules(id, modules) => Self::Message::FollowingModules(id, modules),
+            DotEveryEditorAgentOutputMessage::CanMoveResult(ids, movable) => Self::Message::CanMoveResult(ids, movable),
+            _ => Self::Message::Ignore,
+        });
         Self {
             link,
             trash_area: Rect {
@@ -93,63 +136,112 @@ impl<Controller, T> Agent for DragModuleAgent<Controller, T>
             dragging_component: None,
             logic_agent_bridge: DotEveryEditorAgent::bridge(callback),
             dragging_module: None,
+            dragging_following: Vec::new(),
+            dragging_mode: DragMode::Move,
+            pending_drag: None,
             dragging_module_offset_x: 0,
             dragging_module_offset_y: 0,
             hovering_module: HoveringModuleType::None,
             hovering_index: None,
-            rectangles: HashMap::new(),
+            spatial_index: SpatialIndex::default(),
+            snap_distance: DEFAULT_SNAP_DISTANCE,
+            view_transform: ViewTransform::default(),
+            mouse_x: 0,
+            mouse_y: 0,
             uuid_map: BiHashMap::new(),
             parent_map: HashMap::new(),
-            children_map: HashMap::new(),
+            layout: None,
+            layout_owner: None,
+            layout_hovering: None,
         }
     }
 
     fn update(&mut self, msg: Self::Message) {
         match msg {
             Self::Message::Ignore => {}
+            Self::Message::CanAddResult(target, index, valid) => {
+                if self.hovering_module == HoveringModuleType::Module(target) && self.hovering_index == Some(index) {
+                    if let Some(id) = self.uuid_map.get_by_left(&target) {
+                        self.link.respond(*id, Self::Output::UpdateDropValidity { index, valid });
+                    } else if let (Some(owner), true) = (self.layout_owner, self.is_layout_module(target)) {
+                        self.link.respond(owner, Self::Output::UpdateDropValidity { index, valid });
+                    }
+                }
+            }
+            Self::Message::FollowingModules(id, mut modules) => {
+                if let Some(pending) = &mut self.pending_drag {
+                    if pending.module.id() == id {
+                        pending.following = if modules.first().map(ProgramModule::id) == Some(id) { modules.split_off(1) } else { Vec::new() };
+                        let ids = std::iter::once(&pending.module).chain(&pending.following).map(ProgramModule::id).collect();
+                        self.logic_agent_bridge.send(DotEveryEditorAgentInputMessage::CanMove(ids));
+                    } else {
+                        self.pending_drag = None;
+                    }
+                }
+            }
+            Self::Message::CanMoveResult(ids, movable) => {
+                if let Some(pending) = self.pending_drag.take() {
+                    if ids.first() == Some(&pending.module.id()) && movable {
+                        self.start_drag(pending.offset_x, pending.offset_y, pending.module, pending.following, pending.mode);
+                    }
+                }
+            }
         }
     }
 
     fn handle_input(&mut self, msg: Self::Input, id: HandlerId) {
-        // clog!(format!("{:#?}",self.rectangles));
         match msg {
-            Self::Input::TryStartDrag { offset_x, offset_y, module } => {
+            Self::Input::TryStartDrag { offset_x, offset_y, module, mode } => {
                 // clog!("TryStartDrag", module.id.to_string());
-                if self.dragging_module == None {
-                    // clog!("StartDrag", module.id.to_string());
-                    if let Some(id) = self.manager_id {
-                        self.link.respond(id, Self::Output::CreateDragComponent { offset_x, offset_y, module: module.deep_clone() });
-                        self.dragging_module = Some(module);
-                    }
-                    for id in self.uuid_map.right_values() {
-                        self.link.respond(*id, Self::Output::RequestUpdateRect);
+                if self.dragging_module == None && self.pending_drag.is_none() {
+                    match mode {
+                        DragMode::Duplicate => self.start_drag(offset_x, offset_y, module, Vec::new(), mode),
+                        DragMode::Move => {
+                            self.logic_agent_bridge.send(DotEveryEditorAgentInputMessage::CanMove(vec![module.id()]));
+                            self.pending_drag = Some(PendingDrag { offset_x, offset_y, module, following: Vec::new(), mode });
+                        }
+                        DragMode::MoveFollowing => {
+                            self.logic_agent_bridge.send(DotEveryEditorAgentInputMessage::GetFollowingModules(module.id()));
+                            self.pending_drag = Some(PendingDrag { offset_x, offset_y, module, following: Vec::new(), mode });
+                        }
                     }
                 }
             }
             Self::Input::EndDrag => {
                 // clog!("EndDrag in DragModuleAgent");
-                if let Some(module) = &self.dragging_module {
+                if self.dragging_module.is_some() {
                     match self.hovering_module {
                         HoveringModuleType::Module(hovering_id) => {
                             // clog!("hovering", hovering_id.to_string());
-                            if let Some(index) = self.hovering_index {
-                                self.logic_agent_bridge.send(DotEveryEditorAgentInputMessage::Add(hovering_id, index, module.deep_clone()));
-                                self.logic_agent_bridge.send(DotEveryEditorAgentInputMessage::Remove(module.id));
+                            if let (Some(index), DragMode::Duplicate, Some(module)) = (self.hovering_index, self.dragging_mode, &self.dragging_module) {
+                                self.logic_agent_bridge.send(DotEveryEditorAgentInputMessage::Add(hovering_id, index, module.deep_clone().unlocked()));
+                            } else if let Some(index) = self.hovering_index {
+                                let modules = self.dragging_modules().iter().map(ProgramModule::deep_clone).collect();
+                                self.logic_agent_bridge.send(DotEveryEditorAgentInputMessage::Move(self.dragging_ids(), hovering_id, index, modules));
                                 // clog!("hovering index", format!("Some({:?})", index));
                             } else {
                                 // clog!("hovering index", "None");
                             }
                             if let Some(id) = self.uuid_map.get_by_left(&hovering_id) {
                                 self.link.respond(*id, Self::Output::LeaveHoveringModule);
-                                self.hovering_module = HoveringModuleType::None;
-                                self.hovering_index = None;
                             } else {
                                 // clog!("Uuid is not found in EndDrag");
                             }
+                            self.hovering_module = HoveringModuleType::None;
+                            self.hovering_index = None;
+                            self.update_layout_hovering();
                         }
                         HoveringModuleType::TrashArea => {
-                            // clog!(format!("remove {}",module.id));
-                            self.logic_agent_bridge.send(DotEveryEditorAgentInputMessage::Remove(module.id));
+                            // clog!(format!("remove {:?}", self.dragging_ids()));
+                            if self.dragging_mode != DragMode::Duplicate {
+                                for id in self.dragging_ids() {
+                                    self.logic_agent_bridge.send(DotEveryEditorAgentInputMessage::Remove(id));
+                                }
+                            }
+                            self.hovering_module = HoveringModuleType::None;
+                            if let Some(manager) = self.manager_id {
+                                self.link.respond(manager, DragModuleAgentOutputMessage::HoverTrashArea(false));
+                            }
                         }
                         _ => {}
                     }
@@ -159,63 +251,26 @@ impl<Controller, T> Agent for DragModuleAgent<Controller, T>
                         // clog!("manager id is not found in EndDrag");
                     }
                     self.dragging_module = None;
+                    self.dragging_following.clear();
                 }
+                self.pending_drag = None;
             }
             Self::Input::UpdateMousePosition { x, y } => {
                 // clog!("mousemove", x, y);
-                if let Some(module) = &self.dragging_module {
-                    if let Some(id) = self.dragging_component {
-                        if let Some(dragging_module_rect) = self.rectangles.get(&module.id) {
-                            self.link.respond(id, Self::Output::UpdateDraggingModulePosition {
-                                x,
-                                y,
-                            });
-                            let hovering = if self.trash_area.encloses(x as f64, y as f64) {
-                                HoveringModuleType::TrashArea
-                            } else {
-                                self.get_hovering_module_uuid(x, y, module.id)
-                            };
-                            if self.hovering_module != hovering {
-                                if let HoveringModuleType::Module(now_hovering_module) = self.hovering_module {
-                                    if let Some(now_hovering_module) = self.uuid_map.get_by_left(&now_hovering_module) {
-                                        self.link.respond(*now_hovering_module, Self::Output::LeaveHoveringModule);
-                                    } else {
-                                        // clog!("now hovering module Uuid is not found in UpdateMousePosition");
-                                    }
-                                }
-                                self.hovering_module = hovering;
-                            }
-                            if let HoveringModuleType::Module(id) = &hovering {
-                                if let Some(now_hovering_module) = self.uuid_map.get_by_left(&id) {
-                                    self.link.respond(*now_hovering_module,
-                                                      Self::Output::MoveHoveringModule {
-                                                          x,
-                                                          y,
-                                                          module_w: dragging_module_rect.w,
-                                                          module_h: dragging_module_rect.h,
-                                                      });
-                                } else {
-                                    // clog!("new hovering module Uuid is not found in UpdateMousePosition");
-                                }
-                            }
-                        } else {
-                            // clog!("dragging module rect is not found in UpdateMousePosition");
-                        }
-                    }
-                }
+                self.mouse_x = x;
+                self.mouse_y = y;
+                self.update_hovering();
             }
-            Self::Input::UpdateHoveringIndex(index) => self.hovering_index = index,
+            Self::Input::UpdateHoveringIndex(index) => self.set_hovering_index(index),
             Self::Input::UpdateRect { x, y, w, h } => {
-                // clog!(format!("{:?}: {:?}=>{:?}",id,self.rectangles.get(&id),Rect {x, y, w, h,}));
                 if let Some(uuid) = self.uuid_map.get_by_right(&id) {
-                    self.rectangles.insert(*uuid, Rect {
+                    self.spatial_index.insert(*uuid, self.view_transform.rect_to_content(&Rect {
                         x,
                         y,
                         w,
                         h,
-                    });
+                    }));
                 }
-                // clog!(format!("{:?}",self.rectangles));
             }
             Self::Input::SetMyId(uuid) => {
                 self.insert_uuid(uuid, id);
@@ -228,30 +283,54 @@ impl<Controller, T> Agent for DragModuleAgent<Controller, T>
             Self::Input::SetParentId { my_id, parent_id } => {
                 self.insert_uuid(my_id, id);
                 self.parent_map.insert(my_id, parent_id);
-                if let Some(set) = self.children_map.get_mut(&parent_id) {
-                    set.insert(my_id);
-                } else {
-                    let mut set = HashSet::new();
-                    set.insert(my_id);
-                    self.children_map.insert(parent_id, set);
-                }
             }
             DragModuleAgentInputMessage::SetDraggingComponentId => self.dragging_component = Some(id),
             DragModuleAgentInputMessage::SetTrashArea { x, y, w, h } => self.trash_area = Rect { x, y, w, h },
+            DragModuleAgentInputMessage::SetSnapDistance(distance) => self.snap_distance = distance,
+            DragModuleAgentInputMessage::SetLayout { layout, x, y } => {
+                self.clear_layout();
+                let (origin_x, origin_y) = self.view_transform.to_content(x, y);
+                for (module, parent, rect) in layout.rects() {
+                    self.spatial_index.insert(module, rect.translated(origin_x, origin_y));
+                    self.parent_map.insert(module, parent);
+                }
+                self.layout = Some((layout, origin_x, origin_y));
+                self.layout_owner = Some(id);
+            }
+            DragModuleAgentInputMessage::SetViewTransform(transform) => {
+                if self.view_transform != transform {
+                    self.view_transform = transform;
+                    if let Some(id) = self.layout_owner {
+                        self.link.respond(id, Self::Output::RequestUpdateRect);
+                    }
+                    if self.dragging_module.is_some() {
+                        if let HoveringModuleType::Module(hovering_id) = self.hovering_module {
+                            for (child, _) in self.parent_map.iter().filter(|(_, parent)| **parent == hovering_id) {
+                                if let Some(id) = self.uuid_map.get_by_left(child) {
+                                    self.link.respond(*id, Self::Output::RequestUpdateRect);
+                                }
+                            }
+                        }
+                        self.update_hovering();
+                    }
+                }
+            }
             // DragModuleAgentInputMessage::Clear => {
-            // self.rectangles.clear();
+            // self.spatial_index.clear();
             // self.uuid_map.clear();
             // self.parent_map.clear();
-            // self.children_map.clear();
             // }
         }
     }
 
     fn disconnected(&mut self, id: HandlerId) {
+        if self.layout_owner == Some(id) {
+            self.clear_layout();
+            self.layout_owner = None;
+        }
         if let Some(uuid) = self.uuid_map.get_by_right(&id) {
-            self.rectangles.remove(uuid);
+            self.spatial_index.remove(uuid);
             self.parent_map.remove(uuid);
-            self.children_map.remove(uuid);
         }
         self.uuid_map.remove_by_right(&id);
     }
@@ -275,33 +354,159 @@ impl<Controller, T> DragModuleAgent<Controller, T>
         // clog!(format!("{:?}",self.uuid_map));
     }
 
-    fn get_hovering_module_uuid(&self, x: i32, y: i32, dragging: Uuid) -> HoveringModuleType {
-        if self.root_module_id == None { return HoveringModuleType::None; }
-        let mut q = VecDeque::new();
-        q.push_back(self.root_module_id.unwrap());
-        let mut sender = HoveringModuleType::None;
-        // let mut set = HashSet::new();
-        while let Some(id) = q.pop_front() {
-            // set.insert(id);
-            if dragging == id { continue; }
-            // clog!(format!("get rect by {}",id));
-            if let Some(rect) = self.rectangles.get(&id) {
-                if rect.x <= x as f64 && x as f64 <= rect.x + rect.w &&
-                    rect.y <= y as f64 && y as f64 <= rect.y + rect.h {
-                    if let Some(_) = self.uuid_map.get_by_left(&id) {
-                        sender = HoveringModuleType::Module(id.clone());
+    fn update_hovering(&mut self) {
+        let (x, y) = (self.mouse_x, self.mouse_y);
+        if let Some(module) = &self.dragging_module {
+            if let Some(id) = self.dragging_component {
+                if let Some(dragging_module_rect) = self.spatial_index.get(&module.id()) {
+                    self.link.respond(id, DragModuleAgentOutputMessage::UpdateDraggingModulePosition {
+                        x,
+                        y,
+                    });
+                    let mut hovering = if self.trash_area.encloses(x as f64, y as f64) {
+                        HoveringModuleType::TrashArea
+                    } else {
+                        self.get_hovering_module_uuid(x, y, &self.dragging_ids())
+                    };
+                    let mut layout_index = None;
+                    if let (HoveringModuleType::Module(hovering_id), Some((layout, origin_x, origin_y))) = (hovering, &self.layout) {
+                        if layout.contains(hovering_id) {
+                            let (x, y) = self.view_transform.to_content(x as f64, y as f64);
+                            if let Some((target, index)) = layout.drop_index(hovering_id, x - origin_x, y - origin_y) {
+                                hovering = HoveringModuleType::Module(target);
+                                layout_index = Some(index);
+                            }
+                        }
                     }
+                    if self.hovering_module != hovering {
+                        if let HoveringModuleType::Module(now_hovering_module) = self.hovering_module {
+                            if let Some(now_hovering_module) = self.uuid_map.get_by_left(&now_hovering_module) {
+                                self.link.respond(*now_hovering_module, DragModuleAgentOutputMessage::LeaveHoveringModule);
+                            } else {
+                                // clog!("now hovering module Uuid is not found in UpdateMousePosition");
+                            }
+                        }
+                        if (self.hovering_module == HoveringModuleType::TrashArea) != (hovering == HoveringModuleType::TrashArea) {
+                            if let Some(manager) = self.manager_id {
+                                self.link.respond(manager, DragModuleAgentOutputMessage::HoverTrashArea(hovering == HoveringModuleType::TrashArea));
+                            }
+                        }
+                        self.hovering_module = hovering;
+                        self.hovering_index = None;
+                    }
+                    if let HoveringModuleType::Module(id) = &hovering {
+                        if let Some(now_hovering_module) = self.uuid_map.get_by_left(&id) {
+                            self.link.respond(*now_hovering_module,
+                                              DragModuleAgentOutputMessage::MoveHoveringModule {
+                                                  x,
+                                                  y,
+                                                  module_w: dragging_module_rect.w,
+                                                  module_h: dragging_module_rect.h,
+                                              });
+                        } else {
+                            // clog!("new hovering module Uuid is not found in UpdateMousePosition");
+                        }
+                        if self.is_layout_module(*id) {
+                            self.set_hovering_index(layout_index);
+                        }
+                    }
+                    self.update_layout_hovering();
+                } else {
+                    // clog!("dragging module rect is not found in UpdateMousePosition");
                 }
-            } else {}
-            if let Some(map) = self.children_map.get(&id) {
-                for id in map {
-                    q.push_back(id.clone());
-                }
             }
         }
-        // let uuid = self.uuid_map.left_values().collect::<HashSet<_>>();
-        // let set = uuid.iter().filter(|id| !set.contains(id)).collect::<HashSet<_>>();
-        // clog!(format!("{:#?}",set));
-        sender
+    }
+
+    fn start_drag(&mut self, offset_x: i32, offset_y: i32, module: ProgramModule<T>, following: Vec<ProgramModule<T>>, mode: DragMode) {
+        // clog!("StartDrag", module.id.to_string());
+        if let Some(id) = self.manager_id {
+            self.link.respond(id, DragModuleAgentOutputMessage::CreateDragComponent {
+                offset_x,
+                offset_y,
+                module: module.deep_clone(),
+                following: following.iter().map(ProgramModule::deep_clone).collect(),
+                mode,
+            });
+            self.dragging_module = Some(module);
+            self.dragging_following = following;
+            self.dragging_mode = mode;
+        }
+        for id in self.uuid_map.right_values() {
+            self.link.respond(*id, DragModuleAgentOutputMessage::RequestUpdateRect);
+        }
+        if let Some(id) = self.layout_owner {
+            self.link.respond(id, DragModuleAgentOutputMessage::RequestUpdateRect);
+        }
+    }
+
+    fn set_hovering_index(&mut self, index: Option<DotEveryEditorOperationIndex>) {
+        if self.hovering_index != index {
+            self.hovering_index = index;
+            if let (true, HoveringModuleType::Module(target), Some(index)) = (self.dragging_module.is_some(), self.hovering_module, index) {
+                self.logic_agent_bridge.send(DotEveryEditorAgentInputMessage::CanAdd(target, index, self.dragging_modules()));
+            }
+        }
+    }
+
+    fn is_layout_module(&self, id: Uuid) -> bool {
+        self.layout.as_ref().map(|(layout, _, _)| layout.contains(id)).unwrap_or(false)
+    }
+
+    fn update_layout_hovering(&mut self) {
+        let hovering = match (self.hovering_module, self.hovering_index) {
+            (HoveringModuleType::Module(target), Some(index)) if self.is_layout_module(target) => Some((target, index)),
+            _ => None,
+        };
+        if self.layout_hovering != hovering {
+            self.layout_hovering = hovering;
+            if let Some(owner) = self.layout_owner {
+                self.link.respond(owner, DragModuleAgentOutputMessage::UpdateLayoutHovering(hovering));
+            }
+        }
+    }
+
+    fn clear_layout(&mut self) {
+        if let Some((layout, _, _)) = self.layout.take() {
+            for (module, _, _) in layout.rects() {
+                self.spatial_index.remove(&module);
+                self.parent_map.remove(&module);
+            }
+        }
+    }
+
+    fn dragging_modules(&self) -> Vec<ProgramModule<T>> {
+        self.dragging_module.iter().chain(&self.dragging_following).cloned().collect()
+    }
+
+    fn dragging_ids(&self) -> Vec<Uuid> {
+        self.dragging_module.iter().chain(&self.dragging_following).map(ProgramModule::id).collect()
+    }
+
+    fn get_hovering_module_uuid(&self, x: i32, y: i32, dragging: &[Uuid]) -> HoveringModuleType {
+        let root = match self.root_module_id {
+            Some(root) => root,
+            None => return HoveringModuleType::None,
+        };
+        let (x, y) = self.view_transform.to_content(x as f64, y as f64);
+        self.spatial_index
+            .nearest(x, y, self.snap_distance / self.view_transform.scale)
+            .into_iter()
+            .find(|id| (self.uuid_map.get_by_left(id).is_some() || self.is_layout_module(*id)) && self.is_drop_target(*id, root, dragging))
+            .map(HoveringModuleType::Module)
+            .unwrap_or(HoveringModuleType::None)
+    }
+
+    fn is_drop_target(&self, id: Uuid, root: Uuid, dragging: &[Uuid]) -> bool {
+        let mut id = id;
+        for _ in 0..=self.parent_map.len() {
+            if dragging.contains(&id) { return false; }
+            if id == root { return true; }
+            match self.parent_map.get(&id) {
+                Some(parent) => id = *parent,
+                None => return false,
+            }
+        }
+        false
     }
 }
\ No newline at end of file
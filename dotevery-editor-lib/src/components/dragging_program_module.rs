@@ -1,30 +1,46 @@
+use std::rc::Rc;
+use std::sync::Arc;
+
 use either::Either;
 use wasm_bindgen::JsCast;
 use web_sys::{Element, HtmlInputElement};
 use yew::{Component, ComponentLink, Html};
 use yew::prelude::*;
 
+use crate::components::dotevery_editor::{DragThreshold, EditorRenderer};
 use crate::components::drag_module_agent::{DragModuleAgent, DragModuleAgentInputMessage, DragModuleAgentOutputMessage};
 use crate::components::program_module::{ProgramModuleComponent, ProgramModuleComponentImplTypeCanNotDrag, ProgramModuleProperties};
+use crate::components::program_module_renderer::ProgramModuleRenderer;
+use crate::components::svg_program::{SVG_FONT, view_svg_module};
+use crate::logic::canvas_text_metrics::CanvasTextMetrics;
+use crate::logic::cursor::EditorCursor;
 use crate::logic::dotevery_editor_controller::DotEveryEditorController;
+use crate::logic::layout::{LayoutMetrics, ModuleLayout};
+use crate::logic::i18n::MessageCatalog;
 use crate::logic::program_module::{ProgramModule, ProgramModuleOption};
 
 #[derive(Clone, Properties, PartialEq)]
 pub(crate) struct DraggingProgramModuleProperties<T: 'static + Clone + PartialEq> {
     pub(crate) program_module: ProgramModule<T>,
+    pub(crate) following: Vec<ProgramModule<T>>,
     pub(crate) offset_x: i32,
     pub(crate) offset_y: i32,
     pub(crate) visibility: bool,
+    pub(crate) scale: f64,
+    pub(crate) renderer: EditorRenderer,
+    pub(crate) messages: Rc<MessageCatalog>,
 }
 
 pub(crate) struct DraggingProgramModuleComponent<Controller, Type>
     where Controller: 'static + DotEveryEditorController<Type>,
-          Type: 'static + Clone + PartialEq {
+          Type: 'static + Clone + PartialEq + ProgramModuleRenderer {
     link: ComponentLink<Self>,
     props: DraggingProgramModuleProperties<Type>,
     drag_module_agent_bridge: Box<dyn Bridge<DragModuleAgent<Controller, Type>>>,
     element_x: i32,
     element_y: i32,
+    metrics: LayoutMetrics,
+    text_metrics: CanvasTextMetrics,
 }
 
 pub(crate) enum DraggingProgramModuleMessage {
@@ -35,7 +51,7 @@ pub(crate) enum DraggingProgramModuleMessage {
 
 impl<Controller, T> Component for DraggingProgramModuleComponent<Controller, T>
     where Controller: 'static + DotEveryEditorController<T>,
-          T: 'static + Clone + PartialEq {
+          T: 'static + Clone + PartialEq + ProgramModuleRenderer {
     type Message = DraggingProgramModuleMessage;
     type Properties = DraggingProgramModuleProperties<T>;
 
@@ -55,6 +71,8 @@ impl<Controller, T> Component for DraggingProgramModuleComponent<Controller, T>
             drag_module_agent_bridge: bridge,
             element_x: 0,
             element_y: 0,
+            metrics: LayoutMetrics::default(),
+            text_metrics: CanvasTextMetrics::new(SVG_FONT, &LayoutMetrics::default()),
         }
     }
 
@@ -80,15 +98,26 @@ impl<Controller, T> Component for DraggingProgramModuleComponent<Controller, T>
     }
 
     fn view(&self) -> Html {
-        if self.props.visibility {
-            let props = ProgramModuleProperties {
-                program_module: Either::Left(self.props.program_module.clone()),
-                rect_changed_callback: self.link.callback(|_| Self::Message::Ignore),
-            };
-            let style = format!("position:absolute;top:{}px;left:{}px;", self.element_y, self.element_x);
+        if self.props.visibility && self.props.renderer == EditorRenderer::Svg {
+            self.view_svg()
+        } else if self.props.visibility {
+            let modules = std::iter::once(&self.props.program_module).chain(&self.props.following).map(|module| {
+                let props = ProgramModuleProperties {
+                    program_module: Either::Left(Arc::new(module.clone())),
+                    rect_changed_callback: self.link.callback(|_| Self::Message::Ignore),
+                    cursor: EditorCursor::default(),
+                    drag_threshold: DragThreshold::default(),
+                    messages: Rc::clone(&self.props.messages),
+                    read_only: false,
+                };
+                html! {
+                    <ProgramModuleComponent<Controller, T, ProgramModuleComponentImplTypeCanNotDrag> with props/>
+                }
+            });
+            let style = format!("position:absolute;top:{}px;left:{}px;transform:scale({});", self.element_y, self.element_x, self.props.scale);
             let html: Html = html! {
                 <div style=style class="program_module_dragging">
-                    <ProgramModuleComponent<Controller, T, ProgramModuleComponentImplTypeCanNotDrag> with props/>
+                    {for modules}
                     // {self.props.program_module.id}
                     // <div onmousemove=mouse_move class="program_module_options">
                     //     {for options}
@@ -103,3 +132,28 @@ impl<Controller, T> Component for DraggingProgramModuleComponent<Controller, T>
 
     fn rendered(&mut self, _first_render: bool) {}
 }
+
+impl<Controller, T> DraggingProgramModuleComponent<Controller, T>
+    where Controller: 'static + DotEveryEditorController<T>,
+          T: 'static + Clone + PartialEq + ProgramModuleRenderer {
+    fn view_svg(&self) -> Html {
+        let metrics = &self.metrics;
+        let mut y = 0f64;
+        let mut width = 0f64;
+        let modules = std::iter::once(&self.props.program_module).chain(&self.props.following).map(|module| {
+            let module = &self.props.messages.localize(module, &T::sign_tag);
+            let layout = ModuleLayout::with_text_metrics(module, metrics, &self.text_metrics, 0f64, y);
+            y += layout.rect.h;
+            width = f64::max(width, layout.rect.w);
+            view_svg_module(&layout, module, metrics, &|_| Callback::noop(), &|_, _| Callback::noop(), &|_| Callback::noop())
+        }).collect::<Vec<Html>>();
+        // leave room for the bottom connector of the last module
+        let height = y + metrics.connector_height;
+        let style = format!("position:absolute;top:{}px;left:{}px;transform:scale({});font:{};", self.element_y, self.element_x, self.props.scale, SVG_FONT);
+        html! {
+            <svg style=style class="program_module_dragging svg_program" width=width height=height>
+                {for modules}
+            </svg>
+        }
+    }
+}
@@ -0,0 +1,370 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use uuid::Uuid;
+use yew::{Bridge, Bridged, Callback};
+
+use crate::components::dotevery_editor_agent::{DotEveryEditorAgent, DotEveryEditorAgentInputMessage, DotEveryEditorAgentOutputMessage};
+use crate::components::dotevery_editor_controller_proxy_agent::DotEveryEditorControllerProxyAgent;
+use crate::components::drag_module_agent::{DragMode, DragModuleAgent, DragModuleAgentInputMessage, DragModuleAgentOutputMessage};
+use crate::logic::cursor::{CursorKey, CursorPosition, EditorCursor};
+use crate::logic::dotevery_editor::{DotEveryEditor, DotEveryEditorOperationIndex};
+use crate::logic::dotevery_editor_controller::DotEveryEditorController;
+use crate::logic::layout::{EditorLayout, LayoutMetrics};
+use crate::logic::palette::Palette;
+use crate::logic::program_module::{ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
+use crate::logic::viewport::ViewTransform;
+use crate::util::Rect;
+
+type Recorder<T> = Rc<RefCell<Vec<T>>>;
+
+fn recorder<T: 'static>() -> (Recorder<T>, Callback<T>) {
+    let recorder = Rc::new(RefCell::new(Vec::new()));
+    let callback = {
+        let recorder = Rc::clone(&recorder);
+        Callback::from(move |msg| recorder.borrow_mut().push(msg))
+    };
+    (recorder, callback)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum HeadlessModuleEvent {
+    MoveHoveringModule { x: i32, y: i32, module_w: f64, module_h: f64 },
+    LeaveHoveringModule,
+    UpdateDropValidity { index: DotEveryEditorOperationIndex, valid: bool },
+    RequestRegisterUuid,
+    RequestUpdateRect,
+}
+
+struct HeadlessModule<Controller, Type>
+    where Controller: 'static + DotEveryEditorController<Type>,
+          Type: 'static + Clone + PartialEq {
+    bridge: Box<dyn Bridge<DragModuleAgent<Controller, Type>>>,
+    parent: Option<Uuid>,
+    events: Recorder<HeadlessModuleEvent>,
+}
+
+pub struct HeadlessEditor<Controller, Type = ()>
+    where Controller: 'static + DotEveryEditorController<Type>,
+          Type: 'static + Clone + PartialEq {
+    logic_agent_bridge: Box<dyn Bridge<DotEveryEditorAgent<Controller, Type>>>,
+    drag_module_agent_bridge: Box<dyn Bridge<DragModuleAgent<Controller, Type>>>,
+    dragging_component_bridge: Box<dyn Bridge<DragModuleAgent<Controller, Type>>>,
+    controller_bridge: Box<dyn Bridge<DotEveryEditorControllerProxyAgent<Controller, Type>>>,
+    layout_bridge: Box<dyn Bridge<DragModuleAgent<Controller, Type>>>,
+    modules: HashMap<Uuid, HeadlessModule<Controller, Type>>,
+    logic_outputs: Recorder<DotEveryEditorAgentOutputMessage<Type, Controller::Output>>,
+    drag_outputs: Recorder<DragModuleAgentOutputMessage<Type>>,
+    dragging_component_outputs: Recorder<DragModuleAgentOutputMessage<Type>>,
+    controller_outputs: Recorder<Controller::Output>,
+    layout_outputs: Recorder<DragModuleAgentOutputMessage<Type>>,
+    logic_data: Rc<RefCell<DotEveryEditor<Type>>>,
+    palette_data: Rc<RefCell<Palette<Type>>>,
+    cursor: EditorCursor,
+}
+
+impl<Controller, T> HeadlessEditor<Controller, T>
+    where Controller: 'static + DotEveryEditorController<T>,
+          T: 'static + Clone + PartialEq {
+    pub fn new() -> Self {
+        let logic_data = Rc::new(RefCell::new(DotEveryEditor::new(Vec::new())));
+        let palette_data = Rc::new(RefCell::new(Palette::default()));
+        let (logic_outputs, logic_callback) = recorder();
+        let logic_callback = {
+            let logic_data = Rc::clone(&logic_data);
+            let palette_data = Rc::clone(&palette_data);
+            Callback::from(move |msg: DotEveryEditorAgentOutputMessage<T, Controller::Output>| {
+                match &msg {
+                    DotEveryEditorAgentOutputMessage::ModuleUpdated(logic) => *logic_data.borrow_mut() = logic.clone(),
+                    DotEveryEditorAgentOutputMessage::ModulesUpdated(modules) => {
+                        let mut logic = logic_data.borrow_mut();
+                        for module in modules {
+                            let _ = logic.replace_module(module.clone());
+                        }
+                    }
+                    DotEveryEditorAgentOutputMessage::PaletteUpdated(palette) => *palette_data.borrow_mut() = palette.clone(),
+                    _ => {}
+                }
+                logic_callback.emit(msg);
+            })
+        };
+        let mut logic_agent_bridge = DotEveryEditorAgent::bridge(logic_callback);
+        logic_agent_bridge.send(DotEveryEditorAgentInputMessage::SetMeManager);
+
+        let (drag_outputs, drag_callback) = recorder();
+        let mut drag_module_agent_bridge = DragModuleAgent::bridge(drag_callback);
+        drag_module_agent_bridge.send(DragModuleAgentInputMessage::SetRootId(logic_data.borrow().id()));
+
+        let (dragging_component_outputs, dragging_component_callback) = recorder();
+        let mut dragging_component_bridge = DragModuleAgent::bridge(dragging_component_callback);
+        dragging_component_bridge.send(DragModuleAgentInputMessage::SetDraggingComponentId);
+
+        let (controller_outputs, controller_callback) = recorder();
+        let controller_bridge = Controller::bridge(controller_callback);
+
+        let (layout_outputs, layout_callback) = recorder();
+        let layout_bridge = DragModuleAgent::bridge(layout_callback);
+
+        Self {
+            logic_agent_bridge,
+            drag_module_agent_bridge,
+            dragging_component_bridge,
+            controller_bridge,
+            layout_bridge,
+            modules: HashMap::new(),
+            logic_outputs,
+            drag_outputs,
+            dragging_component_outputs,
+            controller_outputs,
+            layout_outputs,
+            logic_data,
+            palette_data,
+            cursor: EditorCursor::default(),
+        }
+    }
+
+    pub fn editor(&self) -> DotEveryEditor<T> {
+        self.logic_data.borrow().clone()
+    }
+
+    pub fn palette(&self) -> Palette<T> {
+        self.palette_data.borrow().clone()
+    }
+
+    pub fn module(&self, id: Uuid) -> Option<ProgramModule<T>> {
+        self.logic_data.borrow().get_module(id).ok().cloned()
+    }
+
+    pub fn take_logic_outputs(&mut self) -> Vec<DotEveryEditorAgentOutputMessage<T, Controller::Output>> {
+        self.logic_outputs.replace(Vec::new())
+    }
+
+    pub fn take_drag_outputs(&mut self) -> Vec<DragModuleAgentOutputMessage<T>> {
+        self.drag_outputs.replace(Vec::new())
+    }
+
+    pub fn take_dragging_component_outputs(&mut self) -> Vec<DragModuleAgentOutputMessage<T>> {
+        self.dragging_component_outputs.replace(Vec::new())
+    }
+
+    pub fn take_controller_outputs(&mut self) -> Vec<Controller::Output> {
+        self.controller_outputs.replace(Vec::new())
+    }
+
+    pub fn take_layout_outputs(&mut self) -> Vec<DragModuleAgentOutputMessage<T>> {
+        self.layout_outputs.replace(Vec::new())
+    }
+
+    pub fn take_module_events(&mut self, id: Uuid) -> Vec<HeadlessModuleEvent> {
+        self.modules.get(&id).map(|module| module.events.replace(Vec::new())).unwrap_or_default()
+    }
+
+    pub fn hovering_module(&self) -> Option<Uuid> {
+        self.modules.iter().find_map(|(id, module)| match module.events.borrow().iter().rev().find(|event| matches!(event,
+            HeadlessModuleEvent::MoveHoveringModule { .. } | HeadlessModuleEvent::LeaveHoveringModule)) {
+            Some(HeadlessModuleEvent::MoveHoveringModule { .. }) => Some(*id),
+            _ => None,
+        })
+    }
+
+    pub fn send_to_logic(&mut self, msg: DotEveryEditorAgentInputMessage<T, Controller::Input>) {
+        self.logic_agent_bridge.send(msg);
+    }
+
+    pub fn send_to_drag(&mut self, msg: DragModuleAgentInputMessage<T>) {
+        self.drag_module_agent_bridge.send(msg);
+    }
+
+    pub fn send_to_controller(&mut self, msg: Controller::Input) {
+        self.controller_bridge.send(msg);
+    }
+
+    pub fn add(&mut self, target: Uuid, index: DotEveryEditorOperationIndex, module: ProgramModule<T>) {
+        self.send_to_logic(DotEveryEditorAgentInputMessage::Add(target, index, module));
+    }
+
+    pub fn remove(&mut self, id: Uuid) {
+        self.send_to_logic(DotEveryEditorAgentInputMessage::Remove(id));
+    }
+
+    pub fn update_input(&mut self, id: Uuid, index: usize, value: impl Into<String>) {
+        self.send_to_logic(DotEveryEditorAgentInputMessage::UpdateInput { id, index, value: value.into() });
+    }
+
+    pub fn set_collapsed(&mut self, id: Uuid, collapsed: bool) {
+        self.send_to_logic(DotEveryEditorAgentInputMessage::SetCollapsed { id, collapsed });
+    }
+
+    pub fn set_root(&mut self, list: Vec<ProgramModule<T>>) {
+        let len = self.logic_data.borrow().list.len();
+        for _ in 0..len {
+            let id = self.logic_data.borrow().list[0].id();
+            self.remove(id);
+        }
+        for (i, module) in list.into_iter().enumerate() {
+            self.add(Uuid::nil(), DotEveryEditorOperationIndex::Child(i), module);
+        }
+    }
+
+    pub fn set_rect(&mut self, id: Uuid, rect: Rect) {
+        self.prune_modules();
+        let parent = self.parent_of(id);
+        let registered = self.modules.get(&id).map(|module| module.parent == parent).unwrap_or(false);
+        if !registered {
+            let events = Rc::new(RefCell::new(Vec::new()));
+            let callback = {
+                let events = Rc::clone(&events);
+                Callback::from(move |out: DragModuleAgentOutputMessage<T>| {
+                    let event = match out {
+                        DragModuleAgentOutputMessage::MoveHoveringModule { x, y, module_w, module_h } => HeadlessModuleEvent::MoveHoveringModule { x, y, module_w, module_h },
+                        DragModuleAgentOutputMessage::LeaveHoveringModule => HeadlessModuleEvent::LeaveHoveringModule,
+                        DragModuleAgentOutputMessage::UpdateDropValidity { index, valid } => HeadlessModuleEvent::UpdateDropValidity { index, valid },
+                        DragModuleAgentOutputMessage::RequestRegisterUuid => HeadlessModuleEvent::RequestRegisterUuid,
+                        DragModuleAgentOutputMessage::RequestUpdateRect => HeadlessModuleEvent::RequestUpdateRect,
+                        _ => return,
+                    };
+                    events.borrow_mut().push(event);
+                })
+            };
+            let mut bridge = DragModuleAgent::bridge(callback);
+            if let Some(parent_id) = parent {
+                bridge.send(DragModuleAgentInputMessage::SetParentId { my_id: id, parent_id });
+            } else {
+                bridge.send(DragModuleAgentInputMessage::SetMyId(id));
+            }
+            self.modules.insert(id, HeadlessModule { bridge, parent, events });
+        }
+        if let Some(module) = self.modules.get_mut(&id) {
+            let Rect { x, y, w, h } = rect;
+            module.bridge.send(DragModuleAgentInputMessage::UpdateRect { x, y, w, h });
+        }
+    }
+
+    pub fn prune_modules(&mut self) {
+        let removed = self.modules
+            .iter()
+            .filter(|(id, module)| module.parent.is_some() && self.parent_of(**id) != module.parent)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        for id in removed {
+            self.modules.remove(&id);
+        }
+    }
+
+    pub fn cursor(&self) -> &EditorCursor {
+        &self.cursor
+    }
+
+    pub fn cursor_mut(&mut self) -> &mut EditorCursor {
+        &mut self.cursor
+    }
+
+    pub fn select(&mut self, position: CursorPosition) {
+        self.send_to_logic(DotEveryEditorAgentInputMessage::Select(position));
+        let selected = self.logic_outputs.borrow().iter().any(|output| matches!(output, DotEveryEditorAgentOutputMessage::Selected(selected) if *selected == position));
+        if selected {
+            self.cursor.set_position(Some(position));
+        }
+    }
+
+    pub fn press_key(&mut self, key: CursorKey) {
+        let logic = self.editor();
+        for operation in self.cursor.handle_key(&logic, key) {
+            self.send_to_logic(operation.into());
+        }
+    }
+
+    // registers the current editor the way the svg renderer does, with its top left corner at (x, y)
+    pub fn set_layout(&mut self, x: f64, y: f64) -> EditorLayout {
+        let layout = EditorLayout::new(&self.logic_data.borrow(), &LayoutMetrics::default());
+        self.layout_bridge.send(DragModuleAgentInputMessage::SetLayout { layout: layout.clone(), x, y });
+        layout
+    }
+
+    pub fn set_trash_area(&mut self, rect: Rect) {
+        let Rect { x, y, w, h } = rect;
+        self.send_to_drag(DragModuleAgentInputMessage::SetTrashArea { x, y, w, h });
+    }
+
+    pub fn set_view_transform(&mut self, transform: ViewTransform) {
+        self.send_to_drag(DragModuleAgentInputMessage::SetViewTransform(transform));
+    }
+
+    pub fn start_drag(&mut self, id: Uuid, offset_x: i32, offset_y: i32) {
+        if let Some(module) = self.module(id) {
+            self.start_drag_module(module, offset_x, offset_y);
+        }
+    }
+
+    pub fn start_drag_following(&mut self, id: Uuid, offset_x: i32, offset_y: i32) {
+        if let Some(module) = self.module(id) {
+            self.send_to_drag(DragModuleAgentInputMessage::TryStartDrag { offset_x, offset_y, module, mode: DragMode::MoveFollowing });
+        }
+    }
+
+    pub fn start_drag_duplicate(&mut self, id: Uuid, offset_x: i32, offset_y: i32) {
+        if let Some(module) = self.module(id) {
+            self.send_to_drag(DragModuleAgentInputMessage::TryStartDrag { offset_x, offset_y, module, mode: DragMode::Duplicate });
+        }
+    }
+
+    pub fn start_drag_module(&mut self, module: ProgramModule<T>, offset_x: i32, offset_y: i32) {
+        self.send_to_drag(DragModuleAgentInputMessage::TryStartDrag { offset_x, offset_y, module, mode: DragMode::Move });
+    }
+
+    pub fn move_pointer(&mut self, x: i32, y: i32) {
+        self.send_to_drag(DragModuleAgentInputMessage::UpdateMousePosition { x, y });
+    }
+
+    pub fn set_hovering_index(&mut self, index: Option<DotEveryEditorOperationIndex>) {
+        self.send_to_drag(DragModuleAgentInputMessage::UpdateHoveringIndex(index));
+    }
+
+    pub fn end_drag(&mut self) {
+        self.dragging_component_bridge.send(DragModuleAgentInputMessage::EndDrag);
+    }
+
+    pub fn drag_and_drop(&mut self, id: Uuid, x: i32, y: i32, index: DotEveryEditorOperationIndex) {
+        self.start_drag(id, 0, 0);
+        self.move_pointer(x, y);
+        self.set_hovering_index(Some(index));
+        self.end_drag();
+    }
+
+    fn parent_of(&self, id: Uuid) -> Option<Uuid> {
+        let logic = self.logic_data.borrow();
+        if id.is_nil() {
+            return Some(logic.id());
+        }
+        if logic.list.iter().any(|module| module.id() == id) {
+            return Some(Uuid::nil());
+        }
+        let mut stack = logic.list.iter().collect::<Vec<_>>();
+        while let Some(module) = stack.pop() {
+            let options = module.options.iter().filter_map(|option| match option {
+                ProgramModuleOption::ProgramModule(Some(module)) => Some(module),
+                _ => None,
+            });
+            let children = match &module.child {
+                ProgramModuleChildItems::BlockVertical(list) | ProgramModuleChildItems::BlockHorizontal(list) => list.iter(),
+                ProgramModuleChildItems::None => [].iter(),
+            };
+            for child in options.chain(children) {
+                if child.id() == id {
+                    return Some(module.id());
+                }
+                stack.push(child);
+            }
+        }
+        None
+    }
+}
+
+impl<Controller, T> Default for HeadlessEditor<Controller, T>
+    where Controller: 'static + DotEveryEditorController<T>,
+          T: 'static + Clone + PartialEq {
+    fn default() -> Self {
+        Self::new()
+    }
+}
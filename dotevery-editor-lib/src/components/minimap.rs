@@ -0,0 +1,109 @@
+use uuid::Uuid;
+use web_sys::Element;
+use yew::{Component, ComponentLink, Html};
+use yew::prelude::*;
+
+use crate::logic::layout::EditorLayout;
+use crate::logic::viewport::minimap_scale;
+use crate::util::Rect;
+
+const MINIMAP_WIDTH: f64 = 160.0;
+const MINIMAP_HEIGHT: f64 = 240.0;
+
+#[derive(Clone, Properties, PartialEq)]
+pub(crate) struct MinimapProperties {
+    pub(crate) layout: EditorLayout,
+    // the visible part of the canvas in content coordinates
+    pub(crate) view_area: Option<Rect>,
+    pub(crate) onnavigate: Callback<(f64, f64)>,
+}
+
+pub(crate) struct MinimapComponent {
+    link: ComponentLink<Self>,
+    props: MinimapProperties,
+    svg_ref: NodeRef,
+    dragging: bool,
+}
+
+pub(crate) enum MinimapMessage {
+    Press { pointer_id: i32, x: i32, y: i32 },
+    Drag { x: i32, y: i32 },
+    Release,
+}
+
+impl Component for MinimapComponent {
+    type Message = MinimapMessage;
+    type Properties = MinimapProperties;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self { link, props, svg_ref: NodeRef::default(), dragging: false }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> bool {
+        match msg {
+            Self::Message::Press { pointer_id, x, y } => {
+                self.dragging = true;
+                if let Some(element) = self.svg_ref.cast::<Element>() {
+                    if let Err(err) = element.set_pointer_capture(pointer_id) {
+                        clog!(err);
+                    }
+                }
+                self.navigate(x, y);
+            }
+            Self::Message::Drag { x, y } => {
+                if self.dragging { self.navigate(x, y); }
+            }
+            Self::Message::Release => self.dragging = false,
+        }
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> bool {
+        if self.props == props { return false; }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html {
+        let bounds = &self.props.layout.bounds;
+        let scale = self.scale();
+        let modules = self.props.layout.rects().into_iter()
+            .filter(|(id, _, _)| !id.is_nil())
+            .map(|(_, parent, rect)| {
+                let class = if parent == Uuid::nil() { "dotevery_editor_minimap_module dotevery_editor_minimap_stack" } else { "dotevery_editor_minimap_module" };
+                html! {<rect class=class x=rect.x y=rect.y width=rect.w height=rect.h/>}
+            });
+        let view_area = match &self.props.view_area {
+            Some(area) => html! {<rect class="dotevery_editor_minimap_view" x=area.x y=area.y width=area.w height=area.h/>},
+            None => html! {},
+        };
+        let transform = format!("scale({}) translate({} {})", scale, -bounds.x, -bounds.y);
+        let pointer_down = self.link.callback(|e: PointerEvent| MinimapMessage::Press { pointer_id: e.pointer_id(), x: e.client_x(), y: e.client_y() });
+        let pointer_move = self.link.callback(|e: PointerEvent| MinimapMessage::Drag { x: e.client_x(), y: e.client_y() });
+        let pointer_up = self.link.callback(|_| MinimapMessage::Release);
+        html! {
+            <svg ref=self.svg_ref.clone() class="dotevery_editor_minimap" width=MINIMAP_WIDTH height=MINIMAP_HEIGHT onpointerdown=pointer_down onpointermove=pointer_move onpointerup=pointer_up onpointercancel=self.link.callback(|_| MinimapMessage::Release)>
+                <g transform=transform>
+                    {for modules}
+                    {view_area}
+                </g>
+            </svg>
+        }
+    }
+}
+
+impl MinimapComponent {
+    fn scale(&self) -> f64 {
+        minimap_scale(&self.props.layout.bounds, MINIMAP_WIDTH, MINIMAP_HEIGHT)
+    }
+
+    // x and y are client coordinates
+    fn navigate(&self, x: i32, y: i32) {
+        if let Some(element) = self.svg_ref.cast::<Element>() {
+            let rect = element.get_bounding_client_rect();
+            let bounds = &self.props.layout.bounds;
+            let scale = self.scale();
+            self.props.onnavigate.emit(((x as f64 - rect.x()) / scale + bounds.x, (y as f64 - rect.y()) / scale + bounds.y));
+        }
+    }
+}
@@ -10,8 +10,16 @@ pub(crate) mod dotevery_editor_agent;
 pub mod dotevery_editor;
 pub(crate) mod drag_module_agent;
 pub(crate) mod program_module;
+pub mod program_module_renderer;
+pub(crate) mod svg_program;
+pub(crate) mod outline;
+pub(crate) mod minimap;
 pub(crate) mod dotevery_editor_agent_bridge;
 pub(crate) mod dotevery_editor_controller_proxy_agent;
+pub mod headless;
+pub mod viewer;
+#[cfg(test)]
+mod tests;
 
 pub type DotEveryBridge<Controller, Type = ()> = DotEveryEditorAgentBridge<Controller, Type>;
 pub type DotEveryEditorControllerBridge<Controller, Type = ()> = Box<dyn Bridge<DotEveryEditorControllerProxyAgent<Controller, Type>>>;
\ No newline at end of file
@@ -0,0 +1,77 @@
+use std::rc::Rc;
+
+use uuid::Uuid;
+use yew::{Component, ComponentLink, Html};
+use yew::prelude::*;
+
+use crate::components::program_module_renderer::ProgramModuleRenderer;
+use crate::logic::dotevery_editor::DotEveryEditor;
+use crate::logic::i18n::MessageCatalog;
+use crate::logic::outline::{outline, OutlineEntry};
+
+const OUTLINE_INDENT: usize = 12;
+
+#[derive(Clone, Properties, PartialEq)]
+pub(crate) struct OutlineProperties<T: 'static + Clone + PartialEq> {
+    pub(crate) editor: DotEveryEditor<T>,
+    pub(crate) selected: Option<Uuid>,
+    pub(crate) onselect: Callback<Uuid>,
+    pub(crate) messages: Rc<MessageCatalog>,
+}
+
+pub(crate) struct OutlineComponent<Type>
+    where Type: 'static + Clone + PartialEq + ProgramModuleRenderer {
+    props: OutlineProperties<Type>,
+    entries: Vec<OutlineEntry<Type>>,
+}
+
+impl<T> Component for OutlineComponent<T>
+    where T: 'static + Clone + PartialEq + ProgramModuleRenderer {
+    type Message = ();
+    type Properties = OutlineProperties<T>;
+
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
+        let entries = outline(&props.messages.localize_editor(&props.editor, &T::sign_tag));
+        Self { props, entries }
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> bool {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> bool {
+        if self.props == props { return false; }
+        if self.props.editor != props.editor || self.props.messages != props.messages {
+            self.entries = outline(&props.messages.localize_editor(&props.editor, &T::sign_tag));
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html {
+        let entries = self.entries.iter().map(|entry| {
+            let id = entry.id;
+            let onclick = self.props.onselect.reform(move |_: MouseEvent| id);
+            let mut class = "dotevery_editor_outline_item".to_string();
+            if self.props.selected == Some(id) { class.push_str(" dotevery_editor_outline_item_selected"); }
+            let style = format!("padding-left:{}px;", entry.depth * OUTLINE_INDENT);
+            let label = match entry.type_data.label() {
+                Some(label) => html! {<span class="dotevery_editor_outline_label">{label}</span>},
+                None => html! {},
+            };
+            let mut text = entry.text.clone();
+            if entry.collapsed { text.push_str(" …"); }
+            html! {
+                <div class=class style=style onclick=onclick>
+                    {label}
+                    <span class="dotevery_editor_outline_text">{text}</span>
+                </div>
+            }
+        });
+        html! {
+            <div class="dotevery_editor_outline">
+                {for entries}
+            </div>
+        }
+    }
+}
@@ -1,31 +1,60 @@
 use std::collections::HashMap;
 use std::marker::PhantomData;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
 
 use either::Either;
 use uuid::Uuid;
 use wasm_bindgen::JsCast;
-use web_sys::{Element, HtmlInputElement, window};
+use web_sys::{Element, HtmlElement, HtmlInputElement, window};
 use yew::{Component, ComponentLink, Html};
 use yew::prelude::*;
+use yew::services::TimeoutService;
+use yew::services::timeout::TimeoutTask;
 
 use crate::components::dotevery_editor_agent::{DotEveryEditorAgent, DotEveryEditorAgentInputMessage};
-use crate::components::drag_module_agent::{DragModuleAgent, DragModuleAgentInputMessage, DragModuleAgentOutputMessage};
+use crate::components::dotevery_editor::DragThreshold;
+use crate::components::drag_module_agent::{DragMode, DragModuleAgent, DragModuleAgentInputMessage, DragModuleAgentOutputMessage};
 use crate::components::impl_splitter::ImplSplitter;
+use crate::components::program_module_renderer::{shape_class, ProgramModuleRenderer};
+use crate::logic::cursor::{CursorPosition, EditorCursor};
 use crate::logic::dotevery_editor::DotEveryEditorOperationIndex;
 use crate::logic::dotevery_editor_controller::DotEveryEditorController;
+use crate::logic::i18n::MessageCatalog;
 use crate::logic::program_module::{ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
 use crate::util::Rect;
 
+const LONG_PRESS_DURATION: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Copy, PartialEq)]
+struct PointerPress {
+    pointer_id: i32,
+    x: i32,
+    y: i32,
+    last_x: i32,
+    last_y: i32,
+    long_press: bool,
+    delay_elapsed: bool,
+    mode: DragMode,
+}
+
 #[derive(Clone, PartialEq)]
 pub(crate) struct ProgramModuleDefault<T: 'static + Clone + PartialEq> {
-    pub(crate) list: Vec<ProgramModule<T>>,
+    pub(crate) list: Vec<Arc<ProgramModule<T>>>,
+    // Some(height) renders the module as an empty placeholder of that height
+    pub(crate) virtual_heights: Vec<Option<f64>>,
     pub(crate) parent: Uuid,
 }
 
 #[derive(Clone, Properties)]
 pub(crate) struct ProgramModuleProperties<T: 'static + Clone + PartialEq> {
-    pub(crate) program_module: Either<ProgramModule<T>, ProgramModuleDefault<T>>,
+    pub(crate) program_module: Either<Arc<ProgramModule<T>>, ProgramModuleDefault<T>>,
     pub(crate) rect_changed_callback: Callback<(Uuid, Rect)>,
+    pub(crate) cursor: EditorCursor,
+    pub(crate) drag_threshold: DragThreshold,
+    pub(crate) messages: Rc<MessageCatalog>,
+    pub(crate) read_only: bool,
 }
 
 #[derive(PartialEq)]
@@ -58,7 +87,7 @@ impl ImplSplitter<ProgramModuleComponentImplType> for ProgramModuleComponentImpl
 
 pub(crate) struct ProgramModuleComponent<Controller, Type, ImplType>
     where Controller: 'static + DotEveryEditorController<Type>,
-          Type: 'static + Clone + PartialEq,
+          Type: 'static + Clone + PartialEq + ProgramModuleRenderer,
           ImplType: 'static + ImplSplitter<ProgramModuleComponentImplType> {
     link: ComponentLink<Self>,
     props: ProgramModuleProperties<Type>,
@@ -67,23 +96,38 @@ pub(crate) struct ProgramModuleComponent<Controller, Type, ImplType>
     options_ref: NodeRef,
     child_ref: NodeRef,
     options_node_ref: Vec<NodeRef>,
+    virtual_refs: Vec<NodeRef>,
+    // shared with the child components so that an unchanged child is skipped by pointer comparison
+    shared_options: Vec<Option<Arc<ProgramModule<Type>>>>,
+    shared_child: Vec<Arc<ProgramModule<Type>>>,
     drag_module_agent_bridge: Box<dyn Bridge<DragModuleAgent<Controller, Type>>>,
     logic_agent_bridge: Box<dyn Bridge<DotEveryEditorAgent<Controller, Type>>>,
     hovering_module: Option<(i32, i32, f64, f64)>,
     hovering_index: Option<DotEveryEditorOperationIndex>,
+    drop_valid: bool,
     element_x: i32,
     element_y: i32,
+    pointer_press: Option<PointerPress>,
+    press_task: Option<TimeoutTask>,
     phantom_data: PhantomData<ImplType>,
 }
 
 pub(crate) enum ProgramModuleMessage {
     Ignore,
-    Drag { mouse_x: i32, mouse_y: i32 },
-    NoDrag,
+    PointerDown { pointer_id: i32, x: i32, y: i32, long_press: bool, mode: DragMode },
+    PointerMove { pointer_id: i32, x: i32, y: i32 },
+    PointerUp { pointer_id: i32 },
+    PointerCancel { pointer_id: i32 },
+    LongPress,
+    DragDelayElapsed,
+    FocusInput(usize),
     MoveHoveringModule { x: i32, y: i32, module_w: f64, module_h: f64 },
     LeaveHoveringModule,
+    UpdateDropValidity { index: DotEveryEditorOperationIndex, valid: bool },
     UpdateMousePosition { x: i32, y: i32 },
     UpdateInput { index: usize, value: String },
+    ToggleCollapsed,
+    OpenContextMenu { x: i32, y: i32 },
     UpdateSelfRect,
     UpdateChildRect { id: Uuid, rect: Rect },
     RegisterUuid,
@@ -91,7 +135,7 @@ pub(crate) enum ProgramModuleMessage {
 
 impl<Controller, T, ImplType> Component for ProgramModuleComponent<Controller, T, ImplType>
     where Controller: 'static + DotEveryEditorController<T>,
-          T: 'static + Clone + PartialEq,
+          T: 'static + Clone + PartialEq + ProgramModuleRenderer,
           ImplType: 'static + ImplSplitter<ProgramModuleComponentImplType> {
     type Message = ProgramModuleMessage;
     type Properties = ProgramModuleProperties<T>;
@@ -103,6 +147,7 @@ impl<Controller, T, ImplType> Component for ProgramModuleComponent<Controller, T
                     DragModuleAgentOutputMessage::UpdateDraggingModulePosition { x, y } => Self::Message::UpdateMousePosition { x, y },
                     DragModuleAgentOutputMessage::MoveHoveringModule { x, y, module_w, module_h } => Self::Message::MoveHoveringModule { x, y, module_w, module_h },
                     DragModuleAgentOutputMessage::LeaveHoveringModule => Self::Message::LeaveHoveringModule,
+                    DragModuleAgentOutputMessage::UpdateDropValidity { index, valid } => Self::Message::UpdateDropValidity { index, valid },
                     DragModuleAgentOutputMessage::RequestRegisterUuid => Self::Message::RegisterUuid,
                     DragModuleAgentOutputMessage::RequestUpdateRect => Self::Message::UpdateSelfRect,
                     _ => Self::Message::Ignore,
@@ -112,10 +157,10 @@ impl<Controller, T, ImplType> Component for ProgramModuleComponent<Controller, T
         if ImplType::VALUE != ProgramModuleComponentImplType::CanNotDrag {
             match &props.program_module {
                 Either::Left(module) => {
-                    if let Some(parent) = module.parent {
-                        drag_module_agent_bridge.send(DragModuleAgentInputMessage::SetParentId { my_id: module.id, parent_id: parent });
+                    if let Some(parent) = module.parent() {
+                        drag_module_agent_bridge.send(DragModuleAgentInputMessage::SetParentId { my_id: module.id(), parent_id: parent });
                     } else {
-                        drag_module_agent_bridge.send(DragModuleAgentInputMessage::SetMyId(module.id));
+                        drag_module_agent_bridge.send(DragModuleAgentInputMessage::SetMyId(module.id()));
                     }
                 }
                 Either::Right(ProgramModuleDefault { parent, .. }) => {
@@ -125,6 +170,7 @@ impl<Controller, T, ImplType> Component for ProgramModuleComponent<Controller, T
         }
         let callback = link.callback(|_| Self::Message::Ignore);
         let logic_agent_bridge = DotEveryEditorAgent::bridge(callback);
+        let (shared_options, shared_child) = share_children(&props.program_module);
         let options_node_ref =
             if let Either::Left(module) = &props.program_module {
                 (0..module.options.len()).map(|_| NodeRef::default()).collect()
@@ -139,12 +185,18 @@ impl<Controller, T, ImplType> Component for ProgramModuleComponent<Controller, T
             options_ref: NodeRef::default(),
             child_ref: NodeRef::default(),
             options_node_ref,
+            virtual_refs: (0..shared_child.len()).map(|_| NodeRef::default()).collect(),
+            shared_options,
+            shared_child,
             drag_module_agent_bridge,
             logic_agent_bridge,
             hovering_module: None,
             hovering_index: None,
+            drop_valid: true,
             element_x: 0,
             element_y: 0,
+            pointer_press: None,
+            press_task: None,
             phantom_data: PhantomData,
         }
     }
@@ -152,25 +204,80 @@ impl<Controller, T, ImplType> Component for ProgramModuleComponent<Controller, T
     fn update(&mut self, msg: Self::Message) -> bool {
         match msg {
             Self::Message::Ignore => false,
-            Self::Message::Drag { mouse_x: x, mouse_y: y } => {
-                if ImplType::VALUE != ProgramModuleComponentImplType::CanNotDrag {
+            Self::Message::PointerDown { pointer_id, x, y, long_press, mode } => {
+                if self.props.read_only {
                     if let Either::Left(module) = &self.props.program_module {
-                        // clog!("call TryStartDrag");
-                        let self_element = self.self_ref.cast::<Element>().unwrap();
-                        let rect = self_element.get_bounding_client_rect();
-                        let offset = get_page_offset();
-                        self.drag_module_agent_bridge.send(DragModuleAgentInputMessage::TryStartDrag {
-                            offset_x: x - (rect.x() + offset.0).round() as i32,
-                            offset_y: y - (rect.y() + offset.1).round() as i32,
-                            module: module.clone(),
-                        });
+                        self.logic_agent_bridge.send(DotEveryEditorAgentInputMessage::Select(CursorPosition::Module(module.id())));
                     }
+                } else if ImplType::VALUE != ProgramModuleComponentImplType::CanNotDrag {
+                    let delay = self.props.drag_threshold.delay;
+                    let delay_elapsed = !long_press && delay == Duration::from_millis(0);
+                    self.pointer_press = Some(PointerPress { pointer_id, x, y, last_x: x, last_y: y, long_press, delay_elapsed, mode });
+                    self.press_task = if long_press {
+                        Some(TimeoutService::spawn(LONG_PRESS_DURATION, self.link.callback(|_| ProgramModuleMessage::LongPress)))
+                    } else if !delay_elapsed {
+                        Some(TimeoutService::spawn(delay, self.link.callback(|_| ProgramModuleMessage::DragDelayElapsed)))
+                    } else {
+                        None
+                    };
                 }
-                self.drag_module_agent_bridge.send(DragModuleAgentInputMessage::UpdateMousePosition { x, y });
                 false
             }
-            Self::Message::NoDrag => {
-                self.drag_module_agent_bridge.send(DragModuleAgentInputMessage::EndDrag);
+            Self::Message::PointerMove { pointer_id, x, y } => {
+                if let Some(press) = &mut self.pointer_press {
+                    if press.pointer_id != pointer_id { return false; }
+                    press.last_x = x;
+                    press.last_y = y;
+                    let press = *press;
+                    if self.props.drag_threshold.is_exceeded(x - press.x, y - press.y) {
+                        if press.long_press {
+                            self.cancel_pointer_press();
+                        } else if press.delay_elapsed {
+                            self.start_drag(press, x, y);
+                        }
+                    }
+                }
+                false
+            }
+            Self::Message::PointerUp { pointer_id } => {
+                if let Some(press) = self.pointer_press {
+                    if press.pointer_id != pointer_id { return false; }
+                    self.cancel_pointer_press();
+                    if !self.props.drag_threshold.is_exceeded(press.last_x - press.x, press.last_y - press.y) {
+                        if let Either::Left(module) = &self.props.program_module {
+                            self.logic_agent_bridge.send(DotEveryEditorAgentInputMessage::Select(CursorPosition::Module(module.id())));
+                        }
+                    }
+                }
+                false
+            }
+            Self::Message::PointerCancel { pointer_id } => {
+                if self.pointer_press.map(|press| press.pointer_id == pointer_id).unwrap_or(false) {
+                    self.cancel_pointer_press();
+                }
+                false
+            }
+            Self::Message::LongPress => {
+                if let Some(press) = self.pointer_press {
+                    self.start_drag(press, press.x, press.y);
+                }
+                false
+            }
+            Self::Message::DragDelayElapsed => {
+                if let Some(press) = &mut self.pointer_press {
+                    press.delay_elapsed = true;
+                    let press = *press;
+                    self.press_task = None;
+                    if self.props.drag_threshold.is_exceeded(press.last_x - press.x, press.last_y - press.y) {
+                        self.start_drag(press, press.last_x, press.last_y);
+                    }
+                }
+                false
+            }
+            Self::Message::FocusInput(index) => {
+                if let Either::Left(module) = &self.props.program_module {
+                    self.logic_agent_bridge.send(DotEveryEditorAgentInputMessage::Select(CursorPosition::Input(module.id(), index)));
+                }
                 false
             }
             Self::Message::MoveHoveringModule { x, y, module_w, module_h } => {
@@ -178,7 +285,7 @@ impl<Controller, T, ImplType> Component for ProgramModuleComponent<Controller, T
                     self.hovering_module = Some((x, y, module_w, module_h));
                     let x = x as f64;
                     let y = y as f64;
-                    self.hovering_index = None;
+                    let last_index = self.hovering_index.take();
                     if let Some(element) = self.options_ref.cast::<Element>() {
                         let rect = element.get_bounding_client_rect();
                         let offset = get_page_offset();
@@ -202,7 +309,10 @@ impl<Controller, T, ImplType> Component for ProgramModuleComponent<Controller, T
                     // } else {
                     //     clog!("update hovering index", "None");
                     // }
-                    self.drag_module_agent_bridge.send(DragModuleAgentInputMessage::UpdateHoveringIndex(self.hovering_index.clone()));
+                    if self.hovering_index != last_index {
+                        self.drop_valid = true;
+                    }
+                    self.drag_module_agent_bridge.send(DragModuleAgentInputMessage::UpdateHoveringIndex(self.hovering_index));
 
                     self.link.send_message(Self::Message::UpdateSelfRect);
                     true
@@ -214,9 +324,15 @@ impl<Controller, T, ImplType> Component for ProgramModuleComponent<Controller, T
                 // clog!("leave");
                 self.hovering_module = None;
                 self.hovering_index = None;
+                self.drop_valid = true;
                 self.link.send_message(Self::Message::UpdateSelfRect);
                 true
             }
+            Self::Message::UpdateDropValidity { index, valid } => {
+                if self.hovering_index != Some(index) || self.drop_valid == valid { return false; }
+                self.drop_valid = valid;
+                true
+            }
             Self::Message::UpdateMousePosition { x, y } => {
                 self.element_x = x;
                 self.element_y = y;
@@ -228,7 +344,7 @@ impl<Controller, T, ImplType> Component for ProgramModuleComponent<Controller, T
                     if ImplType::VALUE != ProgramModuleComponentImplType::CanNotDrag {
                         let page_offset = get_page_offset();
                         if let Either::Left(module) = &self.props.program_module {
-                            self.props.rect_changed_callback.emit((module.id, Rect {
+                            self.props.rect_changed_callback.emit((module.id(), Rect {
                                 x: rect.x() + page_offset.0,
                                 y: rect.y() + page_offset.1,
                                 w: rect.width(),
@@ -247,11 +363,26 @@ impl<Controller, T, ImplType> Component for ProgramModuleComponent<Controller, T
             }
             Self::Message::UpdateInput { index, value } => {
                 if let Either::Left(module) = &self.props.program_module {
-                    self.logic_agent_bridge.send(DotEveryEditorAgentInputMessage::UpdateInput { id: module.id, index, value });
+                    self.logic_agent_bridge.send(DotEveryEditorAgentInputMessage::UpdateInput { id: module.id(), index, value });
+                }
+                false
+            }
+            Self::Message::ToggleCollapsed => {
+                if let Either::Left(module) = &self.props.program_module {
+                    self.logic_agent_bridge.send(DotEveryEditorAgentInputMessage::SetCollapsed { id: module.id(), collapsed: !module.collapsed });
+                }
+                false
+            }
+            Self::Message::OpenContextMenu { x, y } => {
+                if let Either::Left(module) = &self.props.program_module {
+                    self.logic_agent_bridge.send(DotEveryEditorAgentInputMessage::OpenContextMenu { id: module.id(), x, y });
                 }
                 false
             }
             Self::Message::UpdateChildRect { id, rect } => {
+                if let Either::Right(_) = &self.props.program_module {
+                    self.props.rect_changed_callback.emit((id, rect.clone()));
+                }
                 self.child_rects.insert(id, rect);
                 self.link.send_message(Self::Message::UpdateSelfRect);
                 false
@@ -265,15 +396,22 @@ impl<Controller, T, ImplType> Component for ProgramModuleComponent<Controller, T
     }
 
     fn change(&mut self, mut props: Self::Properties) -> bool {
-        if self.props.program_module == props.program_module { return false; }
+        if same_program_module(&self.props.program_module, &props.program_module) {
+            if self.props.cursor == props.cursor && self.props.drag_threshold == props.drag_threshold && Rc::ptr_eq(&self.props.messages, &props.messages) && self.props.read_only == props.read_only { return false; }
+            self.props.cursor = props.cursor;
+            self.props.drag_threshold = props.drag_threshold;
+            self.props.messages = props.messages;
+            self.props.read_only = props.read_only;
+            return true;
+        }
         if ImplType::VALUE != ProgramModuleComponentImplType::CanNotDrag {
             match &props.program_module {
                 Either::Left(module) => {
                     self.options_node_ref = (0..module.options.len()).map(|_| NodeRef::default()).collect();
-                    if let Some(id) = module.parent {
-                        self.drag_module_agent_bridge.send(DragModuleAgentInputMessage::SetParentId { my_id: module.id.clone(), parent_id: id.clone() });
+                    if let Some(id) = module.parent() {
+                        self.drag_module_agent_bridge.send(DragModuleAgentInputMessage::SetParentId { my_id: module.id(), parent_id: id.clone() });
                     } else {
-                        self.drag_module_agent_bridge.send(DragModuleAgentInputMessage::SetMyId(module.id.clone()));
+                        self.drag_module_agent_bridge.send(DragModuleAgentInputMessage::SetMyId(module.id()));
                     }
                 }
                 Either::Right(ProgramModuleDefault { parent, .. }) => {
@@ -282,38 +420,56 @@ impl<Controller, T, ImplType> Component for ProgramModuleComponent<Controller, T
                 }
             }
         }
+        let (shared_options, shared_child) = share_children(&props.program_module);
+        self.virtual_refs = (0..shared_child.len()).map(|_| NodeRef::default()).collect();
+        self.shared_options = shared_options;
+        self.shared_child = shared_child;
         self.props = props;
         true
     }
 
     fn view(&self) -> Html {
         let rect_changed_callback = self.link.callback(|(id, rect)| Self::Message::UpdateChildRect { id, rect });
-        let module_to_component = move |program_module: &ProgramModule<T>| {
+        let cursor = self.props.cursor.clone();
+        let drag_threshold = self.props.drag_threshold;
+        let messages = Rc::clone(&self.props.messages);
+        let read_only = self.props.read_only;
+        let module_to_component = move |program_module: &Arc<ProgramModule<T>>| {
             let props = ProgramModuleProperties {
-                program_module: Either::Left(program_module.clone()),
+                program_module: Either::Left(Arc::clone(program_module)),
                 rect_changed_callback: rect_changed_callback.clone(),
+                cursor: cursor.clone(),
+                drag_threshold,
+                messages: Rc::clone(&messages),
+                read_only,
             };
             html! { <ProgramModuleComponent<Controller,T, ImplType::Next> with props/> }
         };
-        let list_to_html = move |list: &Vec<ProgramModule<T>>, hovering: usize| {
+        let cursor_index = match self.props.cursor.position() {
+            Some(CursorPosition::Slot(id, DotEveryEditorOperationIndex::Child(i))) if id == self.module_id() => Some(i),
+            _ => None,
+        };
+        let drop_valid = self.drop_valid;
+        let virtual_refs = &self.virtual_refs;
+        let list_to_html = move |list: &[Arc<ProgramModule<T>>], virtual_heights: &[Option<f64>], hovering: usize| {
+            let placeholder = |i: usize| {
+                let mut class = "program_module_placeholder".to_string();
+                if i == hovering {
+                    class.push_str(" program_module_placeholder_hovered");
+                    if !drop_valid { class.push_str(" program_module_placeholder_invalid"); }
+                }
+                if Some(i) == cursor_index { class.push_str(" program_module_placeholder_cursor"); }
+                html! {<div class=class/>}
+            };
             let mut vec = Vec::new();
-
-            for i in 0..usize::min(hovering, list.len()) {
-                vec.push(html! {<div class="program_module_placeholder"/>});
-                vec.push(module_to_component(&list[i]));
-            }
-
-            if hovering > list.len() {
-                vec.push(html! {<div class="program_module_placeholder"/>});
-                return vec;
-            }
-
-            vec.push(html! {<div class="program_module_placeholder program_module_placeholder_hovered"/>});
-            for i in hovering..list.len() {
-                vec.push(module_to_component(&list[i]));
-                vec.push(html! {<div class="program_module_placeholder"/>});
+            for (i, module) in list.iter().enumerate() {
+                vec.push(placeholder(i));
+                match virtual_heights.get(i) {
+                    Some(Some(height)) => vec.push(html! {<div ref=virtual_refs[i].clone() class="program_module_virtual" style=format!("height:{}px;", height)/>}),
+                    _ => vec.push(module_to_component(module)),
+                }
             }
-
+            vec.push(placeholder(list.len()));
             vec
         };
         match &self.props.program_module {
@@ -321,12 +477,17 @@ impl<Controller, T, ImplType> Component for ProgramModuleComponent<Controller, T
                 // clog!("view left",format!("{:#?}", module.isomorphic_transform::<()>()));
                 let child = match &module.child {
                     ProgramModuleChildItems::None => { html! {} }
+                    _ if module.collapsed => {
+                        let count = module.module_count() - 1;
+                        let summary = self.props.messages.format("editor.collapsed_blocks", &[("count", &count.to_string())]);
+                        html! {<div class="program_module_collapsed">{summary}</div>}
+                    }
                     ProgramModuleChildItems::BlockVertical(list) => {
                         if ImplType::VALUE != ProgramModuleComponentImplType::ListOnly || list.len() > 0 {
                             let hovering = if let Some(DotEveryEditorOperationIndex::Child(i)) = self.hovering_index { i } else { list.len() + 1 };
                             html! {
                                 <div ref=self.child_ref.clone() class="program_module_child_vertical">
-                                    {for list_to_html(list, hovering)}
+                                    {for list_to_html(&self.shared_child, &[], hovering)}
                                 </div>
                             }
                         } else { html! {} }
@@ -336,55 +497,126 @@ impl<Controller, T, ImplType> Component for ProgramModuleComponent<Controller, T
                             let hovering = if let Some(DotEveryEditorOperationIndex::Child(i)) = self.hovering_index { i } else { list.len() + 1 };
                             html! {
                                 <div ref=self.child_ref.clone() class="program_module_child_horizontal">
-                                    {for list_to_html(list, hovering)}
+                                    {for list_to_html(&self.shared_child, &[], hovering)}
                                 </div>
                             }
                         } else { html! {} }
                     }
                 };
-                let mouse_move = self.link.callback(|e: MouseEvent| {
-                    if e.buttons() == 1 {
-                        Self::Message::Drag {
-                            mouse_x: e.page_x(),
-                            mouse_y: e.page_y(),
+                let pointer_down = self.link.callback(|e: PointerEvent| {
+                    if e.is_primary() && e.button() == 0 {
+                        e.stop_propagation();
+                        Self::Message::PointerDown {
+                            pointer_id: e.pointer_id(),
+                            x: e.page_x(),
+                            y: e.page_y(),
+                            long_press: e.pointer_type() != "mouse",
+                            mode: if e.ctrl_key() || e.meta_key() {
+                                DragMode::Duplicate
+                            } else if e.alt_key() {
+                                DragMode::MoveFollowing
+                            } else {
+                                DragMode::Move
+                            },
                         }
                     } else {
-                        Self::Message::NoDrag
+                        Self::Message::Ignore
                     }
                 });
+                let pointer_move = self.link.callback(|e: PointerEvent| Self::Message::PointerMove {
+                    pointer_id: e.pointer_id(),
+                    x: e.page_x(),
+                    y: e.page_y(),
+                });
+                let pointer_up = self.link.callback(|e: PointerEvent| Self::Message::PointerUp { pointer_id: e.pointer_id() });
+                let pointer_cancel = self.link.callback(|e: PointerEvent| Self::Message::PointerCancel { pointer_id: e.pointer_id() });
                 let options = module.options.iter().enumerate().map(
                     |(i, o)| match &o {
-                        ProgramModuleOption::StringSign(s) => Self::render_string_sign(self.options_node_ref[i].clone(), s.clone()),
+                        ProgramModuleOption::StringSign(s) => Self::render_string_sign(self.options_node_ref[i].clone(), self.props.messages.sign(module.type_data.sign_tag().as_deref(), i, s).to_string()),
                         ProgramModuleOption::StringInput(s) => Self::render_string_input(
                             self.options_node_ref[i].clone(),
-                            self.link.callback(Self::string_input_mousemove),
+                            self.props.cursor.position() == Some(CursorPosition::Input(module.id(), i)),
+                            self.props.read_only || module.lock.no_edit,
+                            self.link.callback(Self::string_input_pointerdown),
+                            self.link.callback(move |_| ProgramModuleMessage::FocusInput(i)),
                             self.link.callback(Self::string_input_change(i)),
                             s.clone()),
-                        ProgramModuleOption::ProgramModule(p) => self.render_program_module(i, p),
+                        ProgramModuleOption::ProgramModule(_) => self.render_program_module(i, &self.shared_options[i]),
                     });
-                let style = if module.child == ProgramModuleChildItems::None {
+                let mut style = if module.child == ProgramModuleChildItems::None {
                     "height: 100%;".to_string()
                 } else {
                     String::new()
                 };
+                if let Some(color) = module.type_data.color() {
+                    style.push_str(&format!("background-color:{};", color));
+                }
+                let mut options_class = "program_module_options ".to_string();
+                options_class.push_str(shape_class(module.type_data.shape()));
+                let icon = match module.type_data.icon() {
+                    Some(icon) => html! {<span class="program_module_icon">{icon}</span>},
+                    None => html! {},
+                };
+                let header = module.type_data.header(module).unwrap_or_default();
+                let collapse_toggle = if module.child != ProgramModuleChildItems::None && ImplType::VALUE == ProgramModuleComponentImplType::Default && !self.props.read_only {
+                    let pointer_down = self.link.callback(|e: PointerEvent| {
+                        e.stop_propagation();
+                        Self::Message::ToggleCollapsed
+                    });
+                    html! {<span class="program_module_collapse_toggle" onpointerdown=pointer_down>{if module.collapsed { "▸" } else { "▾" }}</span>}
+                } else {
+                    html! {}
+                };
+                let mut class = "program_module".to_string();
+                if let Some(custom) = module.type_data.class() {
+                    class.push(' ');
+                    class.push_str(&custom);
+                }
+                if self.props.cursor.is_on_module(module.id()) { class.push_str(" program_module_cursor"); }
+                if self.props.cursor.holding() == Some(module.id()) { class.push_str(" program_module_holding"); }
+                let lock = if module.lock.is_locked() {
+                    class.push_str(" program_module_locked");
+                    html! {<span class="program_module_lock" title=self.props.messages.get("editor.locked")>{"🔒"}</span>}
+                } else {
+                    html! {}
+                };
+                if module.disabled { class.push_str(" program_module_disabled"); }
+                let comment = match &module.comment {
+                    Some(comment) => html! {<span class="program_module_comment" title=comment.clone()>{comment}</span>},
+                    None => html! {},
+                };
+                let context_menu = if ImplType::VALUE == ProgramModuleComponentImplType::Default && !self.props.read_only {
+                    self.link.callback(|e: MouseEvent| {
+                        e.prevent_default();
+                        e.stop_propagation();
+                        Self::Message::OpenContextMenu { x: e.client_x(), y: e.client_y() }
+                    })
+                } else {
+                    self.link.callback(|_: MouseEvent| Self::Message::Ignore)
+                };
                 let html: Html = html! {
-                    <div ref=self.self_ref.clone() class="program_module">
+                    <div ref=self.self_ref.clone() class=class>
                         // {module.id}
-                        <div ref=self.options_ref.clone() style=style onmousemove=mouse_move class="program_module_options">
+                        {comment}
+                        <div ref=self.options_ref.clone() style=style onpointerdown=pointer_down onpointermove=pointer_move onpointerup=pointer_up onpointercancel=pointer_cancel oncontextmenu=context_menu class=options_class>
+                            {collapse_toggle}
+                            {icon}
+                            {header}
                             {for options}
+                            {lock}
                         </div>
                         {child}
                     </div>
                 };
                 html
             }
-            Either::Right(ProgramModuleDefault { list, .. }) => {
+            Either::Right(ProgramModuleDefault { list, virtual_heights, .. }) => {
                 // clog!("view right",format!("{:#?}",list.iter().map(ProgramModule::isomorphic_transform).collect::<Vec<ProgramModule<()>>>()));
                 let hovering = if let Some(DotEveryEditorOperationIndex::Child(i)) = self.hovering_index { i } else { list.len() + 1 };
                 // clog!(format!("hovering {:?}",hovering));
                 let child = html! {
                     <div ref=self.child_ref.clone() class="program_module_child_vertical">
-                        {for list_to_html(list, hovering)}
+                        {for list_to_html(list, virtual_heights, hovering)}
                     </div>
                 };
                 html! {
@@ -397,6 +629,15 @@ impl<Controller, T, ImplType> Component for ProgramModuleComponent<Controller, T
     }
 
     fn rendered(&mut self, _first_render: bool) {
+        if let (true, Some(CursorPosition::Input(id, i))) = (self.props.cursor.editing(), self.props.cursor.position()) {
+            if id == self.module_id() {
+                if let Some(input) = self.options_node_ref.get(i).and_then(NodeRef::cast::<HtmlElement>) {
+                    if let Err(err) = input.focus() {
+                        clog!(err);
+                    }
+                }
+            }
+        }
         self.link.send_message(Self::Message::UpdateSelfRect);
     }
 }
@@ -413,22 +654,59 @@ fn set_all_input_disabled(base: &Element, disabled: bool) {
 
 impl<Controller, T, ImplType> ProgramModuleComponent<Controller, T, ImplType>
     where Controller: 'static + DotEveryEditorController<T>,
-          T: 'static + Clone + PartialEq,
+          T: 'static + Clone + PartialEq + ProgramModuleRenderer,
           ImplType: 'static + ImplSplitter<ProgramModuleComponentImplType> {
     fn render_string_sign(node_ref: NodeRef, s: String) -> Html {
         html! {<span ref=node_ref class="program_module_option program_module_option_string_sign">{s}</span>}
     }
 
-    fn render_string_input(node_ref: NodeRef, onmousemove: Callback<MouseEvent>, onchange: Callback<ChangeData>, value: String) -> Html {
+    fn module_id(&self) -> Uuid {
+        match &self.props.program_module {
+            Either::Left(module) => module.id(),
+            Either::Right(_) => Uuid::nil(),
+        }
+    }
+
+    fn render_string_input(node_ref: NodeRef, cursor: bool, read_only: bool, onpointerdown: Callback<PointerEvent>, onfocus: Callback<FocusEvent>, onchange: Callback<ChangeData>, value: String) -> Html {
         let disabled = ImplType::VALUE != ProgramModuleComponentImplType::Default;
-        html! {<input ref=node_ref disabled=disabled onmousemove=onmousemove onchange=onchange class="program_module_option program_module_option_string_input" value=value/>}
+        let class = if cursor {
+            "program_module_option program_module_option_string_input program_module_option_string_input_cursor"
+        } else {
+            "program_module_option program_module_option_string_input"
+        };
+        html! {<input ref=node_ref disabled=disabled readonly=read_only onpointerdown=onpointerdown onfocus=onfocus onchange=onchange class=class value=value/>}
     }
 
-    fn string_input_mousemove(e: MouseEvent) -> ProgramModuleMessage {
-        if e.buttons() == 1 { e.stop_propagation(); }
+    fn string_input_pointerdown(e: PointerEvent) -> ProgramModuleMessage {
+        e.stop_propagation();
         ProgramModuleMessage::Ignore
     }
 
+    fn start_drag(&mut self, press: PointerPress, x: i32, y: i32) {
+        self.cancel_pointer_press();
+        if let (Either::Left(module), Some(self_element)) = (&self.props.program_module, self.self_ref.cast::<Element>()) {
+            let rect = self_element.get_bounding_client_rect();
+            let offset = get_page_offset();
+            self.drag_module_agent_bridge.send(DragModuleAgentInputMessage::TryStartDrag {
+                offset_x: press.x - (rect.x() + offset.0).round() as i32,
+                offset_y: press.y - (rect.y() + offset.1).round() as i32,
+                module: (**module).clone(),
+                mode: press.mode,
+            });
+            if let Some(element) = self.options_ref.cast::<Element>() {
+                if let Err(err) = element.set_pointer_capture(press.pointer_id) {
+                    clog!(err);
+                }
+            }
+        }
+        self.drag_module_agent_bridge.send(DragModuleAgentInputMessage::UpdateMousePosition { x, y });
+    }
+
+    fn cancel_pointer_press(&mut self) {
+        self.pointer_press = None;
+        self.press_task = None;
+    }
+
     fn string_input_change(i: usize) -> impl Fn(ChangeData) -> ProgramModuleMessage {
         move |e: ChangeData| {
             if let ChangeData::Value(s) = e {
@@ -439,12 +717,16 @@ impl<Controller, T, ImplType> ProgramModuleComponent<Controller, T, ImplType>
         }
     }
 
-    fn render_program_module(&self, i: usize, p: &Option<ProgramModule<T>>) -> Html {
+    fn render_program_module(&self, i: usize, p: &Option<Arc<ProgramModule<T>>>) -> Html {
         match p {
             Some(p) => {
                 let p = ProgramModuleProperties {
-                    program_module: Either::Left(p.clone()),
+                    program_module: Either::Left(Arc::clone(p)),
                     rect_changed_callback: self.link.callback(|_| ProgramModuleMessage::Ignore),
+                    cursor: self.props.cursor.clone(),
+                    drag_threshold: self.props.drag_threshold,
+                    messages: Rc::clone(&self.props.messages),
+                    read_only: self.props.read_only,
                 };
                 let html: Html = html! {
                     <div ref=self.options_node_ref[i].clone() class="program_module_option program_module_option_module">
@@ -454,14 +736,18 @@ impl<Controller, T, ImplType> ProgramModuleComponent<Controller, T, ImplType>
                 html
             }
             None => {
-                let placeholder = if self.is_hovering(self.options_node_ref[i].clone()) {
-                    html! {
-                        <div class="program_module_option_program_module_placeholder_hovered"/>
-                    }
+                let mut class = if self.hovering_index == Some(DotEveryEditorOperationIndex::OptionAbsolute(i)) {
+                    let mut class = "program_module_option_program_module_placeholder_hovered".to_string();
+                    if !self.drop_valid { class.push_str(" program_module_option_program_module_placeholder_invalid"); }
+                    class
                 } else {
-                    html! {
-                        <div class="program_module_option_program_module_placeholder"/>
-                    }
+                    "program_module_option_program_module_placeholder".to_string()
+                };
+                if self.props.cursor.position() == Some(CursorPosition::Slot(self.module_id(), DotEveryEditorOperationIndex::OptionAbsolute(i))) {
+                    class.push_str(" program_module_option_program_module_placeholder_cursor");
+                }
+                let placeholder = html! {
+                    <div class=class/>
                 };
                 let html: Html = html! {
                     <div ref=self.options_node_ref[i].clone() class="program_module_option program_module_option_module">
@@ -473,20 +759,6 @@ impl<Controller, T, ImplType> ProgramModuleComponent<Controller, T, ImplType>
         }
     }
 
-    fn is_hovering(&self, node_ref: NodeRef) -> bool {
-        if let Some((x, y, _w, _h)) = self.hovering_module {
-            if let Some(element) = node_ref.cast::<Element>() {
-                let rect = element.get_bounding_client_rect();
-                let offset = get_page_offset();
-                if rect.x() + offset.0 <= (x as f64) && (x as f64) <= rect.x() + offset.0 + rect.width() &&
-                    rect.y() + offset.1 <= (y as f64) && (y as f64) <= rect.y() + offset.1 + rect.height() {
-                    return true;
-                }
-            }
-        }
-        false
-    }
-
     fn get_options_hovering_index(&self, x: f64, y: f64) -> Option<DotEveryEditorOperationIndex> {
         if let Either::Left(module) = &self.props.program_module {
             for (i, (option, node_ref)) in module.options.iter().zip(&self.options_node_ref).enumerate() {
@@ -508,35 +780,67 @@ impl<Controller, T, ImplType> ProgramModuleComponent<Controller, T, ImplType>
     }
 
     fn get_child_hovering_index(&self, x: f64, y: f64) -> Option<DotEveryEditorOperationIndex> {
-        match &self.props.program_module {
-            Either::Left(ProgramModule { child: ProgramModuleChildItems::None, .. }) => {
-                None
-            }
-            Either::Left(ProgramModule { child: ProgramModuleChildItems::BlockVertical(list), .. }) | Either::Right(ProgramModuleDefault { list, .. }) => {
-                for (i, module) in list.iter().enumerate() {
-                    if let Some(rect) = self.child_rects.get(&module.id) {
-                        if rect.center().1 > y {
-                            return Some(DotEveryEditorOperationIndex::Child(i));
-                        }
-                    } else {
-                        return None;
-                    }
+        let horizontal = match &self.props.program_module {
+            Either::Left(module) => match &module.child {
+                ProgramModuleChildItems::None => return None,
+                _ if module.collapsed => return None,
+                ProgramModuleChildItems::BlockVertical(_) => false,
+                ProgramModuleChildItems::BlockHorizontal(_) => true,
+            },
+            Either::Right(_) => false,
+        };
+        for (i, module) in self.shared_child.iter().enumerate() {
+            // a virtualized module is measured through its placeholder
+            let virtualized = match &self.props.program_module {
+                Either::Right(ProgramModuleDefault { virtual_heights, .. }) => virtual_heights.get(i).map(Option::is_some).unwrap_or(false),
+                Either::Left(_) => false,
+            };
+            let placeholder = if virtualized { self.virtual_refs[i].cast::<Element>() } else { None };
+            let rect = match placeholder {
+                Some(element) => {
+                    let rect = element.get_bounding_client_rect();
+                    let offset = get_page_offset();
+                    Some(Rect { x: rect.x() + offset.0, y: rect.y() + offset.1, w: rect.width(), h: rect.height() })
                 }
-                Some(DotEveryEditorOperationIndex::Child(list.len()))
-            }
-            Either::Left(ProgramModule { child: ProgramModuleChildItems::BlockHorizontal(list), .. }) => {
-                for (i, module) in list.iter().enumerate() {
-                    if let Some(rect) = self.child_rects.get(&module.id) {
-                        if rect.center().0 > x {
-                            return Some(DotEveryEditorOperationIndex::Child(i));
-                        }
-                    } else {
-                        return None;
-                    }
+                None => self.child_rects.get(&module.id()).cloned(),
+            };
+            if let Some(rect) = rect {
+                let (center_x, center_y) = rect.center();
+                if (horizontal && center_x > x) || (!horizontal && center_y > y) {
+                    return Some(DotEveryEditorOperationIndex::Child(i));
                 }
-                Some(DotEveryEditorOperationIndex::Child(list.len()))
+            } else {
+                return None;
             }
         }
+        Some(DotEveryEditorOperationIndex::Child(self.shared_child.len()))
+    }
+}
+
+type SharedChildren<T> = (Vec<Option<Arc<ProgramModule<T>>>>, Vec<Arc<ProgramModule<T>>>);
+
+fn share_children<T: 'static + Clone + PartialEq>(program_module: &Either<Arc<ProgramModule<T>>, ProgramModuleDefault<T>>) -> SharedChildren<T> {
+    match program_module {
+        Either::Left(module) => {
+            let options = module.options.iter().map(|option| match option {
+                ProgramModuleOption::ProgramModule(Some(module)) => Some(Arc::clone(module)),
+                _ => None,
+            }).collect();
+            let child = match &module.child {
+                ProgramModuleChildItems::None => Vec::new(),
+                ProgramModuleChildItems::BlockVertical(list) | ProgramModuleChildItems::BlockHorizontal(list) => list.clone(),
+            };
+            (options, child)
+        }
+        Either::Right(ProgramModuleDefault { list, .. }) => (Vec::new(), list.clone()),
+    }
+}
+
+fn same_program_module<T: 'static + Clone + PartialEq>(a: &Either<Arc<ProgramModule<T>>, ProgramModuleDefault<T>>, b: &Either<Arc<ProgramModule<T>>, ProgramModuleDefault<T>>) -> bool {
+    match (a, b) {
+        (Either::Left(a), Either::Left(b)) => Arc::ptr_eq(a, b) || a == b,
+        (Either::Right(a), Either::Right(b)) => a.parent == b.parent && a.list.len() == b.list.len() && a.list.iter().zip(&b.list).all(|(a, b)| Arc::ptr_eq(a, b) || a == b),
+        _ => false,
     }
 }
 
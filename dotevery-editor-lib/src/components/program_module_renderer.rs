@@ -0,0 +1,40 @@
+use yew::Html;
+
+use crate::logic::program_module::ProgramModule;
+pub use crate::logic::svg_export::{ModuleStyle, ProgramModuleShape};
+
+pub(crate) fn shape_class(shape: ProgramModuleShape) -> &'static str {
+    match shape {
+        ProgramModuleShape::Block => "program_module_shape_block",
+        ProgramModuleShape::Rounded => "program_module_shape_rounded",
+        ProgramModuleShape::Hexagonal => "program_module_shape_hexagonal",
+    }
+}
+
+// the color and the shape come from ModuleStyle, so that exported images look like the editor
+pub trait ProgramModuleRenderer: ModuleStyle {
+    fn class(&self) -> Option<String> {
+        None
+    }
+
+    fn icon(&self) -> Option<String> {
+        None
+    }
+
+    // shown next to the signs in the outline panel
+    fn label(&self) -> Option<String> {
+        None
+    }
+
+    // the type tag the sign translations of the message catalog are registered under
+    fn sign_tag(&self) -> Option<String> {
+        None
+    }
+
+    // rendered in front of the options, the options and their drop slots are rendered as usual
+    fn header(&self, _module: &ProgramModule<Self>) -> Option<Html> {
+        None
+    }
+}
+
+impl ProgramModuleRenderer for () {}
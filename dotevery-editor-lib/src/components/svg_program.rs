@@ -0,0 +1,401 @@
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::time::Duration;
+
+use uuid::Uuid;
+use web_sys::{Element, window};
+use yew::{Component, ComponentLink, Html};
+use yew::prelude::*;
+use yew::services::TimeoutService;
+use yew::services::timeout::TimeoutTask;
+
+use crate::components::dotevery_editor::DragThreshold;
+use crate::components::dotevery_editor_agent::{DotEveryEditorAgent, DotEveryEditorAgentInputMessage};
+use crate::components::drag_module_agent::{DragMode, DragModuleAgent, DragModuleAgentInputMessage, DragModuleAgentOutputMessage};
+use crate::components::program_module::get_page_offset;
+use crate::components::program_module_renderer::{ProgramModuleRenderer, ProgramModuleShape};
+use crate::logic::canvas_text_metrics::CanvasTextMetrics;
+use crate::logic::cursor::CursorPosition;
+use crate::logic::dotevery_editor::{DotEveryEditor, DotEveryEditorOperationIndex};
+use crate::logic::dotevery_editor_controller::DotEveryEditorController;
+use crate::logic::i18n::MessageCatalog;
+use crate::logic::layout::{block_path, hexagon_path, rounded_path, EditorLayout, LayoutMetrics, ModuleLayout, OptionLayout};
+use crate::logic::program_module::{ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
+use crate::util::Rect;
+
+// text is measured with this font, so it is set on the svg element itself instead of the stylesheet
+pub(crate) const SVG_FONT: &str = "14px sans-serif";
+
+#[derive(Clone, Copy, PartialEq)]
+struct SvgPointerPress {
+    id: Uuid,
+    pointer_id: i32,
+    x: i32,
+    y: i32,
+    last_x: i32,
+    last_y: i32,
+    delay_elapsed: bool,
+    mode: DragMode,
+}
+
+#[derive(Clone, Properties, PartialEq)]
+pub(crate) struct SvgProgramProperties<T: 'static + Clone + PartialEq> {
+    pub(crate) editor: DotEveryEditor<T>,
+    pub(crate) drag_threshold: DragThreshold,
+    // top level modules outside of this area are not drawn, their layout is still used for dropping
+    pub(crate) visible_area: Option<Rect>,
+    pub(crate) messages: Rc<MessageCatalog>,
+    pub(crate) read_only: bool,
+}
+
+pub(crate) struct SvgProgramComponent<Controller, Type>
+    where Controller: 'static + DotEveryEditorController<Type>,
+          Type: 'static + Clone + PartialEq + ProgramModuleRenderer {
+    link: ComponentLink<Self>,
+    props: SvgProgramProperties<Type>,
+    metrics: LayoutMetrics,
+    text_metrics: CanvasTextMetrics,
+    // signs translated for display, drags and edits use props.editor
+    localized: DotEveryEditor<Type>,
+    layout: EditorLayout,
+    layout_sent: Option<(f64, f64)>,
+    svg_ref: NodeRef,
+    drag_module_agent_bridge: Box<dyn Bridge<DragModuleAgent<Controller, Type>>>,
+    logic_agent_bridge: Box<dyn Bridge<DotEveryEditorAgent<Controller, Type>>>,
+    hovering: Option<(Uuid, DotEveryEditorOperationIndex)>,
+    drop_valid: bool,
+    pointer_press: Option<SvgPointerPress>,
+    press_task: Option<TimeoutTask>,
+    phantom_data: PhantomData<Controller>,
+}
+
+pub(crate) enum SvgProgramMessage {
+    Ignore,
+    PointerDown { id: Uuid, pointer_id: i32, x: i32, y: i32, mode: DragMode },
+    PointerMove { pointer_id: i32, x: i32, y: i32 },
+    PointerUp { pointer_id: i32 },
+    PointerCancel { pointer_id: i32 },
+    DragDelayElapsed,
+    EditInput { id: Uuid, index: usize },
+    OpenContextMenu { id: Uuid, x: i32, y: i32 },
+    UpdateLayoutHovering(Option<(Uuid, DotEveryEditorOperationIndex)>),
+    UpdateDropValidity { index: DotEveryEditorOperationIndex, valid: bool },
+    SendLayout,
+}
+
+impl<Controller, T> Component for SvgProgramComponent<Controller, T>
+    where Controller: 'static + DotEveryEditorController<T>,
+          T: 'static + Clone + PartialEq + ProgramModuleRenderer {
+    type Message = SvgProgramMessage;
+    type Properties = SvgProgramProperties<T>;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let callback = link.callback(|out: DragModuleAgentOutputMessage<T>| match out {
+            DragModuleAgentOutputMessage::UpdateLayoutHovering(hovering) => Self::Message::UpdateLayoutHovering(hovering),
+            DragModuleAgentOutputMessage::UpdateDropValidity { index, valid } => Self::Message::UpdateDropValidity { index, valid },
+            DragModuleAgentOutputMessage::RequestUpdateRect => Self::Message::SendLayout,
+            _ => Self::Message::Ignore,
+        });
+        let drag_module_agent_bridge = DragModuleAgent::bridge(callback);
+        let logic_agent_bridge = DotEveryEditorAgent::bridge(link.callback(|_| Self::Message::Ignore));
+        let metrics = LayoutMetrics::default();
+        let text_metrics = CanvasTextMetrics::new(SVG_FONT, &metrics);
+        let localized = props.messages.localize_editor(&props.editor, &T::sign_tag);
+        let layout = EditorLayout::with_text_metrics(&localized, &metrics, &text_metrics);
+        Self {
+            link,
+            props,
+            metrics,
+            text_metrics,
+            localized,
+            layout,
+            layout_sent: None,
+            svg_ref: NodeRef::default(),
+            drag_module_agent_bridge,
+            logic_agent_bridge,
+            hovering: None,
+            drop_valid: true,
+            pointer_press: None,
+            press_task: None,
+            phantom_data: PhantomData,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> bool {
+        match msg {
+            Self::Message::Ignore => false,
+            Self::Message::PointerDown { id, pointer_id, x, y, mode } => {
+                if self.props.read_only {
+                    self.logic_agent_bridge.send(DotEveryEditorAgentInputMessage::Select(CursorPosition::Module(id)));
+                    return false;
+                }
+                let delay = self.props.drag_threshold.delay;
+                let delay_elapsed = delay == Duration::from_millis(0);
+                self.pointer_press = Some(SvgPointerPress { id, pointer_id, x, y, last_x: x, last_y: y, delay_elapsed, mode });
+                self.press_task = if delay_elapsed {
+                    None
+                } else {
+                    Some(TimeoutService::spawn(delay, self.link.callback(|_| SvgProgramMessage::DragDelayElapsed)))
+                };
+                false
+            }
+            Self::Message::PointerMove { pointer_id, x, y } => {
+                if let Some(press) = &mut self.pointer_press {
+                    if press.pointer_id != pointer_id { return false; }
+                    press.last_x = x;
+                    press.last_y = y;
+                    let press = *press;
+                    if press.delay_elapsed && self.props.drag_threshold.is_exceeded(x - press.x, y - press.y) {
+                        self.start_drag(press);
+                    }
+                }
+                false
+            }
+            Self::Message::PointerUp { pointer_id } => {
+                if let Some(press) = self.pointer_press {
+                    if press.pointer_id != pointer_id { return false; }
+                    self.cancel_pointer_press();
+                    if !self.props.drag_threshold.is_exceeded(press.last_x - press.x, press.last_y - press.y) {
+                        self.logic_agent_bridge.send(DotEveryEditorAgentInputMessage::Select(CursorPosition::Module(press.id)));
+                    }
+                }
+                false
+            }
+            Self::Message::PointerCancel { pointer_id } => {
+                if self.pointer_press.map(|press| press.pointer_id == pointer_id).unwrap_or(false) {
+                    self.cancel_pointer_press();
+                }
+                false
+            }
+            Self::Message::DragDelayElapsed => {
+                if let Some(press) = &mut self.pointer_press {
+                    press.delay_elapsed = true;
+                    let press = *press;
+                    self.press_task = None;
+                    if self.props.drag_threshold.is_exceeded(press.last_x - press.x, press.last_y - press.y) {
+                        self.start_drag(press);
+                    }
+                }
+                false
+            }
+            Self::Message::EditInput { id, index } => {
+                let locked = self.props.editor.get_module(id).map(|module| module.lock.no_edit).unwrap_or(false);
+                if self.props.read_only || locked {
+                    self.logic_agent_bridge.send(DotEveryEditorAgentInputMessage::Select(CursorPosition::Input(id, index)));
+                    return false;
+                }
+                let value = match self.props.editor.get_module(id).ok().and_then(|module| module.options.get(index)) {
+                    Some(ProgramModuleOption::StringInput(value)) => value.clone(),
+                    _ => return false,
+                };
+                if let Some(window) = window() {
+                    if let Ok(Some(value)) = window.prompt_with_message_and_default("", &value) {
+                        self.logic_agent_bridge.send(DotEveryEditorAgentInputMessage::UpdateInput { id, index, value });
+                    }
+                }
+                false
+            }
+            Self::Message::OpenContextMenu { id, x, y } => {
+                self.logic_agent_bridge.send(DotEveryEditorAgentInputMessage::OpenContextMenu { id, x, y });
+                false
+            }
+            Self::Message::UpdateLayoutHovering(hovering) => {
+                if self.hovering == hovering { return false; }
+                self.hovering = hovering;
+                self.drop_valid = true;
+                true
+            }
+            Self::Message::UpdateDropValidity { index, valid } => {
+                if self.hovering.map(|(_, hovering)| hovering) != Some(index) || self.drop_valid == valid { return false; }
+                self.drop_valid = valid;
+                true
+            }
+            Self::Message::SendLayout => {
+                self.layout_sent = None;
+                self.send_layout();
+                false
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> bool {
+        if self.props == props { return false; }
+        if self.props.editor != props.editor || self.props.messages != props.messages {
+            self.localized = props.messages.localize_editor(&props.editor, &T::sign_tag);
+            self.layout = EditorLayout::with_text_metrics(&self.localized, &self.metrics, &self.text_metrics);
+            self.layout_sent = None;
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html {
+        let on_pointer_down = |id: Uuid| self.link.callback(move |e: PointerEvent| {
+            if e.is_primary() && e.button() == 0 {
+                e.stop_propagation();
+                SvgProgramMessage::PointerDown {
+                    id,
+                    pointer_id: e.pointer_id(),
+                    x: e.page_x(),
+                    y: e.page_y(),
+                    mode: if e.ctrl_key() || e.meta_key() {
+                        DragMode::Duplicate
+                    } else if e.alt_key() {
+                        DragMode::MoveFollowing
+                    } else {
+                        DragMode::Move
+                    },
+                }
+            } else {
+                SvgProgramMessage::Ignore
+            }
+        });
+        let on_input = |id: Uuid, index: usize| self.link.callback(move |e: PointerEvent| {
+            e.stop_propagation();
+            SvgProgramMessage::EditInput { id, index }
+        });
+        let read_only = self.props.read_only;
+        let on_context_menu = |id: Uuid| self.link.callback(move |e: MouseEvent| {
+            if read_only { return SvgProgramMessage::Ignore; }
+            e.prevent_default();
+            e.stop_propagation();
+            SvgProgramMessage::OpenContextMenu { id, x: e.client_x(), y: e.client_y() }
+        });
+        let visible_area = self.props.visible_area.as_ref();
+        let modules = self.layout.list.modules.iter().zip(&self.localized.list)
+            .filter(|(layout, _)| visible_area.map(|area| area.intersects(&layout.rect)).unwrap_or(true))
+            .map(|(layout, module)| view_svg_module(layout, module, &self.metrics, &on_pointer_down, &on_input, &on_context_menu));
+        let marker = match self.hovering.and_then(|(target, index)| self.layout.insertion_marker(target, index)) {
+            Some(rect) => {
+                let class = if self.drop_valid { "svg_program_drop_marker" } else { "svg_program_drop_marker svg_program_drop_marker_invalid" };
+                html! {<rect class=class x=rect.x y=rect.y width=rect.w height=rect.h rx=2/>}
+            }
+            None => html! {},
+        };
+        let pointer_move = self.link.callback(|e: PointerEvent| SvgProgramMessage::PointerMove { pointer_id: e.pointer_id(), x: e.page_x(), y: e.page_y() });
+        let pointer_up = self.link.callback(|e: PointerEvent| SvgProgramMessage::PointerUp { pointer_id: e.pointer_id() });
+        let pointer_cancel = self.link.callback(|e: PointerEvent| SvgProgramMessage::PointerCancel { pointer_id: e.pointer_id() });
+        let style = format!("font:{};", SVG_FONT);
+        html! {
+            <svg ref=self.svg_ref.clone() class="svg_program" style=style width=self.layout.bounds.w height=self.layout.bounds.h onpointermove=pointer_move onpointerup=pointer_up onpointercancel=pointer_cancel>
+                {for modules}
+                {marker}
+            </svg>
+        }
+    }
+
+    fn rendered(&mut self, _first_render: bool) {
+        self.send_layout();
+    }
+}
+
+impl<Controller, T> SvgProgramComponent<Controller, T>
+    where Controller: 'static + DotEveryEditorController<T>,
+          T: 'static + Clone + PartialEq + ProgramModuleRenderer {
+    fn send_layout(&mut self) {
+        if let Some(element) = self.svg_ref.cast::<Element>() {
+            let rect = element.get_bounding_client_rect();
+            let offset = get_page_offset();
+            let position = (rect.x() + offset.0, rect.y() + offset.1);
+            if self.layout_sent != Some(position) {
+                self.layout_sent = Some(position);
+                self.drag_module_agent_bridge.send(DragModuleAgentInputMessage::SetLayout { layout: self.layout.clone(), x: position.0, y: position.1 });
+            }
+        }
+    }
+
+    fn start_drag(&mut self, press: SvgPointerPress) {
+        self.cancel_pointer_press();
+        let (module, rect) = match (self.props.editor.get_module(press.id), self.layout.get(press.id), self.layout_sent) {
+            (Ok(module), Some(layout), Some(_)) => (module.clone(), layout.rect.clone()),
+            _ => return,
+        };
+        if let Some(element) = self.svg_ref.cast::<Element>() {
+            let bounding = element.get_bounding_client_rect();
+            let offset = get_page_offset();
+            let scale = if self.layout.bounds.w > 0f64 { bounding.width() / self.layout.bounds.w } else { 1f64 };
+            self.drag_module_agent_bridge.send(DragModuleAgentInputMessage::TryStartDrag {
+                offset_x: press.x - (bounding.x() + offset.0 + rect.x * scale).round() as i32,
+                offset_y: press.y - (bounding.y() + offset.1 + rect.y * scale).round() as i32,
+                module,
+                mode: press.mode,
+            });
+            if let Err(err) = element.set_pointer_capture(press.pointer_id) {
+                clog!(err);
+            }
+        }
+        self.drag_module_agent_bridge.send(DragModuleAgentInputMessage::UpdateMousePosition { x: press.last_x, y: press.last_y });
+    }
+
+    fn cancel_pointer_press(&mut self) {
+        self.pointer_press = None;
+        self.press_task = None;
+    }
+}
+
+pub(crate) fn view_svg_module<T: 'static + Clone + PartialEq + ProgramModuleRenderer>(
+    layout: &ModuleLayout,
+    module: &ProgramModule<T>,
+    metrics: &LayoutMetrics,
+    on_pointer_down: &dyn Fn(Uuid) -> Callback<PointerEvent>,
+    on_input: &dyn Fn(Uuid, usize) -> Callback<PointerEvent>,
+    on_context_menu: &dyn Fn(Uuid) -> Callback<MouseEvent>,
+) -> Html {
+    let path = if layout.nested {
+        match module.type_data.shape() {
+            ProgramModuleShape::Hexagonal => hexagon_path(&layout.rect),
+            _ => rounded_path(&layout.rect),
+        }
+    } else {
+        block_path(layout, metrics)
+    };
+    let style = module.type_data.color().map(|color| format!("fill:{};", color)).unwrap_or_default();
+    let mut class = "svg_program_module".to_string();
+    if let Some(custom) = module.type_data.class() {
+        class.push(' ');
+        class.push_str(&custom);
+    }
+    if module.disabled { class.push_str(" svg_program_module_disabled"); }
+    let (title, comment) = match (&module.comment, &layout.comment) {
+        (Some(comment), Some(rect)) => (
+            html! {<title>{comment}</title>},
+            html! {<text class="svg_program_module_comment" x=rect.x y=rect.y + rect.h / 2f64>{comment}</text>},
+        ),
+        _ => (html! {}, html! {}),
+    };
+    let options = layout.options.iter().zip(&module.options).enumerate().map(|(i, (option_layout, option))| match (option_layout, option) {
+        (OptionLayout::Sign(rect), ProgramModuleOption::StringSign(s)) => html! {
+            <text class="svg_program_module_sign" x=rect.x y=rect.y + rect.h / 2f64>{s}</text>
+        },
+        (OptionLayout::Input(rect), ProgramModuleOption::StringInput(s)) => html! {
+            <g class="svg_program_module_input" onpointerdown=on_input(module.id(), i)>
+                <rect x=rect.x y=rect.y width=rect.w height=rect.h rx=4/>
+                <text x=rect.x + metrics.gap y=rect.y + rect.h / 2f64>{s}</text>
+            </g>
+        },
+        (OptionLayout::Module(nested), ProgramModuleOption::ProgramModule(Some(nested_module))) => view_svg_module(nested, nested_module, metrics, on_pointer_down, on_input, on_context_menu),
+        (option_layout, _) => html! {
+            <path class="svg_program_module_slot" d=rounded_path(option_layout.rect())/>
+        },
+    });
+    let children = match (&layout.child, &module.child) {
+        (Some(child), ProgramModuleChildItems::BlockVertical(list)) | (Some(child), ProgramModuleChildItems::BlockHorizontal(list)) => child.modules.iter().zip(list)
+            .map(|(layout, module)| view_svg_module(layout, module, metrics, on_pointer_down, on_input, on_context_menu))
+            .collect::<Html>(),
+        _ => html! {},
+    };
+    let lock = match &layout.lock {
+        Some(rect) => html! {<text class="svg_program_module_lock" x=rect.x y=rect.y + rect.h / 2f64>{"🔒"}</text>},
+        None => html! {},
+    };
+    html! {
+        <g class=class onpointerdown=on_pointer_down(module.id()) oncontextmenu=on_context_menu(module.id())>
+            {title}
+            <path class="svg_program_module_shape" style=style d=path/>
+            {comment}
+            {for options}
+            {lock}
+            {children}
+        </g>
+    }
+}
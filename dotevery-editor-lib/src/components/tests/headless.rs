@@ -0,0 +1,652 @@
+use std::sync::{Arc, RwLock};
+
+use uuid::Uuid;
+use yew::agent::HandlerId;
+
+use crate::components::DotEveryBridge;
+use crate::components::dotevery_editor_agent::{DotEveryEditorAgentInputMessage, DotEveryEditorAgentOutputMessage};
+use crate::components::drag_module_agent::{DragMode, DragModuleAgentInputMessage, DragModuleAgentOutputMessage};
+use crate::components::headless::{HeadlessEditor, HeadlessModuleEvent};
+use crate::components::tests::{block, module, rect};
+use crate::logic::cursor::{CursorKey, CursorPosition};
+use crate::logic::dotevery_editor::{DotEveryEditor, DotEveryEditorOperationIndex};
+use crate::logic::dotevery_editor_controller::{ContextMenuItem, DotEveryEditorCommand, DotEveryEditorController, DotEveryEditorOperation};
+use crate::logic::palette::Palette;
+use crate::logic::program_module::{ModuleLock, ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
+use crate::logic::viewport::ViewTransform;
+use crate::util::Isomorphism;
+
+struct TestController {
+    bridge: DotEveryBridge<Self, i32>,
+    data: Arc<RwLock<DotEveryEditor<i32>>>,
+    palette: Arc<RwLock<Palette<i32>>>,
+    selected: Vec<CursorPosition>,
+}
+
+enum TestControllerInput {
+    RequestCount,
+    RequestSelectedCount,
+    Clear,
+    SetPalette(Palette<i32>),
+}
+
+impl DotEveryEditorController<i32> for TestController {
+    type Input = TestControllerInput;
+    type Output = usize;
+
+    fn create(data: Arc<RwLock<DotEveryEditor<i32>>>, palette: Arc<RwLock<Palette<i32>>>, bridge: DotEveryBridge<Self, i32>) -> Self {
+        Self { bridge, data, palette, selected: Vec::new() }
+    }
+
+    fn update(&mut self) {}
+
+    fn handle_input(&mut self, msg: Self::Input, id: HandlerId) {
+        match msg {
+            TestControllerInput::RequestCount => self.bridge.respond(id, self.data.read().unwrap().list.len()),
+            TestControllerInput::RequestSelectedCount => self.bridge.respond(id, self.selected.len()),
+            TestControllerInput::Clear => {
+                self.data.write().unwrap().list.clear();
+                self.bridge.notify_update(DotEveryEditorCommand::Update);
+            }
+            TestControllerInput::SetPalette(palette) => {
+                *self.palette.write().unwrap() = palette;
+                self.bridge.notify_update(DotEveryEditorCommand::UpdatePalette);
+            }
+        }
+    }
+
+    fn can_add(&self, target: Uuid, _index: DotEveryEditorOperationIndex, module: &ProgramModule<i32>) -> bool {
+        target.is_nil() || module.type_data != 9
+    }
+
+    fn select(&mut self, position: CursorPosition) {
+        self.selected.push(position);
+    }
+
+    fn context_menu(&self, module: &ProgramModule<i32>) -> Vec<ContextMenuItem> {
+        if module.type_data == 0 { vec![ContextMenuItem::new("append", "Append")] } else { Vec::new() }
+    }
+
+    // the first operation is refused by can_add
+    fn context_action(&mut self, module: Uuid, action: &str) -> Vec<DotEveryEditorOperation<i32>> {
+        match action {
+            "append" => vec![
+                DotEveryEditorOperation::Add(module, DotEveryEditorOperationIndex::Child(0), block("x", 9)),
+                DotEveryEditorOperation::Add(Uuid::nil(), DotEveryEditorOperationIndex::Child(0), block("y", 3)),
+            ],
+            _ => Vec::new(),
+        }
+    }
+
+    fn help(&self, module: &ProgramModule<i32>) -> Option<String> {
+        Some(format!("help.{}", module.type_data))
+    }
+}
+
+fn root_types(editor: &DotEveryEditor<i32>) -> Vec<i32> {
+    editor.list.iter().map(|module| module.type_data).collect()
+}
+
+fn child_types(module: &ProgramModule<i32>) -> Vec<i32> {
+    match &module.child {
+        ProgramModuleChildItems::BlockVertical(list) | ProgramModuleChildItems::BlockHorizontal(list) => list.iter().map(|module| module.type_data).collect(),
+        ProgramModuleChildItems::None => Vec::new(),
+    }
+}
+
+fn layout(editor: &mut HeadlessEditor<TestController, i32>) {
+    editor.set_rect(Uuid::nil(), rect(0.0, 0.0, 400.0, 400.0));
+    let ids = editor.editor().list.iter().map(ProgramModule::id).collect::<Vec<_>>();
+    for (i, id) in ids.into_iter().enumerate() {
+        editor.set_rect(id, rect(10.0, 10.0 + 50.0 * i as f64, 200.0, 40.0));
+    }
+}
+
+#[test]
+fn headless_editor_add_and_remove_test() {
+    let mut editor = HeadlessEditor::<TestController, i32>::new();
+    let outputs = editor.take_logic_outputs();
+    assert_eq!(outputs.len(), 2);
+    assert!(matches!(outputs[0], DotEveryEditorAgentOutputMessage::ModuleUpdated(_)));
+    assert!(matches!(outputs[1], DotEveryEditorAgentOutputMessage::PaletteUpdated(_)));
+
+    editor.set_root(vec![module("a", 0), module("b", 1)]);
+    assert_eq!(root_types(&editor.editor()), vec![0, 1]);
+    assert_eq!(editor.take_logic_outputs().len(), 2);
+
+    let a = editor.editor().list[0].id();
+    let b = editor.editor().list[1].id();
+    editor.update_input(a, 1, "value");
+    assert_eq!(editor.take_logic_outputs().len(), 1);
+    assert_eq!(editor.module(a).unwrap().options[1], ProgramModuleOption::StringInput("value".to_string()));
+    editor.update_input(a, 0, "sign");
+    assert!(editor.take_logic_outputs().is_empty());
+
+    editor.remove(b);
+    assert_eq!(root_types(&editor.editor()), vec![0]);
+
+    editor.remove(b);
+    assert_eq!(root_types(&editor.editor()), vec![0]);
+    assert_eq!(editor.take_logic_outputs().len(), 1);
+}
+
+#[test]
+fn headless_editor_drag_into_child_list_test() {
+    let mut editor = HeadlessEditor::<TestController, i32>::new();
+    editor.set_root(vec![module("a", 0), block("b", 1)]);
+    let a = editor.editor().list[0].id();
+    let b = editor.editor().list[1].id();
+    for (i, type_data) in [2, 3].iter().enumerate() {
+        editor.add(b, DotEveryEditorOperationIndex::Child(i), module("c", *type_data));
+    }
+    layout(&mut editor);
+
+    editor.drag_and_drop(a, 50, 70, DotEveryEditorOperationIndex::Child(2));
+
+    let tree = editor.editor();
+    assert_eq!(root_types(&tree), vec![1]);
+    assert_eq!(child_types(&tree.list[0]), vec![2, 3, 0]);
+    assert_eq!(tree.list[0].id(), b);
+    assert!(tree.get_module(a).is_err());
+    assert!(tree.list[0].isomorphisms(&{
+        let mut expected = block("b", 1);
+        for (i, type_data) in [2, 3, 0].iter().enumerate() {
+            expected.add(expected.id(), DotEveryEditorOperationIndex::Child(i), &module("c", *type_data)).unwrap();
+        }
+        expected
+    }));
+    assert_eq!(editor.hovering_module(), None);
+}
+
+#[test]
+fn headless_editor_hovering_test() {
+    let mut editor = HeadlessEditor::<TestController, i32>::new();
+    editor.set_root(vec![module("a", 0), block("b", 1), block("c", 2)]);
+    let a = editor.editor().list[0].id();
+    let b = editor.editor().list[1].id();
+    let c = editor.editor().list[2].id();
+    layout(&mut editor);
+
+    editor.start_drag(a, 0, 0);
+    assert_eq!(editor.take_module_events(a), vec![HeadlessModuleEvent::RequestUpdateRect]);
+    assert_eq!(editor.take_dragging_component_outputs().len(), 0);
+
+    editor.move_pointer(50, 70);
+    assert_eq!(editor.hovering_module(), Some(b));
+    assert_eq!(editor.take_module_events(b), vec![HeadlessModuleEvent::RequestUpdateRect, HeadlessModuleEvent::MoveHoveringModule { x: 50, y: 70, module_w: 200.0, module_h: 40.0 }]);
+    assert_eq!(editor.take_dragging_component_outputs().len(), 1);
+
+    editor.move_pointer(50, 120);
+    assert_eq!(editor.hovering_module(), Some(c));
+    assert_eq!(editor.take_module_events(b), vec![HeadlessModuleEvent::LeaveHoveringModule]);
+
+    editor.move_pointer(300, 390);
+    assert_eq!(editor.hovering_module(), Some(Uuid::nil()));
+
+    editor.set_hovering_index(Some(DotEveryEditorOperationIndex::Child(3)));
+    editor.end_drag();
+    assert_eq!(root_types(&editor.editor()), vec![1, 2, 0]);
+}
+
+#[test]
+fn headless_editor_nested_hovering_test() {
+    let mut editor = HeadlessEditor::<TestController, i32>::new();
+    editor.set_root(vec![module("a", 0), block("b", 1)]);
+    let a = editor.editor().list[0].id();
+    let b = editor.editor().list[1].id();
+    editor.add(b, DotEveryEditorOperationIndex::Child(0), block("c", 2));
+    let c = match &editor.editor().list[1].child {
+        ProgramModuleChildItems::BlockVertical(list) => list[0].id(),
+        _ => unreachable!(),
+    };
+    editor.set_rect(c, rect(20.0, 70.0, 100.0, 20.0));
+    layout(&mut editor);
+
+    editor.start_drag(a, 0, 0);
+    editor.move_pointer(30, 75);
+    assert_eq!(editor.hovering_module(), Some(c));
+    editor.move_pointer(150, 75);
+    assert_eq!(editor.hovering_module(), Some(b));
+
+    editor.move_pointer(50, 410);
+    assert_eq!(editor.hovering_module(), Some(Uuid::nil()));
+    editor.move_pointer(50, 430);
+    assert_eq!(editor.hovering_module(), None);
+    editor.send_to_drag(DragModuleAgentInputMessage::SetSnapDistance(40.0));
+    editor.move_pointer(50, 431);
+    assert_eq!(editor.hovering_module(), Some(Uuid::nil()));
+    editor.end_drag();
+
+    editor.start_drag(b, 0, 0);
+    editor.move_pointer(30, 75);
+    assert_eq!(editor.hovering_module(), Some(Uuid::nil()));
+    editor.end_drag();
+}
+
+#[test]
+fn headless_editor_view_transform_test() {
+    let mut editor = HeadlessEditor::<TestController, i32>::new();
+    editor.set_root(vec![module("a", 0), block("b", 1)]);
+    let a = editor.editor().list[0].id();
+    let b = editor.editor().list[1].id();
+    editor.set_view_transform(ViewTransform::new(0.5, 100.0, 0.0));
+    editor.set_rect(Uuid::nil(), rect(100.0, 0.0, 200.0, 200.0));
+    editor.set_rect(a, rect(105.0, 5.0, 100.0, 20.0));
+    editor.set_rect(b, rect(105.0, 30.0, 100.0, 20.0));
+
+    editor.start_drag(a, 0, 0);
+    editor.move_pointer(150, 40);
+    assert_eq!(editor.hovering_module(), Some(b));
+
+    editor.set_view_transform(ViewTransform::new(1.0, 100.0, 50.0));
+    assert_eq!(editor.hovering_module(), Some(Uuid::nil()));
+    editor.move_pointer(150, 130);
+    assert_eq!(editor.hovering_module(), Some(b));
+    assert_eq!(editor.take_module_events(b).last(), Some(&HeadlessModuleEvent::MoveHoveringModule { x: 150, y: 130, module_w: 200.0, module_h: 40.0 }));
+
+    editor.set_hovering_index(Some(DotEveryEditorOperationIndex::Child(0)));
+    editor.end_drag();
+    assert_eq!(child_types(&editor.editor().list[0]), vec![0]);
+}
+
+#[test]
+fn headless_editor_drop_validity_test() {
+    let mut editor = HeadlessEditor::<TestController, i32>::new();
+    editor.set_root(vec![module("a", 0), block("b", 1), module("vetoed", 9)]);
+    let a = editor.editor().list[0].id();
+    let b = editor.editor().list[1].id();
+    let vetoed = editor.editor().list[2].id();
+    layout(&mut editor);
+
+    editor.start_drag(a, 0, 0);
+    editor.move_pointer(50, 70);
+    editor.set_hovering_index(Some(DotEveryEditorOperationIndex::Child(0)));
+    editor.set_hovering_index(Some(DotEveryEditorOperationIndex::OptionAbsolute(0)));
+    assert_eq!(editor.take_module_events(b)[2..], [
+        HeadlessModuleEvent::UpdateDropValidity { index: DotEveryEditorOperationIndex::Child(0), valid: true },
+        HeadlessModuleEvent::UpdateDropValidity { index: DotEveryEditorOperationIndex::OptionAbsolute(0), valid: false },
+    ]);
+    editor.end_drag();
+    assert_eq!(root_types(&editor.editor()), vec![0, 1, 9]);
+
+    editor.start_drag(vetoed, 0, 0);
+    editor.move_pointer(50, 70);
+    editor.set_hovering_index(Some(DotEveryEditorOperationIndex::Child(0)));
+    assert_eq!(editor.take_module_events(b).last(), Some(&HeadlessModuleEvent::UpdateDropValidity { index: DotEveryEditorOperationIndex::Child(0), valid: false }));
+    editor.end_drag();
+    assert_eq!(root_types(&editor.editor()), vec![0, 1, 9]);
+    assert_eq!(child_types(&editor.editor().list[1]), Vec::<i32>::new());
+}
+
+#[test]
+fn headless_editor_drag_following_test() {
+    let mut editor = HeadlessEditor::<TestController, i32>::new();
+    editor.set_root(vec![block("a", 0), module("b", 1), module("c", 2), module("d", 3)]);
+    let a = editor.editor().list[0].id();
+    let b = editor.editor().list[1].id();
+    layout(&mut editor);
+
+    editor.start_drag_following(b, 0, 0);
+    let following = editor.take_drag_outputs().into_iter().find_map(|output| match output {
+        DragModuleAgentOutputMessage::CreateDragComponent { module, following, .. } => {
+            assert_eq!(module.type_data, 1);
+            Some(following.iter().map(|module| module.type_data).collect::<Vec<_>>())
+        }
+        _ => None,
+    });
+    assert_eq!(following, Some(vec![2, 3]));
+    editor.move_pointer(50, 130);
+    assert_eq!(editor.hovering_module(), Some(Uuid::nil()));
+    editor.move_pointer(50, 20);
+    assert_eq!(editor.hovering_module(), Some(a));
+    editor.take_logic_outputs();
+    editor.set_hovering_index(Some(DotEveryEditorOperationIndex::Child(0)));
+    editor.end_drag();
+    assert_eq!(editor.take_logic_outputs().len(), 1);
+
+    let tree = editor.editor();
+    assert_eq!(root_types(&tree), vec![0]);
+    assert_eq!(child_types(&tree.list[0]), vec![1, 2, 3]);
+    assert!(tree.get_module(b).is_err());
+}
+
+#[test]
+fn headless_editor_drag_duplicate_test() {
+    let mut editor = HeadlessEditor::<TestController, i32>::new();
+    editor.set_root(vec![block("a", 0), module("b", 1)]);
+    let a = editor.editor().list[0].id();
+    let b = editor.editor().list[1].id();
+    editor.update_input(b, 1, "value");
+    layout(&mut editor);
+
+    editor.start_drag_duplicate(b, 0, 0);
+    assert!(editor.take_drag_outputs().iter().any(|output| matches!(output, DragModuleAgentOutputMessage::CreateDragComponent { mode: DragMode::Duplicate, .. })));
+    editor.move_pointer(50, 20);
+    assert_eq!(editor.hovering_module(), Some(a));
+    editor.set_hovering_index(Some(DotEveryEditorOperationIndex::Child(0)));
+    editor.end_drag();
+
+    let tree = editor.editor();
+    assert_eq!(root_types(&tree), vec![0, 1]);
+    assert_eq!(tree.list[1].id(), b);
+    assert_eq!(child_types(&tree.list[0]), vec![1]);
+    let copy = match &tree.list[0].child {
+        ProgramModuleChildItems::BlockVertical(list) => list[0].clone(),
+        _ => unreachable!(),
+    };
+    assert_ne!(copy.id(), b);
+    assert_eq!(copy.options, tree.list[1].options);
+
+    editor.set_trash_area(rect(500.0, 0.0, 100.0, 100.0));
+    editor.start_drag_duplicate(b, 0, 0);
+    editor.move_pointer(550, 50);
+    editor.end_drag();
+    assert_eq!(root_types(&editor.editor()), vec![0, 1]);
+}
+
+#[test]
+fn headless_editor_select_test() {
+    let mut editor = HeadlessEditor::<TestController, i32>::new();
+    editor.set_root(vec![module("a", 0), module("b", 1)]);
+    let b = editor.editor().list[1].id();
+    editor.take_logic_outputs();
+
+    editor.select(CursorPosition::Module(b));
+    assert_eq!(editor.cursor().position(), Some(CursorPosition::Module(b)));
+    editor.select(CursorPosition::Input(b, 1));
+    assert_eq!(editor.cursor().position(), Some(CursorPosition::Input(b, 1)));
+    editor.select(CursorPosition::Module(Uuid::new_v4()));
+    assert_eq!(editor.cursor().position(), Some(CursorPosition::Input(b, 1)));
+    let outputs = editor.take_logic_outputs();
+    assert_eq!(outputs.len(), 2);
+    assert!(outputs.iter().all(|output| matches!(output, DotEveryEditorAgentOutputMessage::Selected(_))));
+
+    editor.send_to_controller(TestControllerInput::RequestSelectedCount);
+    assert_eq!(editor.take_controller_outputs(), vec![2]);
+}
+
+#[test]
+fn headless_editor_drop_without_index_test() {
+    let mut editor = HeadlessEditor::<TestController, i32>::new();
+    editor.set_root(vec![module("a", 0), block("b", 1)]);
+    let a = editor.editor().list[0].id();
+    layout(&mut editor);
+
+    editor.start_drag(a, 0, 0);
+    editor.move_pointer(50, 70);
+    editor.set_hovering_index(None);
+    editor.end_drag();
+    assert_eq!(root_types(&editor.editor()), vec![0, 1]);
+    assert_eq!(editor.hovering_module(), None);
+}
+
+#[test]
+fn headless_editor_drop_into_trash_area_test() {
+    let mut editor = HeadlessEditor::<TestController, i32>::new();
+    editor.set_root(vec![module("a", 0), block("b", 1)]);
+    let b = editor.editor().list[1].id();
+    layout(&mut editor);
+    editor.set_trash_area(rect(500.0, 0.0, 100.0, 100.0));
+
+    editor.start_drag(b, 0, 0);
+    editor.take_drag_outputs();
+    editor.move_pointer(550, 50);
+    assert!(matches!(editor.take_drag_outputs()[..], [DragModuleAgentOutputMessage::HoverTrashArea(true)]));
+    editor.end_drag();
+    assert!(matches!(editor.take_drag_outputs()[..], [DragModuleAgentOutputMessage::HoverTrashArea(false), DragModuleAgentOutputMessage::EndDrag]));
+    assert_eq!(root_types(&editor.editor()), vec![0]);
+}
+
+#[test]
+fn headless_editor_drag_from_palette_test() {
+    let mut editor = HeadlessEditor::<TestController, i32>::new();
+    editor.set_root(vec![block("a", 0)]);
+    let a = editor.editor().list[0].id();
+    layout(&mut editor);
+
+    let palette_module = module("new", 5);
+    editor.set_rect(palette_module.id(), rect(0.0, 0.0, 100.0, 20.0));
+    editor.start_drag_module(palette_module.clone(), 0, 0);
+    editor.move_pointer(50, 20);
+    editor.set_hovering_index(Some(DotEveryEditorOperationIndex::Child(0)));
+    editor.end_drag();
+
+    let tree = editor.editor();
+    assert_eq!(child_types(tree.get_module(a).unwrap()), vec![5]);
+    assert!(tree.get_module(palette_module.id()).is_err());
+}
+
+#[test]
+fn headless_editor_controller_test() {
+    let mut editor = HeadlessEditor::<TestController, i32>::new();
+    editor.set_root(vec![module("a", 0), module("b", 1)]);
+    editor.send_to_controller(TestControllerInput::RequestCount);
+    assert_eq!(editor.take_controller_outputs(), vec![2]);
+
+    editor.take_logic_outputs();
+    editor.send_to_controller(TestControllerInput::Clear);
+    assert_eq!(editor.take_logic_outputs().len(), 1);
+    assert!(editor.editor().list.is_empty());
+}
+
+#[test]
+fn headless_editor_keyboard_move_test() {
+    let mut editor = HeadlessEditor::<TestController, i32>::new();
+    editor.set_root(vec![module("a", 0), block("b", 1)]);
+    let a = editor.editor().list[0].id();
+    let b = editor.editor().list[1].id();
+    editor.update_input(a, 1, "typed");
+
+    editor.press_key(CursorKey::Down);
+    assert_eq!(editor.cursor().position(), Some(CursorPosition::Module(a)));
+    editor.press_key(CursorKey::PickUp);
+    for _ in 0..3 {
+        editor.press_key(CursorKey::Down);
+    }
+    assert_eq!(editor.cursor().position(), Some(CursorPosition::Slot(b, DotEveryEditorOperationIndex::Child(0))));
+    editor.take_logic_outputs();
+    editor.press_key(CursorKey::Drop);
+    assert_eq!(editor.take_logic_outputs().len(), 1);
+
+    let tree = editor.editor();
+    assert_eq!(root_types(&tree), vec![1]);
+    assert_eq!(child_types(&tree.list[0]), vec![0]);
+    let moved = tree.get_modules_by_type(&0)[0];
+    assert_eq!(moved.options[1], ProgramModuleOption::StringInput("typed".to_string()));
+    assert_eq!(editor.cursor().position(), Some(CursorPosition::Module(moved.id())));
+
+    editor.press_key(CursorKey::Delete);
+    assert_eq!(child_types(&editor.editor().list[0]), Vec::<i32>::new());
+    assert_eq!(editor.cursor().position(), Some(CursorPosition::Slot(b, DotEveryEditorOperationIndex::Child(0))));
+}
+
+#[test]
+fn headless_editor_layout_drag_test() {
+    let mut editor = HeadlessEditor::<TestController, i32>::new();
+    editor.set_root(vec![block("a", 0), module("b", 1)]);
+    let a = editor.editor().list[0].id();
+    let b = editor.editor().list[1].id();
+    let layout = editor.set_layout(100.0, 50.0);
+    let child = layout.get(a).unwrap().child.as_ref().unwrap().rect.clone();
+
+    editor.start_drag(b, 0, 0);
+    editor.take_layout_outputs();
+    editor.move_pointer((100.0 + child.x + 10.0) as i32, (50.0 + child.y + child.h / 2.0) as i32);
+    assert!(editor.take_layout_outputs().iter().any(|output| matches!(output, DragModuleAgentOutputMessage::UpdateLayoutHovering(Some((target, DotEveryEditorOperationIndex::Child(0)))) if *target == a)));
+    editor.end_drag();
+    assert!(editor.take_layout_outputs().iter().any(|output| matches!(output, DragModuleAgentOutputMessage::UpdateLayoutHovering(None))));
+
+    let tree = editor.editor();
+    assert_eq!(root_types(&tree), vec![0]);
+    assert_eq!(child_types(&tree.list[0]), vec![1]);
+}
+
+#[test]
+fn headless_editor_modules_updated_test() {
+    let mut editor = HeadlessEditor::<TestController, i32>::new();
+    editor.set_root(vec![block("a", 0), module("b", 1)]);
+    let a = editor.editor().list[0].id();
+    let b = editor.editor().list[1].id();
+    editor.take_logic_outputs();
+
+    editor.add(a, DotEveryEditorOperationIndex::Child(0), module("c", 2));
+    match editor.take_logic_outputs().as_slice() {
+        [DotEveryEditorAgentOutputMessage::ModulesUpdated(modules)] => assert_eq!(modules.iter().map(ProgramModule::id).collect::<Vec<_>>(), vec![a]),
+        _ => panic!("expected only the changed subtree"),
+    }
+    let c = match &editor.editor().list[0].child {
+        ProgramModuleChildItems::BlockVertical(list) => list[0].id(),
+        _ => unreachable!(),
+    };
+    assert_eq!(child_types(&editor.editor().list[0]), vec![2]);
+
+    editor.update_input(c, 1, "value");
+    match editor.take_logic_outputs().as_slice() {
+        [DotEveryEditorAgentOutputMessage::ModulesUpdated(modules)] => assert_eq!(modules.iter().map(ProgramModule::id).collect::<Vec<_>>(), vec![c]),
+        _ => panic!("expected only the changed subtree"),
+    }
+    assert_eq!(editor.module(c).unwrap().options[1], ProgramModuleOption::StringInput("value".to_string()));
+    assert_eq!(editor.module(c).unwrap().parent(), Some(a));
+
+    editor.remove(b);
+    assert!(matches!(editor.take_logic_outputs().as_slice(), [DotEveryEditorAgentOutputMessage::ModuleUpdated(_)]));
+    assert_eq!(root_types(&editor.editor()), vec![0]);
+}
+
+#[test]
+fn headless_editor_collapse_test() {
+    let mut editor = HeadlessEditor::<TestController, i32>::new();
+    editor.set_root(vec![block("a", 0)]);
+    let a = editor.editor().list[0].id();
+    editor.add(a, DotEveryEditorOperationIndex::Child(0), module("b", 1));
+    editor.take_logic_outputs();
+
+    editor.set_collapsed(a, true);
+    match editor.take_logic_outputs().as_slice() {
+        [DotEveryEditorAgentOutputMessage::ModulesUpdated(modules)] => assert!(modules[0].collapsed),
+        _ => panic!("expected only the collapsed subtree"),
+    }
+    assert!(editor.editor().list[0].collapsed);
+    assert_eq!(child_types(&editor.editor().list[0]), vec![1]);
+    assert_eq!(editor.editor().list[0].module_count(), 2);
+
+    editor.set_collapsed(a, true);
+    assert!(editor.take_logic_outputs().is_empty());
+}
+
+#[test]
+fn headless_editor_lock_test() {
+    let mut editor = HeadlessEditor::<TestController, i32>::new();
+    let mut header = block("a", 0);
+    header.lock = ModuleLock::all();
+    let mut fixed_input = module("b", 1);
+    fixed_input.lock.no_edit = true;
+    editor.set_root(vec![header, fixed_input, module("c", 2)]);
+    let a = editor.editor().list[0].id();
+    let b = editor.editor().list[1].id();
+    let c = editor.editor().list[2].id();
+    layout(&mut editor);
+    editor.set_trash_area(rect(500.0, 0.0, 100.0, 100.0));
+    editor.take_logic_outputs();
+
+    editor.start_drag(a, 0, 0);
+    assert!(!editor.take_drag_outputs().iter().any(|output| matches!(output, DragModuleAgentOutputMessage::CreateDragComponent { .. })));
+    editor.move_pointer(550, 50);
+    editor.end_drag();
+    editor.remove(a);
+    assert_eq!(root_types(&editor.editor()), vec![0, 1, 2]);
+
+    editor.update_input(b, 1, "value");
+    assert!(editor.take_logic_outputs().is_empty());
+    assert_eq!(editor.module(b).unwrap().options[1], ProgramModuleOption::StringInput(String::new()));
+
+    editor.drag_and_drop(c, 50, 20, DotEveryEditorOperationIndex::Child(0));
+    assert_eq!(root_types(&editor.editor()), vec![0, 1, 2]);
+    assert!(child_types(&editor.editor().list[0]).is_empty());
+}
+
+#[test]
+fn headless_editor_limit_test() {
+    let mut editor = HeadlessEditor::<TestController, i32>::new();
+    let mut palette = Palette::from(vec![module("a", 0), module("b", 1), module("c", 2)]);
+    palette.set_limit(1, Some(1));
+    palette.budget = Some(3);
+    editor.send_to_controller(TestControllerInput::SetPalette(palette));
+    assert_eq!(editor.palette().limit(&1), Some(1));
+    editor.set_root(vec![module("a", 0)]);
+
+    let b = module("b", 1);
+    let b_id = b.id();
+    editor.add(Uuid::nil(), DotEveryEditorOperationIndex::Child(1), b);
+    editor.add(Uuid::nil(), DotEveryEditorOperationIndex::Child(2), module("b", 1));
+    assert_eq!(root_types(&editor.editor()), vec![0, 1]);
+    editor.send_to_logic(DotEveryEditorAgentInputMessage::Copy(b_id, Uuid::nil(), DotEveryEditorOperationIndex::Child(0)));
+    assert_eq!(root_types(&editor.editor()), vec![0, 1]);
+
+    let c = module("c", 2);
+    let c_id = c.id();
+    editor.add(Uuid::nil(), DotEveryEditorOperationIndex::Child(2), c.clone());
+    editor.add(Uuid::nil(), DotEveryEditorOperationIndex::Child(3), module("a", 0));
+    assert_eq!(root_types(&editor.editor()), vec![0, 1, 2]);
+    editor.take_logic_outputs();
+    editor.send_to_logic(DotEveryEditorAgentInputMessage::CanAdd(Uuid::nil(), DotEveryEditorOperationIndex::Child(0), vec![module("a", 0)]));
+    editor.send_to_logic(DotEveryEditorAgentInputMessage::CanAdd(Uuid::nil(), DotEveryEditorOperationIndex::Child(0), vec![c.clone()]));
+    let results = editor.take_logic_outputs().into_iter().filter_map(|output| match output {
+        DotEveryEditorAgentOutputMessage::CanAddResult(_, _, valid) => Some(valid),
+        _ => None,
+    }).collect::<Vec<_>>();
+    assert_eq!(results, vec![false, true]);
+
+    // moving a module that is already in the program does not use the budget
+    editor.send_to_logic(DotEveryEditorAgentInputMessage::Move(vec![c_id], Uuid::nil(), DotEveryEditorOperationIndex::Child(0), vec![c.deep_clone()]));
+    assert_eq!(root_types(&editor.editor()), vec![2, 0, 1]);
+
+    editor.remove(b_id);
+    editor.add(Uuid::nil(), DotEveryEditorOperationIndex::Child(2), module("b", 1));
+    assert_eq!(root_types(&editor.editor()), vec![2, 0, 1]);
+    assert_eq!(editor.palette().remaining(&editor.editor(), &1), Some(0));
+    assert_eq!(editor.palette().remaining_budget(&editor.editor()), Some(0));
+}
+
+#[test]
+fn headless_editor_context_menu_test() {
+    let mut editor = HeadlessEditor::<TestController, i32>::new();
+    let mut fixed = module("c", 2);
+    fixed.lock.no_edit = true;
+    editor.set_root(vec![block("a", 0), module("b", 1), fixed]);
+    let a = editor.editor().list[0].id();
+    let b = editor.editor().list[1].id();
+    let c = editor.editor().list[2].id();
+    editor.take_logic_outputs();
+
+    editor.send_to_logic(DotEveryEditorAgentInputMessage::OpenContextMenu { id: a, x: 10, y: 20 });
+    let menus = editor.take_logic_outputs().into_iter().filter_map(|output| match output {
+        DotEveryEditorAgentOutputMessage::ContextMenu { id, x, y, items, help } => Some((id, x, y, items, help)),
+        _ => None,
+    }).collect::<Vec<_>>();
+    assert_eq!(menus, vec![(a, 10, 20, vec![ContextMenuItem::new("append", "Append")], Some("help.0".to_string()))]);
+
+    editor.send_to_logic(DotEveryEditorAgentInputMessage::ContextAction { id: a, action: "append".to_string() });
+    assert_eq!(root_types(&editor.editor()), vec![3, 0, 1, 2]);
+    assert!(child_types(&editor.module(a).unwrap()).is_empty());
+
+    editor.send_to_logic(DotEveryEditorAgentInputMessage::Duplicate(b));
+    assert_eq!(root_types(&editor.editor()), vec![3, 0, 1, 1, 2]);
+    editor.add(a, DotEveryEditorOperationIndex::Child(0), module("d", 4));
+    let d = match &editor.module(a).unwrap().child {
+        ProgramModuleChildItems::BlockVertical(list) => list[0].id(),
+        _ => unreachable!(),
+    };
+    editor.send_to_logic(DotEveryEditorAgentInputMessage::Duplicate(d));
+    assert_eq!(child_types(&editor.module(a).unwrap()), vec![4, 4]);
+
+    editor.send_to_logic(DotEveryEditorAgentInputMessage::SetDisabled { id: b, disabled: true });
+    editor.send_to_logic(DotEveryEditorAgentInputMessage::SetComment { id: b, comment: Some("note".to_string()) });
+    assert!(editor.module(b).unwrap().disabled);
+    assert_eq!(editor.module(b).unwrap().comment, Some("note".to_string()));
+
+    editor.send_to_logic(DotEveryEditorAgentInputMessage::SetDisabled { id: c, disabled: true });
+    editor.send_to_logic(DotEveryEditorAgentInputMessage::SetComment { id: c, comment: Some("note".to_string()) });
+    assert!(!editor.module(c).unwrap().disabled);
+    assert_eq!(editor.module(c).unwrap().comment, None);
+}
@@ -0,0 +1,25 @@
+use crate::logic::program_module::{ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
+use crate::util::Rect;
+
+// fixtures shared by the test modules
+
+pub(crate) fn module(sign: &str, type_data: i32) -> ProgramModule<i32> {
+    module_with_input(sign, "", type_data)
+}
+
+pub(crate) fn module_with_input(sign: &str, input: &str, type_data: i32) -> ProgramModule<i32> {
+    ProgramModule::new(vec![ProgramModuleOption::StringSign(sign.to_string()), ProgramModuleOption::StringInput(input.to_string())], ProgramModuleChildItems::None, type_data)
+}
+
+pub(crate) fn block(sign: &str, type_data: i32) -> ProgramModule<i32> {
+    ProgramModule::new(vec![ProgramModuleOption::StringSign(sign.to_string())], ProgramModuleChildItems::BlockVertical(Vec::new()), type_data)
+}
+
+pub(crate) fn rect(x: f64, y: f64, w: f64, h: f64) -> Rect {
+    Rect { x, y, w, h }
+}
+
+#[cfg(test)]
+mod headless;
+#[cfg(test)]
+mod viewer;
@@ -0,0 +1,35 @@
+use crate::components::tests::module_with_input;
+use crate::components::viewer::ViewerState;
+use crate::logic::dotevery_editor::DotEveryEditor;
+use crate::logic::i18n::MessageCatalog;
+use crate::logic::layout::LayoutMetrics;
+
+fn viewer(program: &DotEveryEditor<i32>) -> ViewerState<i32> {
+    let metrics = LayoutMetrics::default();
+    ViewerState::new(program, &MessageCatalog::default(), &|_| None, &metrics, &metrics)
+}
+
+#[test]
+fn viewer_instances_test() {
+    let first = DotEveryEditor::new(vec![module_with_input("print", "hello", 0)]);
+    let second = DotEveryEditor::new(vec![module_with_input("wait", "1", 1), module_with_input("stop", "now", 2)]);
+    let (mut a, mut b) = (viewer(&first), viewer(&second));
+    let (first_id, second_id) = (first.list[0].id(), second.list[1].id());
+
+    assert_eq!(a.layout.list.modules.len(), 1);
+    assert_eq!(b.layout.list.modules.len(), 2);
+    assert!(a.layout.contains(first_id) && !a.layout.contains(second_id));
+    assert!(b.layout.contains(second_id) && !b.layout.contains(first_id));
+
+    assert!(a.select(Some(first_id)));
+    assert!(!a.select(Some(first_id)));
+    assert!(!b.select(Some(first_id)));
+    assert_eq!(b.selected, None);
+    assert!(b.select(Some(second_id)));
+    assert_eq!(a.selected_text(), Some("print hello".to_string()));
+    assert_eq!(b.selected_text(), Some("stop now".to_string()));
+
+    assert!(a.select(None));
+    assert_eq!(a.selected_text(), None);
+    assert_eq!(b.selected, Some(second_id));
+}
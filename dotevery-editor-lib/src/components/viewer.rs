@@ -0,0 +1,155 @@
+use std::rc::Rc;
+
+use uuid::Uuid;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
+use web_sys::DataTransfer;
+use yew::{Component, ComponentLink, Html};
+use yew::prelude::*;
+
+use crate::components::dotevery_editor::default_messages;
+use crate::components::program_module_renderer::ProgramModuleRenderer;
+use crate::components::svg_program::{view_svg_module, SVG_FONT};
+use crate::logic::canvas_text_metrics::CanvasTextMetrics;
+use crate::logic::dotevery_editor::DotEveryEditor;
+use crate::logic::i18n::MessageCatalog;
+use crate::logic::layout::{EditorLayout, LayoutMetrics, TextMetrics};
+use crate::logic::outline::plain_text;
+
+// the document and the selection of one viewer, nothing is shared with the other instances
+pub(crate) struct ViewerState<T: 'static + Clone + PartialEq> {
+    pub(crate) localized: DotEveryEditor<T>,
+    pub(crate) layout: EditorLayout,
+    pub(crate) selected: Option<Uuid>,
+}
+
+impl<T: 'static + Clone + PartialEq> ViewerState<T> {
+    pub(crate) fn new(program: &DotEveryEditor<T>, messages: &MessageCatalog, tag: &dyn Fn(&T) -> Option<String>, metrics: &LayoutMetrics, text: &dyn TextMetrics) -> Self {
+        let localized = messages.localize_editor(program, tag);
+        let layout = EditorLayout::with_text_metrics(&localized, metrics, text);
+        Self { localized, layout, selected: None }
+    }
+
+    // modules of another program are not selectable
+    pub(crate) fn select(&mut self, id: Option<Uuid>) -> bool {
+        let id = id.filter(|id| self.localized.get_module(*id).is_ok());
+        if self.selected == id { return false; }
+        self.selected = id;
+        true
+    }
+
+    pub(crate) fn selected_text(&self) -> Option<String> {
+        self.selected.and_then(|id| self.localized.get_module(id).ok()).map(plain_text)
+    }
+}
+
+#[derive(Clone, Properties, PartialEq)]
+pub struct DotEveryViewerProperties<T: 'static + Clone + PartialEq> {
+    pub program: DotEveryEditor<T>,
+    #[prop_or_else(default_messages)]
+    pub messages: Rc<MessageCatalog>,
+}
+
+impl<T: 'static + Clone + PartialEq> DotEveryViewerProperties<T> {
+    pub fn new(program: DotEveryEditor<T>) -> Self {
+        Self { program, messages: default_messages() }
+    }
+
+    pub fn messages(mut self, messages: MessageCatalog) -> Self {
+        self.messages = Rc::new(messages);
+        self
+    }
+}
+
+// a read-only program drawn with the svg renderer, it uses no agents so any number of viewers can show different programs
+pub struct DotEveryViewerComponent<Type = ()>
+    where Type: 'static + Clone + PartialEq + ProgramModuleRenderer {
+    link: ComponentLink<Self>,
+    props: DotEveryViewerProperties<Type>,
+    metrics: LayoutMetrics,
+    text_metrics: CanvasTextMetrics,
+    state: ViewerState<Type>,
+}
+
+pub enum DotEveryViewerMessage {
+    Ignore,
+    Select(Option<Uuid>),
+}
+
+impl<T> Component for DotEveryViewerComponent<T>
+    where T: 'static + Clone + PartialEq + ProgramModuleRenderer {
+    type Message = DotEveryViewerMessage;
+    type Properties = DotEveryViewerProperties<T>;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let metrics = LayoutMetrics::default();
+        let text_metrics = CanvasTextMetrics::new(SVG_FONT, &metrics);
+        let state = ViewerState::new(&props.program, &props.messages, &T::sign_tag, &metrics, &text_metrics);
+        Self {
+            link,
+            props,
+            metrics,
+            text_metrics,
+            state,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> bool {
+        match msg {
+            Self::Message::Ignore => false,
+            Self::Message::Select(id) => self.state.select(id),
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> bool {
+        if self.props == props { return false; }
+        let selected = self.state.selected;
+        self.state = ViewerState::new(&props.program, &props.messages, &T::sign_tag, &self.metrics, &self.text_metrics);
+        self.state.select(selected);
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html {
+        let on_pointer_down = |id: Uuid| self.link.callback(move |e: PointerEvent| {
+            if e.is_primary() && e.button() == 0 {
+                e.stop_propagation();
+                Self::Message::Select(Some(id))
+            } else {
+                Self::Message::Ignore
+            }
+        });
+        let on_input = |id: Uuid, _: usize| self.link.callback(move |e: PointerEvent| {
+            e.stop_propagation();
+            Self::Message::Select(Some(id))
+        });
+        let on_context_menu = |_: Uuid| self.link.callback(|_: MouseEvent| Self::Message::Ignore);
+        let modules = self.state.layout.list.modules.iter().zip(&self.state.localized.list)
+            .map(|(layout, module)| view_svg_module(layout, module, &self.metrics, &on_pointer_down, &on_input, &on_context_menu));
+        let selection = match self.state.selected.and_then(|id| self.state.layout.get(id)) {
+            Some(layout) => html! {<rect class="svg_program_selection" x=layout.rect.x y=layout.rect.y width=layout.rect.w height=layout.rect.h rx=4/>},
+            None => html! {},
+        };
+        let selected_text = self.state.selected_text();
+        let copy = self.link.callback(move |e: Event| {
+            // ClipboardEvent needs web_sys_unstable_apis, so clipboardData is read off the plain event
+            let data = js_sys::Reflect::get(&e, &JsValue::from_str("clipboardData")).ok().and_then(|data| data.dyn_into::<DataTransfer>().ok());
+            if let (Some(text), Some(data)) = (&selected_text, data) {
+                if data.set_data("text/plain", text).is_ok() {
+                    e.prevent_default();
+                }
+            }
+            Self::Message::Ignore
+        });
+        let clear = self.link.callback(|_: PointerEvent| Self::Message::Select(None));
+        let style = format!("font:{};", SVG_FONT);
+        html! {
+            <div tabindex="0" oncopy=copy class="dotevery_viewer">
+                <svg class="svg_program" style=style width=self.state.layout.bounds.w height=self.state.layout.bounds.h onpointerdown=clear>
+                    {for modules}
+                    {selection}
+                </svg>
+            </div>
+        }
+    }
+}
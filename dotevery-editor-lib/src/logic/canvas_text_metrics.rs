@@ -0,0 +1,48 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, window};
+
+use crate::logic::layout::{LayoutMetrics, TextMetrics};
+
+// measures text with the font the svg renderer draws with, falls back to the monospace metrics outside of a browser
+pub struct CanvasTextMetrics {
+    context: Option<CanvasRenderingContext2d>,
+    fallback: LayoutMetrics,
+    cache: RefCell<HashMap<String, f64>>,
+}
+
+impl CanvasTextMetrics {
+    pub fn new(font: &str, fallback: &LayoutMetrics) -> Self {
+        let context = window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.create_element("canvas").ok())
+            .and_then(|canvas| canvas.dyn_into::<HtmlCanvasElement>().ok())
+            .and_then(|canvas| canvas.get_context("2d").ok().flatten())
+            .and_then(|context| context.dyn_into::<CanvasRenderingContext2d>().ok());
+        if let Some(context) = &context {
+            context.set_font(font);
+        }
+        Self {
+            context,
+            fallback: fallback.clone(),
+            cache: RefCell::new(HashMap::new()),
+        }
+    }
+}
+
+impl TextMetrics for CanvasTextMetrics {
+    fn text_width(&self, text: &str) -> f64 {
+        let context = match &self.context {
+            Some(context) => context,
+            None => return self.fallback.text_width(text),
+        };
+        if let Some(width) = self.cache.borrow().get(text) {
+            return *width;
+        }
+        let width = context.measure_text(text).map(|metrics| metrics.width().ceil()).unwrap_or_else(|_| self.fallback.text_width(text));
+        self.cache.borrow_mut().insert(text.to_string(), width);
+        width
+    }
+}
@@ -1,13 +1,16 @@
 use std::sync::{Arc, RwLock};
 
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 use yew::{Bridge, Bridged, Callback};
 use yew::agent::HandlerId;
 
 use crate::components::dotevery_editor_agent_bridge::DotEveryEditorAgentBridge;
 use crate::components::dotevery_editor_controller_proxy_agent::DotEveryEditorControllerProxyAgent;
 use crate::components::DotEveryBridge;
-use crate::logic::dotevery_editor::DotEveryEditor;
+use crate::logic::cursor::CursorPosition;
+use crate::logic::dotevery_editor::{DotEveryEditor, DotEveryEditorOperationIndex};
+use crate::logic::palette::Palette;
 use crate::logic::program_module::ProgramModule;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -17,13 +20,56 @@ pub enum DotEveryEditorCommand {
     // UpdateLocal { data: DotEveryEditor, module_id: Uuid },TODO
 }
 
+// an entry the controller adds to the context menu of a module, the label is looked up in the message catalog
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ContextMenuItem {
+    pub action: String,
+    pub label: String,
+}
+
+impl ContextMenuItem {
+    pub fn new(action: impl Into<String>, label: impl Into<String>) -> Self {
+        Self { action: action.into(), label: label.into() }
+    }
+}
+
+// an edit the controller hands back to the agent, e.g. from a context menu action
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum DotEveryEditorOperation<Type: 'static + Clone + PartialEq = ()> {
+    Add(Uuid, DotEveryEditorOperationIndex, ProgramModule<Type>),
+    //src,dest,index
+    Copy(Uuid, Uuid, DotEveryEditorOperationIndex),
+    //src,dest,index,modules
+    Move(Vec<Uuid>, Uuid, DotEveryEditorOperationIndex, Vec<ProgramModule<Type>>),
+    Remove(Uuid),
+    UpdateInput { id: Uuid, index: usize, value: String },
+    SetCollapsed { id: Uuid, collapsed: bool },
+    SetDisabled { id: Uuid, disabled: bool },
+    SetComment { id: Uuid, comment: Option<String> },
+}
+
 pub trait DotEveryEditorController<Type: 'static + Clone + PartialEq = ()>: 'static + Sized {
     type Input;
     type Output;
     fn bridge(callback: Callback<Self::Output>) -> Box<dyn Bridge<DotEveryEditorControllerProxyAgent<Self, Type>>> {
         DotEveryEditorControllerProxyAgent::<Self, Type>::bridge(callback)
     }
-    fn create(data: Arc<RwLock<DotEveryEditor<Type>>>, palette: Arc<RwLock<Vec<ProgramModule<Type>>>>, bridge: DotEveryEditorAgentBridge<Self, Type>) -> Self;
+    fn create(data: Arc<RwLock<DotEveryEditor<Type>>>, palette: Arc<RwLock<Palette<Type>>>, bridge: DotEveryEditorAgentBridge<Self, Type>) -> Self;
     fn update(&mut self);
     fn handle_input(&mut self, msg: Self::Input, id: HandlerId);
+    fn can_add(&self, _target: Uuid, _index: DotEveryEditorOperationIndex, _module: &ProgramModule<Type>) -> bool {
+        true
+    }
+    fn select(&mut self, _position: CursorPosition) {}
+    fn context_menu(&self, _module: &ProgramModule<Type>) -> Vec<ContextMenuItem> {
+        Vec::new()
+    }
+    // the returned operations are applied by the agent like any other, so can_add and the locks still apply
+    fn context_action(&mut self, _module: Uuid, _action: &str) -> Vec<DotEveryEditorOperation<Type>> {
+        Vec::new()
+    }
+    // the help text or a key of the message catalog
+    fn help(&self, _module: &ProgramModule<Type>) -> Option<String> {
+        None
+    }
 }
@@ -0,0 +1,27 @@
+use crate::logic::i18n::MessageCatalog;
+
+// key, English, Japanese
+const EDITOR_MESSAGES: &[(&str, &str, &str)] = &[
+    ("editor.search", "Search", "検索"),
+    ("editor.collapsed_blocks", "{count} blocks", "{count} 個のブロック"),
+    ("editor.locked", "Locked", "ロックされています"),
+    ("editor.budget", "{count} blocks left", "残り {count} ブロック"),
+    ("editor.duplicate", "Duplicate", "複製"),
+    ("editor.delete", "Delete", "削除"),
+    ("editor.collapse", "Collapse", "折りたたむ"),
+    ("editor.expand", "Expand", "展開する"),
+    ("editor.disable", "Disable", "無効にする"),
+    ("editor.enable", "Enable", "有効にする"),
+    ("editor.comment", "Comment", "コメント"),
+    ("editor.help", "Help", "ヘルプ"),
+];
+
+// the strings of the editor itself, merge the application catalog into this one
+pub fn editor_messages() -> MessageCatalog {
+    let mut catalog = MessageCatalog::new("en");
+    for (key, en, ja) in EDITOR_MESSAGES {
+        catalog.insert("en", key, en);
+        catalog.insert("ja", key, ja);
+    }
+    catalog
+}
@@ -1,5 +1,8 @@
+pub use dotevery_editor_core::{cursor, dotevery_editor, dotevery_module, i18n, layout, outline, palette, program_module, spatial_index, svg_export, template, viewport};
+
+pub mod canvas_text_metrics;
 pub mod dotevery_editor_controller;
-pub mod dotevery_editor;
-pub mod program_module;
+pub mod editor_messages;
+pub mod png_export;
 #[cfg(test)]
-mod tests;
\ No newline at end of file
+mod tests;
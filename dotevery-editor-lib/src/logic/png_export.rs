@@ -0,0 +1,44 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, HtmlImageElement, window};
+
+use crate::logic::svg_export::SvgExport;
+
+// rasterizes the exported svg through a canvas, `callback` gets a png data url once the image has been loaded
+pub fn export_png(image: &SvgExport, scale: f64, callback: impl FnOnce(Result<String, JsValue>) + 'static) -> Result<(), JsValue> {
+    let document = window().and_then(|window| window.document()).ok_or_else(|| JsValue::from_str("document is not available"))?;
+    let canvas = document.create_element("canvas")?.dyn_into::<HtmlCanvasElement>()?;
+    canvas.set_width((image.width * scale).ceil() as u32);
+    canvas.set_height((image.height * scale).ceil() as u32);
+    let context = canvas.get_context("2d")?
+        .ok_or_else(|| JsValue::from_str("canvas is not available"))?
+        .dyn_into::<CanvasRenderingContext2d>()?;
+    context.scale(scale, scale)?;
+
+    let element = HtmlImageElement::new()?;
+    let callback = Rc::new(RefCell::new(Some(callback)));
+    let onload = {
+        let callback = Rc::clone(&callback);
+        let element = element.clone();
+        Closure::once_into_js(move || {
+            let result = context.draw_image_with_html_image_element(&element, 0f64, 0f64)
+                .and_then(|_| canvas.to_data_url_with_type("image/png"));
+            if let Some(callback) = callback.borrow_mut().take() {
+                callback(result);
+            }
+        })
+    };
+    let onerror = Closure::once_into_js(move || {
+        if let Some(callback) = callback.borrow_mut().take() {
+            callback(Err(JsValue::from_str("failed to load the exported svg")));
+        }
+    });
+    element.set_onload(Some(onload.unchecked_ref()));
+    element.set_onerror(Some(onerror.unchecked_ref()));
+    let url = format!("data:image/svg+xml;charset=utf-8,{}", String::from(js_sys::encode_uri_component(&image.svg)));
+    element.set_src(&url);
+    Ok(())
+}
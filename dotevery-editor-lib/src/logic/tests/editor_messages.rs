@@ -0,0 +1,14 @@
+use crate::logic::editor_messages::editor_messages;
+
+#[test]
+fn editor_messages_test() {
+    let mut messages = editor_messages();
+    assert_eq!(messages.locale(), "en");
+    assert_eq!(messages.get("editor.search"), "Search");
+    assert_eq!(messages.format("editor.collapsed_blocks", &[("count", "3")]), "3 blocks");
+    messages.set_locale("ja");
+    assert_eq!(messages.get("editor.search"), "検索");
+    assert_eq!(messages.format("editor.collapsed_blocks", &[("count", "3")]), "3 個のブロック");
+    messages.set_locale("fr");
+    assert_eq!(messages.get("editor.search"), "Search");
+}
@@ -1,6 +1,2 @@
 #[cfg(test)]
-mod isomorphisms;
-#[cfg(test)]
-mod program_module;
-#[cfg(test)]
-mod dotevery_editor;
\ No newline at end of file
+mod editor_messages;
@@ -1,3 +1,5 @@
+pub use dotevery_editor_core::util::{Isomorphism, Rect};
+
 #[macro_export]
 macro_rules! clog {
     ($($e:expr),*)=>{web_sys::console::log(&{
@@ -7,26 +9,4 @@ macro_rules! clog {
         )*
         arr
     })}
-}
-
-#[derive(Clone, Debug)]
-pub struct Rect {
-    pub x: f64,
-    pub y: f64,
-    pub w: f64,
-    pub h: f64,
-}
-
-impl Rect {
-    pub fn encloses(&self, x: f64, y: f64) -> bool {
-        self.x < x && x < self.x + self.w && self.y < y && y < self.y + self.y + self.h
-    }
-
-    pub fn center(&self) -> (f64, f64) {
-        (self.x + self.w / 2f64, self.y + self.h / 2f64)
-    }
-}
-
-pub trait Isomorphism {
-    fn isomorphisms(&self, other: &Self) -> bool;
 }
\ No newline at end of file
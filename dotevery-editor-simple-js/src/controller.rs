@@ -1,61 +1,64 @@
 use std::sync::{Arc, RwLock};
 
 use enum_iterator::IntoEnumIterator;
+use uuid::Uuid;
 use yew::agent::HandlerId;
 
 use dotevery_editor_lib::clog;
 use dotevery_editor_lib::components::DotEveryBridge;
-use dotevery_editor_lib::logic::dotevery_editor::DotEveryEditor;
-use dotevery_editor_lib::logic::dotevery_editor_controller::{DotEveryEditorCommand, DotEveryEditorController};
+use dotevery_editor_lib::logic::cursor::CursorPosition;
+use dotevery_editor_lib::logic::dotevery_editor::{DotEveryEditor, DotEveryEditorOperationIndex};
+use dotevery_editor_lib::logic::dotevery_editor_controller::{ContextMenuItem, DotEveryEditorCommand, DotEveryEditorController, DotEveryEditorOperation};
+use dotevery_editor_lib::logic::dotevery_module::DotEveryModule;
+use dotevery_editor_lib::logic::i18n::MessageCatalog;
+use dotevery_editor_lib::logic::palette::{Palette, PaletteCategory};
+use dotevery_editor_lib::logic::template::PaletteTemplate;
 use dotevery_editor_lib::logic::program_module::{ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
 
-use crate::program_module_enum::{create_module, ProgramModuleType};
+use crate::program_module_enum::ProgramModuleType;
+
+const VARIABLES_CATEGORY: &str = "Variables";
+const PRINT_VALUE_ACTION: &str = "print_value";
 
 pub struct Controller {
     bridge: DotEveryBridge<Self, ProgramModuleType>,
     data: Arc<RwLock<DotEveryEditor<ProgramModuleType>>>,
-    palette: Arc<RwLock<Vec<ProgramModule<ProgramModuleType>>>>,
+    palette: Arc<RwLock<Palette<ProgramModuleType>>>,
+    // the module at the editor cursor
+    selected: Option<Uuid>,
 }
 
 pub enum ControllerInput {
     RequestUpdateLogicData,
+    RequestSelectedModule,
     AddVariable(String),
     RemoveVariable(String),
+    LoadPalette(String),
 }
 
 pub enum ControllerOutput {
     UpdateLogicData(DotEveryEditor<ProgramModuleType>),
+    SelectedModule(Option<ProgramModule<ProgramModuleType>>),
+    // translations of the signs written in the loaded palette
+    PaletteLoaded(MessageCatalog),
+    PaletteLoadFailed(String),
 }
 
 impl DotEveryEditorController<ProgramModuleType> for Controller {
     type Input = ControllerInput;
     type Output = ControllerOutput;
 
-    fn create(data: Arc<RwLock<DotEveryEditor<ProgramModuleType>>>, palette: Arc<RwLock<Vec<ProgramModule<ProgramModuleType>>>>, bridge: DotEveryBridge<Self, ProgramModuleType>) -> Self {
+    fn create(data: Arc<RwLock<DotEveryEditor<ProgramModuleType>>>, palette: Arc<RwLock<Palette<ProgramModuleType>>>, bridge: DotEveryBridge<Self, ProgramModuleType>) -> Self {
         clog!("Controller created");
 
-        let types = [
-            ProgramModuleType::Print,
-            ProgramModuleType::NumberLiteral,
-            ProgramModuleType::StringLiteral,
-            ProgramModuleType::Switch,
-            ProgramModuleType::Case,
-            ProgramModuleType::DefaultCase,
-            ProgramModuleType::ValueAssign,
-            ProgramModuleType::ValueAdd,
-            ProgramModuleType::ValueSub,
-            ProgramModuleType::ValueMul,
-            ProgramModuleType::ValueDiv,
-            ProgramModuleType::ValueRem,
-        ];
-        let palette_data: Vec<_> = types.iter().map(|t| create_module(t.clone())).collect();
         // data.write().unwrap().list = palette_data.clone();
         // data.write().unwrap().list.push(palette_data[0].deep_clone());
-        *palette.write().unwrap() = palette_data;
+        *palette.write().unwrap() = ProgramModuleType::palette();
         Self {
             bridge,
             data,
             palette,
+            selected: None,
         }
     }
 
@@ -68,22 +71,18 @@ impl DotEveryEditorController<ProgramModuleType> for Controller {
             ControllerInput::RequestUpdateLogicData => {
                 self.bridge.respond(id, Self::Output::UpdateLogicData(self.data.read().unwrap().clone()));
             }
+            ControllerInput::RequestSelectedModule => {
+                let module = self.selected.and_then(|selected| self.data.read().unwrap().get_module(selected).ok().cloned());
+                self.bridge.respond(id, Self::Output::SelectedModule(module));
+            }
             ControllerInput::AddVariable(s) => {
                 let mut palette = self.palette.write().unwrap();
-                palette.push(create_module(ProgramModuleType::Variable(s)));
+                palette.push(VARIABLES_CATEGORY, ProgramModuleType::Variable(s).create_module());
                 self.bridge.notify_update(DotEveryEditorCommand::UpdatePalette);
             }
             ControllerInput::RemoveVariable(s) => {
-                let mut palette = self.palette.write().unwrap();
-                let index = palette.iter().enumerate().find_map(|(i, module)| {
-                    if module.type_data == ProgramModuleType::Variable(s.clone()) {
-                        Some(i)
-                    } else {
-                        None
-                    }
-                });
-                if let Some(index) = index {
-                    palette.remove(index);
+                let removed = self.palette.write().unwrap().remove_by_type(&ProgramModuleType::Variable(s.clone()));
+                if removed.is_some() {
                     self.bridge.notify_update(DotEveryEditorCommand::UpdatePalette);
                 }
                 let mut data = self.data.write().unwrap();
@@ -96,6 +95,75 @@ impl DotEveryEditorController<ProgramModuleType> for Controller {
                     self.bridge.notify_update(DotEveryEditorCommand::Update);
                 }
             }
+            ControllerInput::LoadPalette(source) => {
+                match PaletteTemplate::parse(&source).and_then(|template| template.to_palette::<ProgramModuleType>().map(|palette| (template, palette))) {
+                    Ok((template, mut loaded)) => {
+                        let mut palette = self.palette.write().unwrap();
+                        let variables = palette.category(VARIABLES_CATEGORY).map(|category| category.items.clone()).unwrap_or_default();
+                        if loaded.category(VARIABLES_CATEGORY).is_none() {
+                            let color = ProgramModuleType::categories().into_iter().find(|category| category.name == VARIABLES_CATEGORY).map(|category| category.color).unwrap_or_default();
+                            loaded.categories.push(PaletteCategory::new(VARIABLES_CATEGORY, color, Vec::new()));
+                        }
+                        for variable in variables {
+                            loaded.push(VARIABLES_CATEGORY, variable);
+                        }
+                        *palette = loaded;
+                        self.bridge.notify_update(DotEveryEditorCommand::UpdatePalette);
+                        let mut signs = MessageCatalog::default();
+                        template.register_signs(&mut signs);
+                        self.bridge.respond(id, Self::Output::PaletteLoaded(signs));
+                    }
+                    Err(err) => {
+                        self.bridge.respond(id, Self::Output::PaletteLoadFailed(format!("{:?}", err)));
+                    }
+                }
+            }
+        }
+    }
+
+    // a slot selects the module that has it
+    fn select(&mut self, position: CursorPosition) {
+        let id = match position {
+            CursorPosition::Module(id) | CursorPosition::Input(id, _) | CursorPosition::Slot(id, _) => id,
+        };
+        self.selected = Some(id).filter(|id| !id.is_nil());
+    }
+
+    fn context_menu(&self, module: &ProgramModule<ProgramModuleType>) -> Vec<ContextMenuItem> {
+        if module.type_data.is_value() {
+            vec![ContextMenuItem::new(PRINT_VALUE_ACTION, "sample.print_value")]
+        } else {
+            Vec::new()
         }
     }
+
+    // adds a print statement showing a copy of the value after the statement that contains it
+    fn context_action(&mut self, module: Uuid, action: &str) -> Vec<DotEveryEditorOperation<ProgramModuleType>> {
+        if action != PRINT_VALUE_ACTION { return Vec::new(); }
+        let data = self.data.read().unwrap();
+        let (value, (dest, index)) = match (data.get_module(module), data.duplicate_position(module)) {
+            (Ok(value), Ok(position)) => (value, position),
+            _ => return Vec::new(),
+        };
+        let mut print = ProgramModuleType::Print.create_module();
+        if print.add(print.id(), DotEveryEditorOperationIndex::OptionAbsolute(1), &value.deep_clone()).is_err() {
+            return Vec::new();
+        }
+        vec![DotEveryEditorOperation::Add(dest, index, print)]
+    }
+
+    fn help(&self, module: &ProgramModule<ProgramModuleType>) -> Option<String> {
+        let key = match module.type_data {
+            ProgramModuleType::Print => "help.print",
+            ProgramModuleType::StringLiteral => "help.string",
+            ProgramModuleType::NumberLiteral => "help.number",
+            ProgramModuleType::Variable(_) => "help.variable",
+            ProgramModuleType::Switch => "help.switch",
+            ProgramModuleType::Case => "help.case",
+            ProgramModuleType::DefaultCase => "help.default",
+            ProgramModuleType::ValueAssign => "help.assign",
+            ProgramModuleType::ValueAdd | ProgramModuleType::ValueSub | ProgramModuleType::ValueMul | ProgramModuleType::ValueDiv | ProgramModuleType::ValueRem => "help.operator",
+        };
+        Some(key.to_string())
+    }
 }
@@ -21,6 +21,7 @@ use crate::program_module_enum::ProgramModuleType;
 // use dotevery_editor_lib::logic::program_module_list::ProgramModuleList;
 pub mod controller;
 pub mod main_component;
+pub mod messages;
 pub mod program_module_enum;
 
 #[global_allocator]
@@ -1,19 +1,31 @@
 use std::collections::HashSet;
+use std::rc::Rc;
 
 use wasm_bindgen::JsValue;
-use web_sys::{HtmlInputElement, HtmlSelectElement};
-use yew::{Bridge, Component, ComponentLink, Html, html, NodeRef, Properties};
+use wasm_bindgen::JsCast;
+use web_sys::{File, HtmlAnchorElement, HtmlInputElement, HtmlSelectElement};
+use yew::{Bridge, ChangeData, Component, ComponentLink, Html, html, NodeRef, Properties};
+use yew::services::reader::{FileData, ReaderService, ReaderTask};
 
 use dotevery_editor_lib::clog;
 use dotevery_editor_lib::components::dotevery_editor::DotEveryEditorComponent;
 use dotevery_editor_lib::components::DotEveryEditorControllerBridge;
+use dotevery_editor_lib::components::program_module_renderer::ProgramModuleRenderer;
+use dotevery_editor_lib::logic::canvas_text_metrics::CanvasTextMetrics;
 use dotevery_editor_lib::logic::dotevery_editor::DotEveryEditor;
 use dotevery_editor_lib::logic::dotevery_editor_controller::DotEveryEditorController;
+use dotevery_editor_lib::logic::i18n::MessageCatalog;
+use dotevery_editor_lib::logic::layout::LayoutMetrics;
+use dotevery_editor_lib::logic::png_export::export_png;
 use dotevery_editor_lib::logic::program_module::{ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
+use dotevery_editor_lib::logic::svg_export::{export_module_svg, export_svg, SvgExport, SvgExportStyle};
 
 use crate::controller::{Controller, ControllerInput, ControllerOutput};
+use crate::messages::{messages, LOCALES};
 use crate::ProgramModuleType;
 
+const DEFAULT_LOCALE: &str = "ja";
+
 #[derive(Clone, Default, Properties)]
 pub struct MainComponentProperties {}
 
@@ -26,12 +38,31 @@ pub struct MainComponent {
     variable_list_ref: NodeRef,
     compile_result: String,
     exec_result: String,
+    reader_service: ReaderService,
+    reader_task: Option<ReaderTask>,
+    palette_error: String,
+    // the logic data requested next is exported instead of being run
+    export_request: Option<ExportFormat>,
+    messages: Rc<MessageCatalog>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Svg,
+    Png,
 }
 
 pub enum MainComponentMessage {
     Run,
+    Export(ExportFormat),
+    ExportSelection(ExportFormat),
+    PngExported(Result<String, JsValue>),
     AddVariable,
     RemoveVariable,
+    LoadPaletteFile(File),
+    PaletteFileLoaded(FileData),
+    SetLocale(String),
+    Ignore,
     MsgFromController(ControllerOutput),
 }
 
@@ -50,6 +81,11 @@ impl Component for MainComponent {
             variable_list_ref: NodeRef::default(),
             compile_result: String::new(),
             exec_result: String::new(),
+            reader_service: ReaderService::new(),
+            reader_task: None,
+            palette_error: String::new(),
+            export_request: None,
+            messages: Rc::new(messages(DEFAULT_LOCALE)),
         }
     }
 
@@ -60,6 +96,23 @@ impl Component for MainComponent {
                 self.controller_bridge.send(ControllerInput::RequestUpdateLogicData);
                 false
             }
+            Self::Message::Export(format) => {
+                self.export_request = Some(format);
+                self.controller_bridge.send(ControllerInput::RequestUpdateLogicData);
+                false
+            }
+            Self::Message::ExportSelection(format) => {
+                self.export_request = Some(format);
+                self.controller_bridge.send(ControllerInput::RequestSelectedModule);
+                false
+            }
+            Self::Message::PngExported(result) => {
+                match result {
+                    Ok(url) => download("program.png", &url),
+                    Err(err) => clog!(err),
+                }
+                false
+            }
             Self::Message::AddVariable => {
                 if let Some(element) = self.variable_input_ref.cast::<HtmlInputElement>() {
                     let variable_name: String = element.value();
@@ -88,11 +141,49 @@ impl Component for MainComponent {
                     false
                 }
             }
+            Self::Message::LoadPaletteFile(file) => {
+                match self.reader_service.read_file(file, self.link.callback(Self::Message::PaletteFileLoaded)) {
+                    Ok(task) => {
+                        self.reader_task = Some(task);
+                        false
+                    }
+                    Err(err) => {
+                        self.palette_error = format!("{:?}", err);
+                        true
+                    }
+                }
+            }
+            Self::Message::PaletteFileLoaded(data) => {
+                self.reader_task = None;
+                match String::from_utf8(data.content) {
+                    Ok(source) => {
+                        self.palette_error = String::new();
+                        self.controller_bridge.send(ControllerInput::LoadPalette(source));
+                    }
+                    Err(err) => {
+                        self.palette_error = format!("{}: {}", data.name, err);
+                    }
+                }
+                true
+            }
+            Self::Message::SetLocale(locale) => {
+                let mut messages = (*self.messages).clone();
+                messages.set_locale(&locale);
+                self.messages = Rc::new(messages);
+                true
+            }
+            Self::Message::Ignore => false,
             MainComponentMessage::MsgFromController(msg) => {
                 match msg {
                     ControllerOutput::UpdateLogicData(logic) => {
+                        if let Some(format) = self.export_request.take() {
+                            let (metrics, text_metrics, style) = export_settings();
+                            let image = export_svg(&self.messages.localize_editor(&logic, &ProgramModuleType::sign_tag), &metrics, &text_metrics, &style);
+                            self.export(&image, "program", format);
+                            return false;
+                        }
                         clog!("run");
-                        let result = match compile(logic, &self.variables) {
+                        let result = match compile(logic, &self.variables, &self.messages) {
                             Ok((prefix, code, suffix)) => {
                                 self.compile_result = code.clone();
                                 Some(format!("{}{}{}", prefix, code, suffix))
@@ -100,9 +191,9 @@ impl Component for MainComponent {
                             Err(msg) => {
                                 self.compile_result = match msg {
                                     CompileError::NeedProgramModule(traceback) => {
-                                        format!("NeedProgramModuleError\n{}", traceback)
+                                        self.messages.format("compile.need_module", &[("traceback", &traceback)])
                                     }
-                                    e => format!("{:?}", e)
+                                    CompileError::ModuleStructureError => self.messages.get("compile.structure"),
                                 };
                                 self.exec_result = String::new();
                                 None
@@ -115,7 +206,35 @@ impl Component for MainComponent {
                         clog!(&self.exec_result);
                         true
                     }
-                    _ => false,
+                    ControllerOutput::SelectedModule(module) => {
+                        let format = match self.export_request.take() {
+                            Some(format) => format,
+                            None => return false,
+                        };
+                        match module {
+                            Some(module) => {
+                                let (metrics, text_metrics, style) = export_settings();
+                                let image = export_module_svg(&self.messages.localize(&module, &ProgramModuleType::sign_tag), &metrics, &text_metrics, &style);
+                                self.export(&image, "selection", format);
+                            }
+                            None => {
+                                if let Some(window) = web_sys::window() {
+                                    let _ = window.alert_with_message(&self.messages.get("sample.nothing_selected"));
+                                }
+                            }
+                        }
+                        false
+                    }
+                    ControllerOutput::PaletteLoaded(signs) => {
+                        let mut messages = (*self.messages).clone();
+                        messages.merge(&signs);
+                        self.messages = Rc::new(messages);
+                        true
+                    }
+                    ControllerOutput::PaletteLoadFailed(err) => {
+                        self.palette_error = err;
+                        true
+                    }
                 }
             }
         }
@@ -130,16 +249,28 @@ impl Component for MainComponent {
         let variables = self.variables.iter().map(|s| {
             html! {<option>{s}</option>}
         });
+        let locales = LOCALES.iter().map(|(locale, name)| {
+            html! {<option value = *locale selected = self.messages.locale() == *locale>{*name}</option>}
+        });
         html! {
             <div class = "sample_main container">
-                <button class ="btn btn-primary" onclick = self.link.callback( |_ | Self::Message::Run)>{"Compile & Run"}</button>
+                <button class ="btn btn-primary" onclick = self.link.callback( |_ | Self::Message::Run)>{self.messages.get("sample.run")}</button>
+                <button class ="btn btn-secondary ml-2" onclick = self.link.callback( |_ | Self::Message::Export(ExportFormat::Svg))>{self.messages.get("sample.export_svg")}</button>
+                <button class ="btn btn-secondary ml-2" onclick = self.link.callback( |_ | Self::Message::Export(ExportFormat::Png))>{self.messages.get("sample.export_png")}</button>
+                <button class ="btn btn-secondary ml-2" onclick = self.link.callback( |_ | Self::Message::ExportSelection(ExportFormat::Svg))>{self.messages.get("sample.export_selection")}</button>
+                <div class = "form-group form-inline float-right">
+                    <label class = "mr-2">{self.messages.get("sample.locale")}</label>
+                    <select class = "form-control" onchange = self.link.callback(Self::change_locale)>
+                        {for locales}
+                    </select>
+                </div>
                 <div>
                     <div class= "form-group form-inline">
                         <div class = "form-group">
-                            <input ref = self.variable_input_ref.clone() type = "text" class = "form-control" placeholder = "Variable name" />
+                            <input ref = self.variable_input_ref.clone() type = "text" class = "form-control" placeholder = self.messages.get("sample.variable_name") />
                         </div>
                         <div class = "form-group">
-                            <button class = "btn btn-primary" onclick = self.link.callback( | _ | Self::Message::AddVariable)>{"追加"}</button>
+                            <button class = "btn btn-primary" onclick = self.link.callback( | _ | Self::Message::AddVariable)>{self.messages.get("sample.add")}</button>
                         </div>
                     </div>
                     <div class = "form-group">
@@ -147,10 +278,15 @@ impl Component for MainComponent {
                             {for variables}
                         </select>
                     </div>
-                    <button class = "btn btn-primary" onclick = self.link.callback( | _ | Self::Message::RemoveVariable)>{"削除"}</button>
+                    <button class = "btn btn-primary" onclick = self.link.callback( | _ | Self::Message::RemoveVariable)>{self.messages.get("sample.remove")}</button>
+                </div>
+                <div class = "form-group">
+                    <label>{self.messages.get("sample.palette")}</label>
+                    <input type = "file" accept = ".json,.toml" class = "form-control-file" onchange = self.link.callback(Self::load_palette_file)/>
+                    <small class = "text-danger">{self.palette_error.clone()}</small>
                 </div>
                 <div class = "editor_area">
-                    <DotEveryEditorComponent <Controller, ProgramModuleType> />
+                    <DotEveryEditorComponent <Controller, ProgramModuleType> outline=true minimap=true messages=Rc::clone(&self.messages)/>
                     <div class = "result_area">
                         <pre class = "border m-3 p-2"> <code>{self.compile_result.clone()}</code> </pre>
                         <pre class = "border m-3 p-2"> <samp>{self.exec_result.clone()}</samp> </pre>
@@ -161,13 +297,70 @@ impl Component for MainComponent {
     }
 }
 
+impl MainComponent {
+    // `name` without the extension
+    fn export(&self, image: &SvgExport, name: &str, format: ExportFormat) {
+        match format {
+            ExportFormat::Svg => {
+                let url = format!("data:image/svg+xml;charset=utf-8,{}", String::from(js_sys::encode_uri_component(&image.svg)));
+                download(&format!("{}.svg", name), &url);
+            }
+            ExportFormat::Png => {
+                let callback = self.link.callback(MainComponentMessage::PngExported);
+                if let Err(err) = export_png(image, 2f64, move |result| callback.emit(result)) {
+                    clog!(err);
+                }
+            }
+        }
+    }
+
+    fn change_locale(e: ChangeData) -> MainComponentMessage {
+        match e {
+            ChangeData::Select(element) => MainComponentMessage::SetLocale(element.value()),
+            _ => MainComponentMessage::Ignore,
+        }
+    }
+
+    fn load_palette_file(e: ChangeData) -> MainComponentMessage {
+        if let ChangeData::Files(files) = e {
+            if let Some(file) = files.get(0) {
+                return MainComponentMessage::LoadPaletteFile(file);
+            }
+        }
+        MainComponentMessage::Ignore
+    }
+}
+
+fn export_settings() -> (LayoutMetrics, CanvasTextMetrics, SvgExportStyle) {
+    let metrics = LayoutMetrics::default();
+    let style = SvgExportStyle { background: Some("#fff".to_string()), ..SvgExportStyle::default() };
+    let text_metrics = CanvasTextMetrics::new(&style.font, &metrics);
+    (metrics, text_metrics, style)
+}
+
+fn download(name: &str, url: &str) {
+    let anchor = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.create_element("a").ok())
+        .and_then(|element| element.dyn_into::<HtmlAnchorElement>().ok());
+    if let Some(anchor) = anchor {
+        anchor.set_href(url);
+        anchor.set_download(name);
+        anchor.click();
+    }
+}
+
 #[derive(Debug)]
 enum CompileError {
     ModuleStructureError,
     NeedProgramModule(String),
 }
 
-fn compile(data: DotEveryEditor<ProgramModuleType>, variables: &HashSet<String>) -> Result<(String, String, String), CompileError> {
+fn trace(messages: &MessageCatalog, key: &str, args: &[(&str, &str)], traceback: String) -> CompileError {
+    CompileError::NeedProgramModule(format!("{}\n{}", messages.format(key, args), traceback))
+}
+
+fn compile(data: DotEveryEditor<ProgramModuleType>, variables: &HashSet<String>, messages: &MessageCatalog) -> Result<(String, String, String), CompileError> {
     let mut prefix = String::from("(()=>{");
     prefix.push_str("\nlet console={log:function(v){console.buffer=console.buffer+v+\"\\n\";},buffer:\"\"};\n");
     let mut s = String::new();
@@ -175,10 +368,11 @@ fn compile(data: DotEveryEditor<ProgramModuleType>, variables: &HashSet<String>)
         s.push_str(&format!("let {} = undefined;\n", variable));
     }
     for (i, module) in data.list.iter().enumerate() {
-        match compile_inner(module) {
+        if module.disabled { continue; }
+        match compile_inner(module, messages) {
             Ok(c) => { s.push_str(&format!("{}\n", c)); }
             Err(CompileError::NeedProgramModule(traceback)) => {
-                return Err(CompileError::NeedProgramModule(format!("at block[{}]\n{}", i, traceback)));
+                return Err(trace(messages, "compile.at_block", &[("index", &i.to_string())], traceback));
             }
             Err(err) => { return Err(err); }
         }
@@ -189,15 +383,19 @@ fn compile(data: DotEveryEditor<ProgramModuleType>, variables: &HashSet<String>)
     Ok((prefix, s, suffix))
 }
 
-fn compile_inner(module: &ProgramModule<ProgramModuleType>) -> Result<String, CompileError> {
+fn compile_inner(module: &ProgramModule<ProgramModuleType>, messages: &MessageCatalog) -> Result<String, CompileError> {
+    // disabled statements are skipped by the callers, a disabled value leaves its slot empty
+    if module.disabled {
+        return Err(CompileError::NeedProgramModule(messages.get("compile.disabled")));
+    }
     match &module.type_data {
         ProgramModuleType::Print => {
             match &module.options.get(1) {
                 Some(ProgramModuleOption::ProgramModule(Some(inner_module))) => {
-                    match compile_inner(&inner_module) {
+                    match compile_inner(&inner_module, messages) {
                         Ok(s) => { Ok(format!("console.log({});", s)) }
                         Err(CompileError::NeedProgramModule(traceback)) => {
-                            Err(CompileError::NeedProgramModule(format!("at print argument\n{}", traceback)))
+                            Err(trace(messages, "compile.at_print_argument", &[], traceback))
                         }
                         e => e
                     }
@@ -229,15 +427,15 @@ fn compile_inner(module: &ProgramModule<ProgramModuleType>) -> Result<String, Co
         ProgramModuleType::Switch => {
             let prefix = if let Some(ProgramModuleOption::ProgramModule(module)) = module.options.get(1) {
                 if let Some(module) = module {
-                    match compile_inner(module) {
+                    match compile_inner(module, messages) {
                         Ok(s) => { format!("switch ({}) {{", s) }
                         Err(CompileError::NeedProgramModule(traceback)) => {
-                            return Err(CompileError::NeedProgramModule(format!("at switch argument\n{}", traceback)));
+                            return Err(trace(messages, "compile.at_switch_argument", &[], traceback));
                         }
                         e => return e,
                     }
                 } else {
-                    return Err(CompileError::NeedProgramModule(String::from("at switch argument")));
+                    return Err(CompileError::NeedProgramModule(messages.get("compile.at_switch_argument")));
                 }
             } else {
                 return Err(CompileError::ModuleStructureError);
@@ -245,13 +443,14 @@ fn compile_inner(module: &ProgramModule<ProgramModuleType>) -> Result<String, Co
             let code = if let ProgramModuleChildItems::BlockHorizontal(list) = &module.child {
                 let mut code = String::new();
                 for (i, module) in list.iter().enumerate() {
-                    match compile_inner(module) {
+                    if module.disabled { continue; }
+                    match compile_inner(module, messages) {
                         Ok(s) => {
                             code.push_str(&s);
                             code.push('\n');
                         }
                         Err(CompileError::NeedProgramModule(traceback)) => {
-                            return Err(CompileError::NeedProgramModule(format!("at switch block[{}]\n{}", i, traceback)));
+                            return Err(trace(messages, "compile.at_switch_block", &[("index", &i.to_string())], traceback));
                         }
                         e => return e,
                     }
@@ -266,15 +465,15 @@ fn compile_inner(module: &ProgramModule<ProgramModuleType>) -> Result<String, Co
         ProgramModuleType::Case => {
             let prefix = if let Some(ProgramModuleOption::ProgramModule(module)) = module.options.get(1) {
                 if let Some(module) = module {
-                    match compile_inner(module) {
+                    match compile_inner(module, messages) {
                         Ok(s) => { format!("case {}:", s) }
                         Err(CompileError::NeedProgramModule(traceback)) => {
-                            return Err(CompileError::NeedProgramModule(format!("at case argument\n{}", traceback)));
+                            return Err(trace(messages, "compile.at_case_argument", &[], traceback));
                         }
                         e => return e,
                     }
                 } else {
-                    return Err(CompileError::NeedProgramModule(String::from("at case argument")));
+                    return Err(CompileError::NeedProgramModule(messages.get("compile.at_case_argument")));
                 }
             } else {
                 return Err(CompileError::ModuleStructureError);
@@ -282,13 +481,14 @@ fn compile_inner(module: &ProgramModule<ProgramModuleType>) -> Result<String, Co
             let code = if let ProgramModuleChildItems::BlockVertical(list) = &module.child {
                 let mut code = String::new();
                 for (i, module) in list.iter().enumerate() {
-                    match compile_inner(module) {
+                    if module.disabled { continue; }
+                    match compile_inner(module, messages) {
                         Ok(s) => {
                             code.push_str(&s);
                             code.push('\n');
                         }
                         Err(CompileError::NeedProgramModule(traceback)) => {
-                            return Err(CompileError::NeedProgramModule(format!("at case block[{}]\n{}", i, traceback)));
+                            return Err(trace(messages, "compile.at_case_block", &[("index", &i.to_string())], traceback));
                         }
                         e => return e,
                     }
@@ -305,13 +505,14 @@ fn compile_inner(module: &ProgramModule<ProgramModuleType>) -> Result<String, Co
             let code = if let ProgramModuleChildItems::BlockVertical(list) = &module.child {
                 let mut code = String::new();
                 for (i, module) in list.iter().enumerate() {
-                    match compile_inner(module) {
+                    if module.disabled { continue; }
+                    match compile_inner(module, messages) {
                         Ok(s) => {
                             code.push_str(&s);
                             code.push('\n');
                         }
                         Err(CompileError::NeedProgramModule(traceback)) => {
-                            return Err(CompileError::NeedProgramModule(format!("at default block[{}]\n{}", i, traceback)));
+                            return Err(trace(messages, "compile.at_default_block", &[("index", &i.to_string())], traceback));
                         }
                         e => return e,
                     }
@@ -323,45 +524,45 @@ fn compile_inner(module: &ProgramModule<ProgramModuleType>) -> Result<String, Co
             let suffix = String::from("break;");
             Ok(format!("{}\n{}{}", prefix, code, suffix))
         }
-        ProgramModuleType::ValueAssign => match compile_binary_operator(&module, "=", "assignment") {
+        ProgramModuleType::ValueAssign => match compile_binary_operator(&module, "=", "operator.assignment", messages) {
             Ok(s) => { Ok(format!("{};", s)) }
             e => e
         },
-        ProgramModuleType::ValueAdd => compile_binary_operator(&module, "+", "add"),
-        ProgramModuleType::ValueSub => compile_binary_operator(&module, "-", "sub"),
-        ProgramModuleType::ValueMul => compile_binary_operator(&module, "*", "multiply"),
-        ProgramModuleType::ValueDiv => compile_binary_operator(&module, "/", "divide"),
-        ProgramModuleType::ValueRem => compile_binary_operator(&module, "%", "mod"),
+        ProgramModuleType::ValueAdd => compile_binary_operator(&module, "+", "operator.add", messages),
+        ProgramModuleType::ValueSub => compile_binary_operator(&module, "-", "operator.sub", messages),
+        ProgramModuleType::ValueMul => compile_binary_operator(&module, "*", "operator.multiply", messages),
+        ProgramModuleType::ValueDiv => compile_binary_operator(&module, "/", "operator.divide", messages),
+        ProgramModuleType::ValueRem => compile_binary_operator(&module, "%", "operator.mod", messages),
     }
 }
 
-fn compile_binary_operator(module: &&ProgramModule<ProgramModuleType>, operator: &str, name: &str) -> Result<String, CompileError> {
+fn compile_binary_operator(module: &&ProgramModule<ProgramModuleType>, operator: &str, name: &str, messages: &MessageCatalog) -> Result<String, CompileError> {
     let left = if let ProgramModuleOption::ProgramModule(inner_module) = &module.options[0] {
         if let Some(inner_module) = inner_module {
-            match compile_inner(&inner_module) {
+            match compile_inner(&inner_module, messages) {
                 Ok(s) => { s }
                 Err(CompileError::NeedProgramModule(traceback)) => {
-                    return Err(CompileError::NeedProgramModule(format!("at {} operator left argument\n{}", name, traceback)));
+                    return Err(trace(messages, "compile.at_left_argument", &[("operator", &messages.get(name))], traceback));
                 }
                 e => return e,
             }
         } else {
-            return Err(CompileError::NeedProgramModule(format!("at {} operator left argument", name)));
+            return Err(CompileError::NeedProgramModule(messages.format("compile.at_left_argument", &[("operator", &messages.get(name))])));
         }
     } else {
         return Err(CompileError::ModuleStructureError);
     };
     let right = if let ProgramModuleOption::ProgramModule(inner_module) = &module.options[2] {
         if let Some(inner_module) = inner_module {
-            match compile_inner(&inner_module) {
+            match compile_inner(&inner_module, messages) {
                 Ok(s) => { s }
                 Err(CompileError::NeedProgramModule(traceback)) => {
-                    return Err(CompileError::NeedProgramModule(format!("at {} operator right argument\n{}", name, traceback)));
+                    return Err(trace(messages, "compile.at_right_argument", &[("operator", &messages.get(name))], traceback));
                 }
                 e => return e,
             }
         } else {
-            return Err(CompileError::NeedProgramModule(format!("at {} operator right argument", name)));
+            return Err(CompileError::NeedProgramModule(messages.format("compile.at_right_argument", &[("operator", &messages.get(name))])));
         }
     } else {
         return Err(CompileError::ModuleStructureError);
@@ -0,0 +1,65 @@
+use dotevery_editor_lib::logic::dotevery_module::DotEveryModule;
+use dotevery_editor_lib::logic::editor_messages::editor_messages;
+use dotevery_editor_lib::logic::i18n::MessageCatalog;
+
+use crate::ProgramModuleType;
+
+pub const LOCALES: &[(&str, &str)] = &[("ja", "日本語"), ("en", "English")];
+
+// key, English, Japanese
+const MESSAGES: &[(&str, &str, &str)] = &[
+    ("sample.run", "Compile & Run", "コンパイルして実行"),
+    ("sample.export_svg", "Export SVG", "SVG で書き出し"),
+    ("sample.export_png", "Export PNG", "PNG で書き出し"),
+    ("sample.export_selection", "Export selection", "選択中のブロックを書き出し"),
+    ("sample.nothing_selected", "Select a block to export first.", "書き出すブロックを選択してください。"),
+    ("sample.variable_name", "Variable name", "変数名"),
+    ("sample.add", "Add", "追加"),
+    ("sample.remove", "Remove", "削除"),
+    ("sample.palette", "Palette (JSON/TOML)", "パレット (JSON/TOML)"),
+    ("sample.locale", "Language", "言語"),
+    ("sample.print_value", "Print this value", "この値を表示"),
+    ("help.print", "Prints the value in the output.", "値を出力に表示します。"),
+    ("help.string", "A text value.", "文字列の値です。"),
+    ("help.number", "A number value.", "数値です。"),
+    ("help.variable", "The value stored in the variable.", "変数に入っている値です。"),
+    ("help.switch", "Runs the case whose value equals the value of switch.", "switch の値と等しい case を実行します。"),
+    ("help.case", "Runs its blocks when the switch value equals this value.", "switch の値がこの値と等しいときにブロックを実行します。"),
+    ("help.default", "Runs its blocks when no case matches.", "どの case にも当てはまらないときにブロックを実行します。"),
+    ("help.assign", "Stores the right value in the variable on the left.", "右辺の値を左辺の変数に代入します。"),
+    ("help.operator", "Calculates a value from the left and right values.", "左辺と右辺の値から計算します。"),
+    ("compile.need_module", "NeedProgramModuleError\n{traceback}", "ブロックが足りません\n{traceback}"),
+    ("compile.structure", "ModuleStructureError", "ブロックの構造が正しくありません"),
+    ("compile.disabled", "the block is disabled", "ブロックが無効になっています"),
+    ("compile.at_block", "at block[{index}]", "ブロック[{index}]"),
+    ("compile.at_print_argument", "at print argument", "print の引数"),
+    ("compile.at_switch_argument", "at switch argument", "switch の引数"),
+    ("compile.at_switch_block", "at switch block[{index}]", "switch のブロック[{index}]"),
+    ("compile.at_case_argument", "at case argument", "case の引数"),
+    ("compile.at_case_block", "at case block[{index}]", "case のブロック[{index}]"),
+    ("compile.at_default_block", "at default block[{index}]", "default のブロック[{index}]"),
+    ("compile.at_left_argument", "at {operator} operator left argument", "{operator}演算子の左辺"),
+    ("compile.at_right_argument", "at {operator} operator right argument", "{operator}演算子の右辺"),
+    ("operator.assignment", "assignment", "代入"),
+    ("operator.add", "add", "加算"),
+    ("operator.sub", "sub", "減算"),
+    ("operator.multiply", "multiply", "乗算"),
+    ("operator.divide", "divide", "除算"),
+    ("operator.mod", "mod", "剰余"),
+    ("Statements", "Statements", "文"),
+    ("Literals", "Literals", "リテラル"),
+    ("Control", "Control", "制御"),
+    ("Operators", "Operators", "演算子"),
+    ("Variables", "Variables", "変数"),
+];
+
+pub fn messages(locale: &str) -> MessageCatalog {
+    let mut catalog = editor_messages();
+    for (key, en, ja) in MESSAGES {
+        catalog.insert("en", key, en);
+        catalog.insert("ja", key, ja);
+    }
+    ProgramModuleType::register_signs(&mut catalog);
+    catalog.set_locale(locale);
+    catalog
+}
@@ -1,143 +1,89 @@
 use enum_iterator::IntoEnumIterator;
 
-use dotevery_editor_lib::logic::program_module::{ProgramModule, ProgramModuleChildItems, ProgramModuleOption};
-
-#[derive(Clone, PartialEq)]
+use dotevery_editor_lib::components::program_module_renderer::ProgramModuleRenderer;
+use dotevery_editor_lib::logic::dotevery_module::DotEveryModule;
+use dotevery_editor_lib::logic::svg_export::{ModuleStyle, ProgramModuleShape};
+use dotevery_editor_lib::logic::template::FromTemplateTag;
+
+#[derive(Clone, PartialEq, DotEveryModule)]
+#[module_category(name = "Statements", color = "#4c97ff")]
+#[module_category(name = "Literals", color = "#9966ff")]
+#[module_category(name = "Control", color = "#ffab19")]
+#[module_category(name = "Operators", color = "#59c059")]
+#[module_category(name = "Variables", color = "#ff8c1a")]
 pub enum ProgramModuleType {
+    #[module(category = "Statements", sign(text = "print", locales(ja = "表示")), slot = "value")]
     Print,
+    #[module(category = "Literals", sign = "\"", input = "value", sign = "\"")]
     StringLiteral,
+    #[module(category = "Literals", sign(text = "数値", locales(en = "number")), input = "value")]
     NumberLiteral,
+    #[module(category = "Variables", field_sign)]
     Variable(String),
+    #[module(category = "Control", sign = "switch", slot = "value", child = "horizontal")]
     Switch,
+    #[module(category = "Control", sign = "case", slot = "value", sign = ":", child = "vertical")]
     Case,
+    #[module(category = "Control", sign = "default:", child = "vertical")]
     DefaultCase,
+    #[module(category = "Statements", slot = "left", sign = "=", slot = "right")]
     ValueAssign,
+    #[module(category = "Operators", slot = "left", sign = "＋", slot = "right")]
     ValueAdd,
+    #[module(category = "Operators", slot = "left", sign = "－", slot = "right")]
     ValueSub,
+    #[module(category = "Operators", slot = "left", sign = "×", slot = "right")]
     ValueMul,
+    #[module(category = "Operators", slot = "left", sign = "÷", slot = "right")]
     ValueDiv,
+    #[module(category = "Operators", slot = "left", sign(text = "mod", locales(ja = "余り")), slot = "right")]
     ValueRem,
 }
 
-fn create_module_print() -> ProgramModule<ProgramModuleType> {
-    ProgramModule::new(
-        vec![ProgramModuleOption::StringSign(String::from("print")), ProgramModuleOption::ProgramModule(None)],
-        ProgramModuleChildItems::None,
-        ProgramModuleType::Print,
-    )
-}
-
-fn create_module_string_literal() -> ProgramModule<ProgramModuleType> {
-    ProgramModule::new(
-        vec![ProgramModuleOption::StringSign(String::from("\"")), ProgramModuleOption::StringInput(String::new()), ProgramModuleOption::StringSign(String::from("\""))],
-        ProgramModuleChildItems::None,
-        ProgramModuleType::StringLiteral,
-    )
-}
-
-fn create_module_number_literal() -> ProgramModule<ProgramModuleType> {
-    ProgramModule::new(
-        vec![ProgramModuleOption::StringSign(String::from("数値")), ProgramModuleOption::StringInput(String::new())],
-        ProgramModuleChildItems::None,
-        ProgramModuleType::NumberLiteral,
-    )
-}
-
-fn create_module_variable(s: String) -> ProgramModule<ProgramModuleType> {
-    ProgramModule::new(
-        vec![ProgramModuleOption::StringSign(s.clone())],
-        ProgramModuleChildItems::None,
-        ProgramModuleType::Variable(s),
-    )
-}
-
-fn create_module_switch() -> ProgramModule<ProgramModuleType> {
-    ProgramModule::new(
-        vec![ProgramModuleOption::StringSign(String::from("switch")), ProgramModuleOption::ProgramModule(None)],
-        ProgramModuleChildItems::BlockHorizontal(Vec::new()),
-        ProgramModuleType::Switch,
-    )
-}
-
-fn create_module_case() -> ProgramModule<ProgramModuleType> {
-    ProgramModule::new(
-        vec![ProgramModuleOption::StringSign(String::from("case")), ProgramModuleOption::ProgramModule(None), ProgramModuleOption::StringSign(String::from(":"))],
-        ProgramModuleChildItems::BlockVertical(Vec::new()),
-        ProgramModuleType::Case,
-    )
-}
-
-fn create_module_default_case() -> ProgramModule<ProgramModuleType> {
-    ProgramModule::new(
-        vec![ProgramModuleOption::StringSign(String::from("default:"))],
-        ProgramModuleChildItems::BlockVertical(Vec::new()),
-        ProgramModuleType::DefaultCase,
-    )
-}
-
-fn create_module_value_assign() -> ProgramModule<ProgramModuleType> {
-    ProgramModule::new(
-        vec![ProgramModuleOption::ProgramModule(None), ProgramModuleOption::StringSign(String::from("=")), ProgramModuleOption::ProgramModule(None)],
-        ProgramModuleChildItems::None,
-        ProgramModuleType::ValueAssign,
-    )
-}
-
-fn create_module_value_add() -> ProgramModule<ProgramModuleType> {
-    ProgramModule::new(
-        vec![ProgramModuleOption::ProgramModule(None), ProgramModuleOption::StringSign(String::from("＋")), ProgramModuleOption::ProgramModule(None)],
-        ProgramModuleChildItems::None,
-        ProgramModuleType::ValueAdd,
-    )
+impl ProgramModuleType {
+    // a module that goes into a slot rather than a statement list
+    pub fn is_value(&self) -> bool {
+        !matches!(self, ProgramModuleType::Print | ProgramModuleType::ValueAssign | ProgramModuleType::Switch | ProgramModuleType::Case | ProgramModuleType::DefaultCase)
+    }
 }
 
-fn create_module_value_sub() -> ProgramModule<ProgramModuleType> {
-    ProgramModule::new(
-        vec![ProgramModuleOption::ProgramModule(None), ProgramModuleOption::StringSign(String::from("－")), ProgramModuleOption::ProgramModule(None)],
-        ProgramModuleChildItems::None,
-        ProgramModuleType::ValueSub,
-    )
-}
+impl ModuleStyle for ProgramModuleType {
+    fn color(&self) -> Option<String> {
+        self.category().map(|category| category.color.to_string())
+    }
 
-fn create_module_value_mul() -> ProgramModule<ProgramModuleType> {
-    ProgramModule::new(
-        vec![ProgramModuleOption::ProgramModule(None), ProgramModuleOption::StringSign(String::from("×")), ProgramModuleOption::ProgramModule(None)],
-        ProgramModuleChildItems::None,
-        ProgramModuleType::ValueMul,
-    )
+    fn shape(&self) -> ProgramModuleShape {
+        match self {
+            ProgramModuleType::Print | ProgramModuleType::ValueAssign | ProgramModuleType::Switch | ProgramModuleType::Case | ProgramModuleType::DefaultCase => ProgramModuleShape::Block,
+            _ => ProgramModuleShape::Rounded,
+        }
+    }
 }
 
-fn create_module_value_div() -> ProgramModule<ProgramModuleType> {
-    ProgramModule::new(
-        vec![ProgramModuleOption::ProgramModule(None), ProgramModuleOption::StringSign(String::from("÷")), ProgramModuleOption::ProgramModule(None)],
-        ProgramModuleChildItems::None,
-        ProgramModuleType::ValueDiv,
-    )
-}
+impl ProgramModuleRenderer for ProgramModuleType {
+    fn class(&self) -> Option<String> {
+        let class = match self {
+            ProgramModuleType::Print | ProgramModuleType::ValueAssign => "simple_js_statement",
+            ProgramModuleType::StringLiteral | ProgramModuleType::NumberLiteral => "simple_js_literal",
+            ProgramModuleType::Variable(_) => "simple_js_variable",
+            ProgramModuleType::Switch | ProgramModuleType::Case | ProgramModuleType::DefaultCase => "simple_js_control",
+            ProgramModuleType::ValueAdd | ProgramModuleType::ValueSub | ProgramModuleType::ValueMul | ProgramModuleType::ValueDiv | ProgramModuleType::ValueRem => "simple_js_operator",
+        };
+        Some(class.to_string())
+    }
 
-fn create_module_value_rem() -> ProgramModule<ProgramModuleType> {
-    ProgramModule::new(
-        vec![ProgramModuleOption::ProgramModule(None), ProgramModuleOption::StringSign(String::from("mod")), ProgramModuleOption::ProgramModule(None)],
-        ProgramModuleChildItems::None,
-        ProgramModuleType::ValueRem,
-    )
-}
+    fn label(&self) -> Option<String> {
+        self.category().map(|category| category.name.to_lowercase())
+    }
 
+    fn sign_tag(&self) -> Option<String> {
+        self.type_tag()
+    }
 
-pub fn create_module(t: ProgramModuleType) -> ProgramModule<ProgramModuleType> {
-    match t {
-        ProgramModuleType::Print => create_module_print(),
-        ProgramModuleType::StringLiteral => create_module_string_literal(),
-        ProgramModuleType::NumberLiteral => create_module_number_literal(),
-        ProgramModuleType::Variable(s) => create_module_variable(s),
-        ProgramModuleType::Switch => create_module_switch(),
-        ProgramModuleType::Case => create_module_case(),
-        ProgramModuleType::DefaultCase => create_module_default_case(),
-        ProgramModuleType::ValueAssign => create_module_value_assign(),
-        ProgramModuleType::ValueAdd => create_module_value_add(),
-        ProgramModuleType::ValueSub => create_module_value_sub(),
-        ProgramModuleType::ValueMul => create_module_value_mul(),
-        ProgramModuleType::ValueDiv => create_module_value_div(),
-        ProgramModuleType::ValueRem => create_module_value_rem(),
+    fn icon(&self) -> Option<String> {
+        match self {
+            ProgramModuleType::Switch | ProgramModuleType::Case | ProgramModuleType::DefaultCase => Some("⑂".to_string()),
+            _ => None,
+        }
     }
-}
\ No newline at end of file
+}